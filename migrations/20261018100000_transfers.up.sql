@@ -0,0 +1,6 @@
+-- Add up migration script here
+ALTER TABLE record
+ADD COLUMN transfer_account_id INTEGER NULL REFERENCES account (account_id);
+
+ALTER TABLE record
+ADD COLUMN transfer_amount INTEGER NULL;
@@ -0,0 +1,106 @@
+-- Add up migration script here
+ALTER TABLE user
+ADD COLUMN username TEXT;
+
+ALTER TABLE user
+ADD COLUMN created_at DATETIME;
+
+CREATE UNIQUE INDEX user_username ON user (username);
+
+-- data created before users existed is owned by a default user
+INSERT INTO
+  user (user_id, username, created_at)
+SELECT
+  1,
+  "default",
+  CURRENT_TIMESTAMP
+WHERE
+  EXISTS (SELECT 1 FROM account)
+  OR EXISTS (SELECT 1 FROM category)
+  OR EXISTS (SELECT 1 FROM record);
+
+ALTER TABLE account
+ADD COLUMN user_id INTEGER NULL REFERENCES user (user_id);
+
+UPDATE account
+SET
+  user_id = 1;
+
+-- category names become unique per user, category and record are rebuilt
+-- together so dropping the old tables never leaves dangling references
+CREATE TABLE category_new (
+  category_id INTEGER PRIMARY KEY,
+  user_id INTEGER NOT NULL REFERENCES user (user_id),
+  name TEXT NOT NULL,
+  parent_id INTEGER NULL REFERENCES category_new (category_id),
+  budget INTEGER NULL,
+  UNIQUE (user_id, name)
+);
+
+INSERT INTO
+  category_new (category_id, user_id, name, parent_id, budget)
+SELECT
+  category_id,
+  1,
+  name,
+  parent_id,
+  budget
+FROM
+  category;
+
+CREATE TABLE record_new (
+  record_id INTEGER PRIMARY KEY,
+  user_id INTEGER NOT NULL REFERENCES user (user_id),
+  account_id INTEGER NOT NULL REFERENCES account (account_id),
+  amount INTEGER NOT NULL,
+  description TEXT,
+  record_type INTEGER NOT NULL REFERENCES record_type (record_type_id),
+  category_id INTEGER NULL REFERENCES category_new (category_id),
+  transfer_account_id INTEGER NULL REFERENCES account (account_id),
+  transfer_amount INTEGER NULL,
+  created_at DATETIME NOT NULL,
+  updated_at DATETIME NOT NULL
+);
+
+INSERT INTO
+  record_new (
+    record_id,
+    user_id,
+    account_id,
+    amount,
+    description,
+    record_type,
+    category_id,
+    transfer_account_id,
+    transfer_amount,
+    created_at,
+    updated_at
+  )
+SELECT
+  record_id,
+  1,
+  account_id,
+  amount,
+  description,
+  record_type,
+  category_id,
+  transfer_account_id,
+  transfer_amount,
+  created_at,
+  updated_at
+FROM
+  record;
+
+DROP TABLE record;
+
+DROP TABLE category;
+
+ALTER TABLE category_new
+RENAME TO category;
+
+ALTER TABLE record_new
+RENAME TO record;
+
+CREATE INDEX account_user_id ON account (user_id);
+
+CREATE INDEX record_user_id ON record (user_id);
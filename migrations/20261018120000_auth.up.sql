@@ -0,0 +1,15 @@
+-- Add up migration script here
+ALTER TABLE user
+ADD COLUMN password_hash TEXT NULL;
+
+CREATE TABLE token (
+  token_id INTEGER PRIMARY KEY,
+  user_id INTEGER NOT NULL REFERENCES user (user_id),
+  token_hash TEXT UNIQUE NOT NULL,
+  kind TEXT NOT NULL,
+  name TEXT NULL,
+  created_at DATETIME NOT NULL,
+  expires_at DATETIME NULL
+);
+
+CREATE INDEX token_user_id ON token (user_id);
@@ -0,0 +1,25 @@
+-- Add up migration script here
+CREATE TABLE recurring_record (
+  recurring_record_id INTEGER PRIMARY KEY,
+  user_id INTEGER NOT NULL REFERENCES user (user_id),
+  account_id INTEGER NOT NULL REFERENCES account (account_id),
+  record_type INTEGER NOT NULL REFERENCES record_type (record_type_id),
+  amount INTEGER NOT NULL,
+  description TEXT NULL,
+  category_id INTEGER NULL REFERENCES category (category_id),
+  transfer_account_id INTEGER NULL REFERENCES account (account_id),
+  transfer_amount INTEGER NULL,
+  recurrence_unit TEXT NOT NULL,
+  recurrence_interval INTEGER NOT NULL,
+  recurrence_day_of_month INTEGER NULL,
+  starts_at DATETIME NOT NULL,
+  ends_at DATETIME NULL,
+  occurrence_count INTEGER NOT NULL DEFAULT 0,
+  next_occurrence_at DATETIME NULL,
+  created_at DATETIME NOT NULL,
+  updated_at DATETIME NOT NULL
+);
+
+CREATE INDEX recurring_record_user_id ON recurring_record (user_id);
+
+CREATE INDEX recurring_record_next_occurrence_at ON recurring_record (next_occurrence_at);
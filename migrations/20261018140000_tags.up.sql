@@ -0,0 +1,15 @@
+-- Add up migration script here
+CREATE TABLE tag (
+  tag_id INTEGER PRIMARY KEY,
+  user_id INTEGER NOT NULL REFERENCES user (user_id),
+  name TEXT NOT NULL,
+  UNIQUE (user_id, name)
+);
+
+CREATE TABLE record_tag (
+  record_id INTEGER NOT NULL REFERENCES record (record_id) ON DELETE CASCADE,
+  tag_id INTEGER NOT NULL REFERENCES tag (tag_id) ON DELETE CASCADE,
+  PRIMARY KEY (record_id, tag_id)
+);
+
+CREATE INDEX record_tag_tag_id ON record_tag (tag_id);
@@ -0,0 +1,12 @@
+-- Add up migration script here
+CREATE TABLE record_split (
+  record_split_id INTEGER PRIMARY KEY,
+  record_id INTEGER NOT NULL REFERENCES record (record_id) ON DELETE CASCADE,
+  category_id INTEGER NOT NULL REFERENCES category (category_id),
+  amount INTEGER NOT NULL,
+  note TEXT NULL
+);
+
+CREATE INDEX record_split_record_id ON record_split (record_id);
+
+CREATE INDEX record_split_category_id ON record_split (category_id);
@@ -0,0 +1,15 @@
+-- Add up migration script here
+ALTER TABLE record
+ADD COLUMN deleted_at TEXT NULL;
+
+ALTER TABLE account
+ADD COLUMN deleted_at TEXT NULL;
+
+ALTER TABLE category
+ADD COLUMN deleted_at TEXT NULL;
+
+CREATE INDEX record_deleted_at ON record (deleted_at);
+
+CREATE INDEX account_deleted_at ON account (deleted_at);
+
+CREATE INDEX category_deleted_at ON category (deleted_at);
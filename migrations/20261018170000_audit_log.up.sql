@@ -0,0 +1,16 @@
+-- Add up migration script here
+CREATE TABLE audit_log (
+  audit_log_id INTEGER PRIMARY KEY,
+  user_id INTEGER NOT NULL REFERENCES user (user_id),
+  actor_id INTEGER NULL REFERENCES user (user_id),
+  entity_type TEXT NOT NULL,
+  entity_id INTEGER NOT NULL,
+  action TEXT NOT NULL,
+  before TEXT NULL,
+  after TEXT NULL,
+  created_at DATETIME NOT NULL
+);
+
+CREATE INDEX audit_log_user_id_created_at ON audit_log (user_id, created_at);
+
+CREATE INDEX audit_log_entity ON audit_log (entity_type, entity_id);
@@ -0,0 +1,16 @@
+-- Add up migration script here
+ALTER TABLE account
+ADD COLUMN currency TEXT NOT NULL DEFAULT 'USD';
+
+ALTER TABLE user
+ADD COLUMN reporting_currency TEXT NOT NULL DEFAULT 'USD';
+
+CREATE TABLE exchange_rate (
+  exchange_rate_id INTEGER PRIMARY KEY,
+  user_id INTEGER NOT NULL REFERENCES user (user_id),
+  base_currency TEXT NOT NULL,
+  quote_currency TEXT NOT NULL,
+  rate REAL NOT NULL,
+  valid_on TEXT NOT NULL,
+  UNIQUE (user_id, base_currency, quote_currency, valid_on)
+);
@@ -0,0 +1,12 @@
+-- Add up migration script here
+ALTER TABLE record
+ADD COLUMN occurred_at DATETIME NULL;
+
+ALTER TABLE record
+ADD COLUMN posted_at DATETIME NULL;
+
+UPDATE record
+SET
+  occurred_at = created_at;
+
+CREATE INDEX record_occurred_at ON record (occurred_at);
@@ -0,0 +1,363 @@
+use std::{
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+use clap::Parser;
+use serde::Deserialize;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode};
+use thiserror::Error;
+use tracing::Level;
+
+/// Longest trash retention, a hundred years keeps the purge cutoff far from
+/// the limits of the date types.
+const MAX_TRASH_RETENTION_DAYS: u64 = 36_500;
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("cannot read config file {path}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("cannot parse config file {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    #[error("database.path must not be empty")]
+    EmptyDatabasePath,
+    #[error("database.max_connections must be greater than zero")]
+    InvalidPoolSize,
+    #[error("scheduler.interval_secs must be greater than zero")]
+    InvalidSchedulerInterval,
+    #[error("trash.retention_days must be between 1 and {MAX_TRASH_RETENTION_DAYS}")]
+    InvalidTrashRetention,
+    #[error("log.level \"{0}\" is invalid, expected one of trace, debug, info, warn, error")]
+    InvalidLogLevel(String),
+}
+
+/// Command line flags, every flag can also be set with an environment variable
+/// and overrides the value from the config file.
+#[derive(Parser, Debug, Default)]
+#[command(version, about)]
+pub struct Args {
+    /// Path to a TOML config file
+    #[arg(short, long, env = "BUDGET_CONFIG")]
+    pub config: Option<PathBuf>,
+    /// Path to the SQLite database file
+    #[arg(long, env = "BUDGET_DATABASE_PATH")]
+    pub database_path: Option<String>,
+    /// Create the database file if it does not exist
+    #[arg(long, env = "BUDGET_DATABASE_CREATE_IF_MISSING")]
+    pub database_create_if_missing: Option<bool>,
+    /// Use write-ahead logging journal mode
+    #[arg(long, env = "BUDGET_DATABASE_WAL")]
+    pub database_wal: Option<bool>,
+    /// How long to wait for a locked database, in milliseconds
+    #[arg(long, env = "BUDGET_DATABASE_BUSY_TIMEOUT_MS")]
+    pub database_busy_timeout_ms: Option<u64>,
+    /// Maximum number of pooled database connections
+    #[arg(long, env = "BUDGET_DATABASE_MAX_CONNECTIONS")]
+    pub database_max_connections: Option<u32>,
+    /// Address the HTTP server listens on
+    #[arg(long, env = "BUDGET_BIND")]
+    pub bind: Option<SocketAddr>,
+    /// Create records from recurring records in the background
+    #[arg(long, env = "BUDGET_SCHEDULER_ENABLED")]
+    pub scheduler_enabled: Option<bool>,
+    /// How often the scheduler looks for due recurring records, in seconds
+    #[arg(long, env = "BUDGET_SCHEDULER_INTERVAL_SECS")]
+    pub scheduler_interval_secs: Option<u64>,
+    /// How long deleted items stay in the trash before they are purged, in days
+    #[arg(long, env = "BUDGET_TRASH_RETENTION_DAYS")]
+    pub trash_retention_days: Option<u64>,
+    /// Log level: trace, debug, info, warn or error
+    #[arg(long, env = "BUDGET_LOG_LEVEL")]
+    pub log_level: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub database: DatabaseConfig,
+    pub server: ServerConfig,
+    pub scheduler: SchedulerConfig,
+    pub trash: TrashConfig,
+    pub log: LogConfig,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseConfig {
+    pub path: String,
+    pub create_if_missing: bool,
+    pub wal: bool,
+    pub busy_timeout_ms: u64,
+    pub max_connections: u32,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            path: "budget.db".into(),
+            create_if_missing: true,
+            wal: true,
+            busy_timeout_ms: 5000,
+            max_connections: 5,
+        }
+    }
+}
+
+impl DatabaseConfig {
+    pub fn connect_options(&self) -> SqliteConnectOptions {
+        let journal_mode = if self.wal {
+            SqliteJournalMode::Wal
+        } else {
+            SqliteJournalMode::Delete
+        };
+
+        SqliteConnectOptions::new()
+            .filename(&self.path)
+            .create_if_missing(self.create_if_missing)
+            .journal_mode(journal_mode)
+            .busy_timeout(Duration::from_millis(self.busy_timeout_ms))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub bind: SocketAddr,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            bind: SocketAddr::from(([0, 0, 0, 0], 4000)),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SchedulerConfig {
+    pub enabled: bool,
+    pub interval_secs: u64,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 60,
+        }
+    }
+}
+
+impl SchedulerConfig {
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TrashConfig {
+    pub retention_days: u64,
+}
+
+impl Default for TrashConfig {
+    fn default() -> Self {
+        Self { retention_days: 30 }
+    }
+}
+
+impl TrashConfig {
+    pub fn retention(&self) -> Duration {
+        Duration::from_secs(self.retention_days * 24 * 60 * 60)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogConfig {
+    pub level: String,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            level: "info".into(),
+        }
+    }
+}
+
+impl LogConfig {
+    pub fn level(&self) -> Result<Level, ConfigError> {
+        Level::from_str(&self.level).map_err(|_| ConfigError::InvalidLogLevel(self.level.clone()))
+    }
+}
+
+impl Config {
+    /// Loads the config from command line flags, environment variables and
+    /// the config file they point to.
+    pub fn load() -> Result<Self, ConfigError> {
+        Self::from_args(Args::parse())
+    }
+
+    pub fn from_args(args: Args) -> Result<Self, ConfigError> {
+        let mut config = match &args.config {
+            Some(path) => Self::from_file(path)?,
+            None => Self::default(),
+        };
+
+        config.apply_args(args);
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let content = std::fs::read_to_string(path).map_err(|source| ConfigError::Read {
+            path: path.to_owned(),
+            source,
+        })?;
+
+        toml::from_str(&content).map_err(|source| ConfigError::Parse {
+            path: path.to_owned(),
+            source,
+        })
+    }
+
+    fn apply_args(&mut self, args: Args) {
+        if let Some(path) = args.database_path {
+            self.database.path = path;
+        }
+        if let Some(create_if_missing) = args.database_create_if_missing {
+            self.database.create_if_missing = create_if_missing;
+        }
+        if let Some(wal) = args.database_wal {
+            self.database.wal = wal;
+        }
+        if let Some(busy_timeout_ms) = args.database_busy_timeout_ms {
+            self.database.busy_timeout_ms = busy_timeout_ms;
+        }
+        if let Some(max_connections) = args.database_max_connections {
+            self.database.max_connections = max_connections;
+        }
+        if let Some(bind) = args.bind {
+            self.server.bind = bind;
+        }
+        if let Some(enabled) = args.scheduler_enabled {
+            self.scheduler.enabled = enabled;
+        }
+        if let Some(interval_secs) = args.scheduler_interval_secs {
+            self.scheduler.interval_secs = interval_secs;
+        }
+        if let Some(retention_days) = args.trash_retention_days {
+            self.trash.retention_days = retention_days;
+        }
+        if let Some(level) = args.log_level {
+            self.log.level = level;
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.database.path.trim().is_empty() {
+            return Err(ConfigError::EmptyDatabasePath);
+        }
+
+        if self.database.max_connections == 0 {
+            return Err(ConfigError::InvalidPoolSize);
+        }
+
+        if self.scheduler.interval_secs == 0 {
+            return Err(ConfigError::InvalidSchedulerInterval);
+        }
+
+        if !(1..=MAX_TRASH_RETENTION_DAYS).contains(&self.trash.retention_days) {
+            return Err(ConfigError::InvalidTrashRetention);
+        }
+
+        self.log.level()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_config_file() {
+        let config: Config = toml::from_str(
+            r#"
+            [database]
+            path = "/var/lib/budget/budget.db"
+            wal = false
+
+            [server]
+            bind = "127.0.0.1:8080"
+            "#,
+        )
+        .expect("must parse config");
+
+        assert_eq!(config.database.path, "/var/lib/budget/budget.db");
+        assert!(!config.database.wal);
+        assert!(config.database.create_if_missing);
+        assert_eq!(config.server.bind, SocketAddr::from(([127, 0, 0, 1], 8080)));
+        assert_eq!(config.log.level, "info");
+    }
+
+    #[test]
+    fn test_args_override_defaults() {
+        let config = Config::from_args(Args {
+            database_path: Some("test.db".into()),
+            database_max_connections: Some(1),
+            log_level: Some("debug".into()),
+            ..Default::default()
+        })
+        .expect("must build config");
+
+        assert_eq!(config.database.path, "test.db");
+        assert_eq!(config.database.max_connections, 1);
+        assert_eq!(config.log.level().unwrap(), Level::DEBUG);
+    }
+
+    #[test]
+    fn test_validate() {
+        let result = Config::from_args(Args {
+            database_max_connections: Some(0),
+            ..Default::default()
+        });
+        assert!(matches!(result, Err(ConfigError::InvalidPoolSize)));
+
+        let result = Config::from_args(Args {
+            log_level: Some("verbose".into()),
+            ..Default::default()
+        });
+        assert!(matches!(result, Err(ConfigError::InvalidLogLevel(_))));
+
+        let result = Config::from_args(Args {
+            scheduler_interval_secs: Some(0),
+            ..Default::default()
+        });
+        assert!(matches!(result, Err(ConfigError::InvalidSchedulerInterval)));
+
+        let result = Config::from_args(Args {
+            trash_retention_days: Some(0),
+            ..Default::default()
+        });
+        assert!(matches!(result, Err(ConfigError::InvalidTrashRetention)));
+
+        let result = Config::from_args(Args {
+            trash_retention_days: Some(u64::MAX / (24 * 60 * 60)),
+            ..Default::default()
+        });
+        assert!(matches!(result, Err(ConfigError::InvalidTrashRetention)));
+    }
+}
@@ -8,10 +8,59 @@ pub enum BudgetServiceError {
     RecordValidationError(#[from] models::RecordError),
     #[error("cagegory validation error: {0}")]
     CategoryValidationError(#[from] models::CategoryError),
+    #[error("tag validation error: {0}")]
+    TagValidationError(#[from] models::TagError),
     #[error("account validation error: {0}")]
     AccountValidationError(#[from] models::AccountError),
+    #[error("import validation error: {0}")]
+    ImportValidationError(#[from] models::ImportError),
+    #[error("recurrence validation error: {0}")]
+    RecurrenceValidationError(#[from] models::RecurrenceError),
+    #[error("amount validation error: {0}")]
+    MoneyValidationError(#[from] models::MoneyError),
+    #[error("currency validation error: {0}")]
+    CurrencyValidationError(#[from] models::CurrencyError),
+    #[error("report validation error: {0}")]
+    ReportValidationError(#[from] models::ReportError),
+    #[error("delete policy validation error: {0}")]
+    DeletePolicyValidationError(#[from] models::DeletePolicyError),
+    #[error("user validation error: {0}")]
+    UserValidationError(#[from] models::UserError),
+    #[error("page validation error: {0}")]
+    PageValidationError(#[from] models::PageError),
+    /// Built by the repository, which translates constraint violations.
     #[error("database error: {0}")]
-    DatabaseError(#[from] sqlx::Error),
-    #[error("{0} not found")]
-    EntityNotFoundError(String),
+    DatabaseError(#[source] sqlx::Error),
+    #[error("transaction error: {0}")]
+    TransactionError(&'static str),
+    #[error("unauthorized: {0}")]
+    Unauthorized(&'static str),
+    #[error("{entity} {id} not found")]
+    EntityNotFoundError { entity: &'static str, id: i64 },
+    #[error("{0}")]
+    ConflictError(String),
+    #[error("{0}")]
+    InvalidReferenceError(String),
+}
+
+impl BudgetServiceError {
+    /// Request fields a validation error is about, with the message for each,
+    /// empty when the error is not tied to particular fields.
+    pub fn field_errors(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Self::RecordValidationError(e) => e.field_errors(),
+            Self::CategoryValidationError(e) => e.field_errors(),
+            Self::TagValidationError(e) => e.field_errors(),
+            Self::AccountValidationError(e) => e.field_errors(),
+            Self::RecurrenceValidationError(e) => e.field_errors(),
+            Self::UserValidationError(e) => e.field_errors(),
+            Self::PageValidationError(e) => e.field_errors(),
+            Self::ImportValidationError(e) => e.field_errors(),
+            Self::MoneyValidationError(e) => e.field_errors(),
+            Self::CurrencyValidationError(e) => e.field_errors(),
+            Self::ReportValidationError(e) => e.field_errors(),
+            Self::DeletePolicyValidationError(e) => e.field_errors(),
+            _ => Vec::new(),
+        }
+    }
 }
@@ -1,5 +1,11 @@
-use std::{num::NonZeroI64, str::FromStr};
+use std::{
+    collections::{HashMap, HashSet},
+    num::NonZeroI64,
+    str::FromStr,
+    time::Duration,
+};
 
+use ::chrono::{Datelike, Days, Months};
 use sqlx::types::chrono::{self, Local};
 use strum::EnumString;
 use thiserror::Error;
@@ -17,6 +23,33 @@ const MAX_CATEGORY_NAME_LENGTH: usize = 100;
 pub enum CategoryError {
     #[error("category name must not be empty or longer than 100 characters")]
     InvalidCategoryName,
+    #[error("invalid category id \"{0}\"")]
+    InvalidCategoryId(String),
+    #[error("parent category {0} does not exist")]
+    ParentNotFound(i64),
+    #[error("category cannot be moved under itself or one of its descendants")]
+    CategoryCycle,
+    #[error("category budget cannot be negative")]
+    NegativeBudget,
+    #[error(transparent)]
+    InvalidBudget(MoneyError),
+    #[error("{}", join_errors(.0))]
+    Multiple(Vec<CategoryError>),
+}
+
+impl CategoryError {
+    /// Request fields the error is about, with the message for each.
+    pub fn field_errors(&self) -> Vec<(&'static str, String)> {
+        let field = match self {
+            Self::Multiple(errors) => return errors.iter().flat_map(Self::field_errors).collect(),
+            Self::InvalidCategoryName => "name",
+            Self::NegativeBudget | Self::InvalidBudget(_) => "budget",
+            Self::InvalidCategoryId(_) => "category_ids",
+            Self::ParentNotFound(_) | Self::CategoryCycle => "parent_id",
+        };
+
+        vec![(field, self.to_string())]
+    }
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
@@ -33,25 +66,256 @@ impl Category {
         budget: Option<i64>,
         parent_id: Option<i64>,
     ) -> Result<Self, CategoryError> {
-        if name.chars().count() == 0 || name.chars().count() > MAX_CATEGORY_NAME_LENGTH {
-            return Err(CategoryError::InvalidCategoryName);
+        let mut category = Self {
+            id: 0,
+            name: String::new(),
+            budget: None,
+            parent_id,
+        };
+        category.update(name, budget)?;
+
+        Ok(category)
+    }
+
+    /// Replaces the name and budget, reporting every invalid one.
+    pub fn update(&mut self, name: String, budget: Option<i64>) -> Result<(), CategoryError> {
+        let name_error = (name.chars().count() == 0
+            || name.chars().count() > MAX_CATEGORY_NAME_LENGTH)
+            .then_some(CategoryError::InvalidCategoryName);
+        let budget_error = budget
+            .is_some_and(|budget| budget < 0)
+            .then_some(CategoryError::NegativeBudget);
+        if name_error.is_some() || budget_error.is_some() {
+            return Err(combine([name_error, budget_error], CategoryError::Multiple));
         }
 
-        Ok(Self {
+        self.name = name;
+        self.budget = budget;
+
+        Ok(())
+    }
+}
+
+/// Turns the failures of independent checks into one error, wrapping them
+/// with `multiple` when more than one check failed.
+fn combine<E>(errors: impl IntoIterator<Item = Option<E>>, multiple: fn(Vec<E>) -> E) -> E {
+    let mut errors: Vec<E> = errors.into_iter().flatten().collect();
+    if errors.len() == 1 {
+        errors.remove(0)
+    } else {
+        multiple(errors)
+    }
+}
+
+fn join_errors<E: std::fmt::Display>(errors: &[E]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Category with its descendants.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CategoryTree {
+    pub category: Category,
+    pub children: Vec<CategoryTree>,
+}
+
+impl CategoryTree {
+    /// Arranges categories into trees, categories whose parent is not in the
+    /// list become roots. Siblings are ordered by name.
+    pub fn build(categories: Vec<Category>) -> Vec<Self> {
+        let ids: HashSet<i64> = categories.iter().map(|c| c.id).collect();
+        let mut children: HashMap<Option<i64>, Vec<Category>> = HashMap::new();
+        for category in categories {
+            let parent_id = category.parent_id.filter(|id| ids.contains(id));
+            children.entry(parent_id).or_default().push(category);
+        }
+
+        Self::attach(None, &mut children)
+    }
+
+    /// Returns the tree rooted at the category with `id`.
+    pub fn subtree(categories: Vec<Category>, id: i64) -> Option<Self> {
+        let mut stack = Self::build(categories);
+        while let Some(tree) = stack.pop() {
+            if tree.category.id == id {
+                return Some(tree);
+            }
+            stack.extend(tree.children);
+        }
+
+        None
+    }
+
+    fn attach(
+        parent_id: Option<i64>,
+        children: &mut HashMap<Option<i64>, Vec<Category>>,
+    ) -> Vec<Self> {
+        // Removing the entry before recursing ends cycles, categories in a
+        // cycle are never reached from a root.
+        let mut categories = children.remove(&parent_id).unwrap_or_default();
+        categories.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+
+        categories
+            .into_iter()
+            .map(|category| {
+                let children = Self::attach(Some(category.id), children);
+                Self { category, children }
+            })
+            .collect()
+    }
+}
+
+const MAX_TAG_NAME_LENGTH: usize = 50;
+
+#[derive(Debug, Error)]
+pub enum TagError {
+    #[error("tag name must not be empty or longer than 50 characters")]
+    InvalidTagName,
+    #[error("tag \"{0}\" already exists")]
+    DuplicateTagName(String),
+    #[error("tag cannot be merged into itself")]
+    MergeIntoItself,
+    #[error("invalid tag id \"{0}\"")]
+    InvalidTagId(String),
+}
+
+impl TagError {
+    /// Request fields the error is about, with the message for each.
+    pub fn field_errors(&self) -> Vec<(&'static str, String)> {
+        let field = match self {
+            Self::InvalidTagName | Self::DuplicateTagName(_) => "name",
+            Self::MergeIntoItself => "target_id",
+            Self::InvalidTagId(_) => "tag_ids",
+        };
+
+        vec![(field, self.to_string())]
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tag {
+    pub id: i64,
+    pub name: String,
+}
+
+impl Tag {
+    pub fn new(name: String) -> Result<Self, TagError> {
+        let mut tag = Self {
             id: 0,
-            name,
-            budget,
-            parent_id,
-        })
+            name: String::new(),
+        };
+        tag.set_name(name)?;
+
+        Ok(tag)
+    }
+
+    pub fn set_name(&mut self, name: String) -> Result<(), TagError> {
+        let name = name.trim().to_owned();
+        if name.is_empty() || name.chars().count() > MAX_TAG_NAME_LENGTH {
+            return Err(TagError::InvalidTagName);
+        }
+
+        self.name = name;
+
+        Ok(())
     }
 }
 
+/// Outcome total of all records with a tag in a period.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TagSpending {
+    pub tag: Tag,
+    pub spent: i64,
+}
+
 #[derive(Debug, Error)]
 pub enum RecordError {
     #[error("amount cannot be equal or less than zero")]
     AmountCannotBeLessOrEqualToZero,
     #[error("invalid record type \"{0}\"")]
     InvalidRecordType(String),
+    #[error("transfer requires a destination account")]
+    TransferDestinationRequired,
+    #[error("transfer source and destination accounts must differ")]
+    TransferToSameAccount,
+    #[error("only transfers can have a destination account or received amount")]
+    UnexpectedTransferDestination,
+    #[error("transfers cannot be split")]
+    SplitTransfer,
+    #[error("split records take their categories from the split lines")]
+    SplitWithCategory,
+    #[error("split lines add up to {actual} instead of the record amount {expected}")]
+    SplitAmountMismatch { expected: i64, actual: i64 },
+    #[error("transfers between accounts in {from} and {to} need a received amount")]
+    ReceivedAmountRequired { from: Currency, to: Currency },
+    #[error("a transaction cannot be posted before it took place")]
+    PostedBeforeOccurred,
+    #[error("received amount cannot be equal or less than zero")]
+    InvalidReceivedAmount,
+    #[error(transparent)]
+    InvalidAmount(#[from] MoneyError),
+    #[error("{}", join_errors(.0))]
+    Multiple(Vec<RecordError>),
+}
+
+impl RecordError {
+    /// Request fields the error is about, with the message for each.
+    pub fn field_errors(&self) -> Vec<(&'static str, String)> {
+        let field = match self {
+            Self::Multiple(errors) => return errors.iter().flat_map(Self::field_errors).collect(),
+            Self::AmountCannotBeLessOrEqualToZero | Self::InvalidAmount(_) => "amount",
+            Self::InvalidRecordType(_) => "transaction_type",
+            Self::TransferDestinationRequired
+            | Self::TransferToSameAccount
+            | Self::UnexpectedTransferDestination => "destination_account_id",
+            Self::SplitTransfer | Self::SplitWithCategory | Self::SplitAmountMismatch { .. } => {
+                "splits"
+            }
+            Self::ReceivedAmountRequired { .. } | Self::InvalidReceivedAmount => "received_amount",
+            Self::PostedBeforeOccurred => "posted_at",
+        };
+
+        vec![(field, self.to_string())]
+    }
+}
+
+/// Receiving side of a transfer record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transfer {
+    pub account_id: i64,
+    /// Amount credited to the destination account, differs from the record
+    /// amount when the accounts use different currencies.
+    pub amount: NonZeroI64,
+    /// Currency of the destination account.
+    pub currency: Currency,
+}
+
+/// Part of a split record's amount assigned to a category.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordSplit {
+    pub id: i64,
+    pub category: Category,
+    pub amount: NonZeroI64,
+    pub note: Option<String>,
+}
+
+impl RecordSplit {
+    pub fn new(category: Category, amount: i64, note: Option<String>) -> Result<Self, RecordError> {
+        if amount <= 0 {
+            return Err(RecordError::AmountCannotBeLessOrEqualToZero);
+        }
+
+        Ok(Self {
+            id: 0,
+            category,
+            amount: NonZeroI64::try_from(amount)
+                .map_err(|_| RecordError::AmountCannotBeLessOrEqualToZero)?,
+            note,
+        })
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -60,8 +324,19 @@ pub struct Record {
     pub account_id: i64,
     pub record_type: RecordType,
     pub amount: NonZeroI64,
+    /// Currency of the account, split lines use it as well.
+    pub currency: Currency,
     pub description: Option<String>,
     pub category: Option<Category>,
+    /// Lines dividing the amount between categories, a record with splits
+    /// has no category of its own.
+    pub splits: Vec<RecordSplit>,
+    pub tags: Vec<Tag>,
+    pub transfer: Option<Transfer>,
+    /// When the transaction took place, lists, filters and reports use it.
+    pub occurred_at: chrono::DateTime<Local>,
+    /// When the transaction was posted to the account, if known.
+    pub posted_at: Option<chrono::DateTime<Local>>,
     pub created_at: chrono::DateTime<Local>,
     pub updated_at: chrono::DateTime<Local>,
 }
@@ -70,65 +345,1793 @@ impl Record {
     pub fn new(
         account_id: i64,
         record_type: String,
-        amount: i64,
+        amount: Money,
         category: Option<Category>,
         description: Option<String>,
     ) -> Result<Self, RecordError> {
-        let transaction_type = RecordType::from_str(&record_type)
-            .map_err(|_| RecordError::InvalidRecordType(record_type))?;
-
-        let amount = NonZeroI64::try_from(amount)
-            .map_err(|_| RecordError::AmountCannotBeLessOrEqualToZero)?;
+        let transaction_type = match RecordType::from_str(&record_type) {
+            Ok(RecordType::Transfer) => Err(RecordError::TransferDestinationRequired),
+            Ok(transaction_type) => Ok(transaction_type),
+            Err(_) => Err(RecordError::InvalidRecordType(record_type)),
+        };
+        let (transaction_type, (amount, currency)) = match (transaction_type, positive(amount)) {
+            (Ok(transaction_type), Ok(amount)) => (transaction_type, amount),
+            (transaction_type, amount) => {
+                return Err(combine(
+                    [transaction_type.err(), amount.err()],
+                    RecordError::Multiple,
+                ));
+            }
+        };
 
         Ok(Self {
             id: 0,
             account_id,
             record_type: transaction_type,
             amount,
+            currency,
             category,
+            splits: Vec::new(),
+            tags: Vec::new(),
+            transfer: None,
             description,
+            occurred_at: Local::now(),
+            posted_at: None,
             created_at: Local::now(),
             updated_at: Local::now(),
         })
     }
 
-    pub fn set_amount(&mut self, new_amount: i64) -> Result<(), RecordError> {
-        self.amount = NonZeroI64::try_from(new_amount)
-            .map_err(|_| RecordError::AmountCannotBeLessOrEqualToZero)?;
+    pub fn new_transfer(
+        account_id: i64,
+        destination_account_id: i64,
+        amount: Money,
+        received_amount: Option<Money>,
+        category: Option<Category>,
+        description: Option<String>,
+    ) -> Result<Self, RecordError> {
+        let same_account =
+            (destination_account_id == account_id).then_some(RecordError::TransferToSameAccount);
+        let received_error = received_amount
+            .as_ref()
+            .is_some_and(|received| received.minor() <= 0)
+            .then_some(RecordError::InvalidReceivedAmount);
+        let (amount, currency) = match positive(amount) {
+            Ok(amount) if same_account.is_none() && received_error.is_none() => amount,
+            amount => {
+                return Err(combine(
+                    [amount.err(), same_account, received_error],
+                    RecordError::Multiple,
+                ));
+            }
+        };
+
+        let mut record = Self {
+            id: 0,
+            account_id,
+            record_type: RecordType::Transfer,
+            amount,
+            currency,
+            category,
+            splits: Vec::new(),
+            tags: Vec::new(),
+            transfer: None,
+            description,
+            occurred_at: Local::now(),
+            posted_at: None,
+            created_at: Local::now(),
+            updated_at: Local::now(),
+        };
+        record.set_transfer(destination_account_id, received_amount)?;
+
+        Ok(record)
+    }
+
+    /// Sets when the transaction took place and when it was posted, a
+    /// transaction cannot be posted before it took place.
+    pub fn set_dates(
+        &mut self,
+        occurred_at: chrono::DateTime<Local>,
+        posted_at: Option<chrono::DateTime<Local>>,
+    ) -> Result<(), RecordError> {
+        if posted_at.is_some_and(|posted_at| posted_at < occurred_at) {
+            return Err(RecordError::PostedBeforeOccurred);
+        }
+        self.occurred_at = occurred_at;
+        self.posted_at = posted_at;
+
+        Ok(())
+    }
+
+    /// Sets the amount, which must be positive and in the currency of the
+    /// record, like the amount a record is created with.
+    pub fn set_amount(&mut self, amount: Money) -> Result<(), RecordError> {
+        let (amount, currency) = positive(amount)?;
+        if currency != self.currency {
+            return Err(MoneyError::CurrencyMismatch(currency, self.currency.clone()).into());
+        }
+        self.amount = amount;
+
+        Ok(())
+    }
+
+    /// Replaces the split lines, they must add up to the record amount. An
+    /// empty list turns the record back into a regular one.
+    pub fn set_splits(&mut self, splits: Vec<RecordSplit>) -> Result<(), RecordError> {
+        if !splits.is_empty() {
+            if self.record_type == RecordType::Transfer {
+                return Err(RecordError::SplitTransfer);
+            }
+            if self.category.is_some() {
+                return Err(RecordError::SplitWithCategory);
+            }
+
+            let expected: i64 = self.amount.into();
+            let actual = splits
+                .iter()
+                .try_fold(Money::zero(self.currency.clone()), |sum, split| {
+                    sum.checked_add(&Money::new(split.amount.get(), self.currency.clone()))
+                })?
+                .minor();
+            if actual != expected {
+                return Err(RecordError::SplitAmountMismatch { expected, actual });
+            }
+        }
+
+        self.splits = splits;
+
+        Ok(())
+    }
+
+    /// Sets the receiving side of a transfer, `received_amount` defaults to
+    /// the record amount and is in the currency of the destination account.
+    pub fn set_transfer(
+        &mut self,
+        destination_account_id: i64,
+        received_amount: Option<Money>,
+    ) -> Result<(), RecordError> {
+        if self.record_type != RecordType::Transfer {
+            return Err(RecordError::UnexpectedTransferDestination);
+        }
+
+        if destination_account_id == self.account_id {
+            return Err(RecordError::TransferToSameAccount);
+        }
+
+        let (amount, currency) = match received_amount {
+            Some(amount) => positive(amount).map_err(|_| RecordError::InvalidReceivedAmount)?,
+            None => (self.amount, self.currency.clone()),
+        };
+
+        self.transfer = Some(Transfer {
+            account_id: destination_account_id,
+            amount,
+            currency,
+        });
+
+        Ok(())
+    }
+
+    /// Moves the record, including the receiving side of a transfer, from one
+    /// account to another.
+    pub fn replace_account(
+        &mut self,
+        account_id: i64,
+        new_account_id: i64,
+    ) -> Result<(), RecordError> {
+        if self.account_id == account_id {
+            self.account_id = new_account_id;
+        }
+        if let Some(transfer) = self
+            .transfer
+            .as_mut()
+            .filter(|t| t.account_id == account_id)
+        {
+            transfer.account_id = new_account_id;
+        }
+        if self
+            .transfer
+            .as_ref()
+            .is_some_and(|t| t.account_id == self.account_id)
+        {
+            return Err(RecordError::TransferToSameAccount);
+        }
 
         Ok(())
     }
+
+    /// Moves the record and its split lines from one category to another.
+    pub fn replace_category(&mut self, category_id: i64, new_category: &Category) {
+        if self.category.as_ref().is_some_and(|c| c.id == category_id) {
+            self.category = Some(new_category.clone());
+        }
+        for split in self
+            .splits
+            .iter_mut()
+            .filter(|s| s.category.id == category_id)
+        {
+            split.category = new_category.clone();
+        }
+    }
+
+    /// Returns the balance changes this record applies to accounts as
+    /// `(account_id, delta)` pairs.
+    pub fn balance_changes(&self) -> Vec<(i64, i64)> {
+        let amount: i64 = self.amount.into();
+        match self.record_type {
+            RecordType::Income => vec![(self.account_id, amount)],
+            RecordType::Outcome => vec![(self.account_id, -amount)],
+            RecordType::Transfer => {
+                let mut changes = vec![(self.account_id, -amount)];
+                if let Some(transfer) = &self.transfer {
+                    changes.push((transfer.account_id, transfer.amount.into()));
+                }
+                changes
+            }
+        }
+    }
 }
 
-#[derive(Error, Debug)]
-pub enum AccountError {
-    #[error("unknown account type")]
-    UnknownAcountType,
+/// Splits a record amount into its minor units and currency, failing when it
+/// is not positive.
+fn positive(amount: Money) -> Result<(NonZeroI64, Currency), RecordError> {
+    if amount.minor() <= 0 {
+        return Err(RecordError::AmountCannotBeLessOrEqualToZero);
+    }
+    let minor = NonZeroI64::try_from(amount.minor())
+        .map_err(|_| RecordError::AmountCannotBeLessOrEqualToZero)?;
+
+    Ok((minor, amount.currency))
 }
 
-#[derive(Debug, EnumString, Clone, strum_macros::Display, PartialEq, Eq)]
-pub enum AccountType {
-    Cash,
-    DebitCard,
-    CreditCard,
+#[derive(Debug, Error)]
+pub enum ReportError {
+    #[error("report period must start before it ends")]
+    InvalidPeriod,
+    #[error("no exchange rate from {from} to {to} on or before {date}")]
+    MissingExchangeRate {
+        from: Currency,
+        to: Currency,
+        date: chrono::NaiveDate,
+    },
+}
+
+impl ReportError {
+    /// Request fields the error is about, with the message for each.
+    pub fn field_errors(&self) -> Vec<(&'static str, String)> {
+        let field = match self {
+            Self::InvalidPeriod => "to",
+            Self::MissingExchangeRate { .. } => "currency",
+        };
+
+        vec![(field, self.to_string())]
+    }
+}
+
+/// Budget of a category compared with the actual spending in a period,
+/// spending of subcategories is included in their parents.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CategoryBudgetReport {
+    pub category: Category,
+    pub spent: i64,
+    /// Budget left after spending, negative when overspent.
+    pub remaining: Option<i64>,
+    pub percent_used: Option<f64>,
+}
+
+impl CategoryBudgetReport {
+    pub fn new(category: Category, spent: i64) -> Self {
+        let remaining = category.budget.map(|budget| budget - spent);
+        let percent_used = category
+            .budget
+            .filter(|budget| *budget > 0)
+            .map(|budget| (spent as f64 * 10000.0 / budget as f64).round() / 100.0);
+
+        Self {
+            category,
+            spent,
+            remaining,
+            percent_used,
+        }
+    }
 }
 
+/// Income and outcome of one interval of a cash flow report.
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub struct Account {
-    pub id: i64,
-    pub name: String,
-    pub account_type: AccountType,
-    pub balance: i64,
+pub struct CashFlowBucket {
+    /// First day of the interval.
+    pub start: chrono::NaiveDate,
+    pub income: i64,
+    pub outcome: i64,
+    /// Income minus outcome.
+    pub net: i64,
+    /// Balance of the report's accounts at the end of this interval, their
+    /// balance at the start of the report plus the net flow since.
+    pub running_balance: i64,
 }
 
-impl Account {
-    pub fn new(name: String, balance: i64, account_type: String) -> Result<Self, AccountError> {
-        Ok(Self {
-            id: 0,
-            name,
-            balance,
-            account_type: AccountType::from_str(&account_type)
-                .map_err(|_| AccountError::UnknownAcountType)?,
-        })
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error("delimiter must be a single ASCII character")]
+    InvalidDelimiter,
+    #[error("decimal separator must be \".\" or \",\"")]
+    InvalidDecimalSeparator,
+    #[error("columns can only be referenced by name when the file has a header")]
+    NamedColumnWithoutHeader,
+    #[error("column \"{0}\" not found in header")]
+    UnknownColumn(String),
+    #[error("cannot read header: {0}")]
+    InvalidHeader(String),
+    #[error("invalid mapping: {0}")]
+    InvalidMapping(String),
+    #[error("{0} is required")]
+    MissingField(&'static str),
+}
+
+impl ImportError {
+    /// Request fields the error is about, with the message for each.
+    pub fn field_errors(&self) -> Vec<(&'static str, String)> {
+        let field = match self {
+            Self::InvalidDelimiter
+            | Self::InvalidDecimalSeparator
+            | Self::NamedColumnWithoutHeader
+            | Self::UnknownColumn(_)
+            | Self::InvalidMapping(_) => "mapping",
+            Self::InvalidHeader(_) => "file",
+            Self::MissingField(field) => field,
+        };
+
+        vec![(field, self.to_string())]
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CurrencyError {
+    #[error("invalid currency \"{0}\", expected an ISO 4217 code such as EUR")]
+    InvalidCurrencyCode(String),
+    #[error("exchange rate must be greater than zero")]
+    InvalidExchangeRate,
+    #[error("exchange rate must convert between two different currencies")]
+    SameCurrency,
+    #[error("line {line}: {message}")]
+    InvalidRateRow { line: u64, message: String },
+}
+
+impl CurrencyError {
+    /// Request fields the error is about, with the message for each.
+    pub fn field_errors(&self) -> Vec<(&'static str, String)> {
+        let field = match self {
+            Self::InvalidCurrencyCode(_) => "currency",
+            Self::InvalidExchangeRate => "rate",
+            Self::SameCurrency => "quote",
+            // The line of the file is part of the message.
+            Self::InvalidRateRow { .. } => return Vec::new(),
+        };
+
+        vec![(field, self.to_string())]
+    }
+}
+
+/// Currencies whose minor unit is not a hundredth, by number of decimal
+/// places.
+pub const MINOR_UNITS: [(u32, &[&str]); 3] = [
+    (
+        0,
+        &[
+            "BIF", "CLP", "DJF", "GNF", "ISK", "JPY", "KMF", "KRW", "PYG", "RWF", "UGX", "UYI",
+            "VND", "VUV", "XAF", "XOF", "XPF",
+        ],
+    ),
+    (3, &["BHD", "IQD", "JOD", "KWD", "LYD", "OMR", "TND"]),
+    (4, &["CLF", "UYW"]),
+];
+
+/// Active ISO 4217 codes of currencies and funds, sorted. Precious metals,
+/// bond market units and testing codes have no minor unit and are left out.
+const CURRENCY_CODES: [&str; 170] = [
+    "AED", "AFN", "ALL", "AMD", "ANG", "AOA", "ARS", "AUD", "AWG", "AZN", "BAM", "BBD", "BDT",
+    "BGN", "BHD", "BIF", "BMD", "BND", "BOB", "BOV", "BRL", "BSD", "BTN", "BWP", "BYN", "BZD",
+    "CAD", "CDF", "CHE", "CHF", "CHW", "CLF", "CLP", "CNY", "COP", "COU", "CRC", "CUC", "CUP",
+    "CVE", "CZK", "DJF", "DKK", "DOP", "DZD", "EGP", "ERN", "ETB", "EUR", "FJD", "FKP", "GBP",
+    "GEL", "GHS", "GIP", "GMD", "GNF", "GTQ", "GYD", "HKD", "HNL", "HTG", "HUF", "IDR", "ILS",
+    "INR", "IQD", "IRR", "ISK", "JMD", "JOD", "JPY", "KES", "KGS", "KHR", "KMF", "KPW", "KRW",
+    "KWD", "KYD", "KZT", "LAK", "LBP", "LKR", "LRD", "LSL", "LYD", "MAD", "MDL", "MGA", "MKD",
+    "MMK", "MNT", "MOP", "MRU", "MUR", "MVR", "MWK", "MXN", "MXV", "MYR", "MZN", "NAD", "NGN",
+    "NIO", "NOK", "NPR", "NZD", "OMR", "PAB", "PEN", "PGK", "PHP", "PKR", "PLN", "PYG", "QAR",
+    "RON", "RSD", "RUB", "RWF", "SAR", "SBD", "SCR", "SDG", "SEK", "SGD", "SHP", "SLE", "SLL",
+    "SOS", "SRD", "SSP", "STN", "SVC", "SYP", "SZL", "THB", "TJS", "TMT", "TND", "TOP", "TRY",
+    "TTD", "TWD", "TZS", "UAH", "UGX", "USD", "USN", "UYI", "UYU", "UYW", "UZS", "VED", "VES",
+    "VND", "VUV", "WST", "XAF", "XCD", "XCG", "XDR", "XOF", "XPF", "YER", "ZAR", "ZMW", "ZWG",
+    "ZWL",
+];
+
+/// ISO 4217 alphabetic currency code.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Currency(String);
+
+impl Currency {
+    pub fn code(&self) -> &str {
+        &self.0
+    }
+
+    /// Number of decimal places of the currency's minor unit, for example 2
+    /// for cents of `USD` and 0 for `JPY`.
+    pub fn minor_units(&self) -> u32 {
+        MINOR_UNITS
+            .iter()
+            .find(|(_, codes)| codes.contains(&self.code()))
+            .map_or(2, |(minor_units, _)| *minor_units)
+    }
+}
+
+impl FromStr for Currency {
+    type Err = CurrencyError;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        let code = code.trim();
+        let upper = code.to_ascii_uppercase();
+        if CURRENCY_CODES.binary_search(&upper.as_str()).is_err() {
+            return Err(CurrencyError::InvalidCurrencyCode(code.to_owned()));
+        }
+
+        Ok(Self(upper))
+    }
+}
+
+impl Default for Currency {
+    fn default() -> Self {
+        Self("USD".into())
+    }
+}
+
+impl std::fmt::Display for Currency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum MoneyError {
+    #[error("invalid amount \"{0}\", expected a decimal number such as \"12.50\"")]
+    InvalidAmount(String),
+    #[error("amount \"{amount}\" has more than {minor_units} decimal places allowed in {currency}")]
+    TooManyDecimalPlaces {
+        amount: String,
+        currency: Currency,
+        minor_units: u32,
+    },
+    #[error("amount is too large")]
+    Overflow,
+    #[error("cannot combine amounts in {0} and {1}")]
+    CurrencyMismatch(Currency, Currency),
+}
+
+impl MoneyError {
+    /// Request fields the error is about, with the message for each.
+    pub fn field_errors(&self) -> Vec<(&'static str, String)> {
+        vec![("amount", self.to_string())]
+    }
+}
+
+/// Amount of money in minor units of its currency, such as cents.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Money {
+    minor: i64,
+    currency: Currency,
+}
+
+impl Money {
+    pub fn new(minor: i64, currency: Currency) -> Self {
+        Self { minor, currency }
+    }
+
+    pub fn zero(currency: Currency) -> Self {
+        Self::new(0, currency)
+    }
+
+    /// Parses a decimal amount in major units such as `"12.50"` or `"-3"`.
+    /// Fails instead of rounding when it has more decimal places than the
+    /// currency's minor unit.
+    pub fn parse(amount: &str, currency: Currency) -> Result<Self, MoneyError> {
+        let invalid = || MoneyError::InvalidAmount(amount.to_owned());
+
+        let trimmed = amount.trim();
+        let (negative, digits) = match trimmed.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        let is_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+        if whole.is_empty() || !is_digits(whole) || !is_digits(fraction) {
+            return Err(invalid());
+        }
+        if digits.ends_with('.') {
+            return Err(invalid());
+        }
+
+        let minor_units = currency.minor_units();
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.len() > minor_units as usize {
+            return Err(MoneyError::TooManyDecimalPlaces {
+                amount: amount.to_owned(),
+                currency,
+                minor_units,
+            });
+        }
+
+        let mut minor: i64 = 0;
+        let padded = format!("{fraction:0<width$}", width = minor_units as usize);
+        for digit in whole.chars().chain(padded.chars()) {
+            minor = minor
+                .checked_mul(10)
+                .and_then(|m| m.checked_add(i64::from(digit as u8 - b'0')))
+                .ok_or(MoneyError::Overflow)?;
+        }
+
+        Ok(Self::new(if negative { -minor } else { minor }, currency))
+    }
+
+    pub fn minor(&self) -> i64 {
+        self.minor
+    }
+
+    pub fn currency(&self) -> &Currency {
+        &self.currency
+    }
+
+    pub fn checked_add(&self, other: &Money) -> Result<Money, MoneyError> {
+        self.same_currency(other)?;
+        let minor = self
+            .minor
+            .checked_add(other.minor)
+            .ok_or(MoneyError::Overflow)?;
+
+        Ok(Self::new(minor, self.currency.clone()))
+    }
+
+    pub fn checked_sub(&self, other: &Money) -> Result<Money, MoneyError> {
+        self.same_currency(other)?;
+        let minor = self
+            .minor
+            .checked_sub(other.minor)
+            .ok_or(MoneyError::Overflow)?;
+
+        Ok(Self::new(minor, self.currency.clone()))
+    }
+
+    pub fn checked_neg(&self) -> Result<Money, MoneyError> {
+        let minor = self.minor.checked_neg().ok_or(MoneyError::Overflow)?;
+
+        Ok(Self::new(minor, self.currency.clone()))
+    }
+
+    /// Converts the amount to `currency` at `rate`, the price of one major
+    /// unit of this amount's currency in `currency`, rounded to the nearest
+    /// minor unit.
+    pub fn convert(&self, rate: f64, currency: Currency) -> Result<Money, MoneyError> {
+        let exponent = currency.minor_units() as i32 - self.currency.minor_units() as i32;
+        let minor = (self.minor as f64 * rate * 10f64.powi(exponent)).round();
+        // i64::MAX is not representable, the nearest f64 is one past it.
+        if !minor.is_finite() || minor < i64::MIN as f64 || minor >= i64::MAX as f64 {
+            return Err(MoneyError::Overflow);
+        }
+
+        Ok(Self::new(minor as i64, currency))
+    }
+
+    fn same_currency(&self, other: &Money) -> Result<(), MoneyError> {
+        if self.currency != other.currency {
+            return Err(MoneyError::CurrencyMismatch(
+                self.currency.clone(),
+                other.currency.clone(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Formats the amount in major units with all decimal places of the
+/// currency, such as `12.50`, without the currency code.
+impl std::fmt::Display for Money {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let minor_units = self.currency.minor_units();
+        let sign = if self.minor < 0 { "-" } else { "" };
+        let abs = self.minor.unsigned_abs();
+        if minor_units == 0 {
+            return write!(f, "{sign}{abs}");
+        }
+
+        let scale = 10u64.pow(minor_units);
+        write!(
+            f,
+            "{sign}{}.{:0width$}",
+            abs / scale,
+            abs % scale,
+            width = minor_units as usize
+        )
+    }
+}
+
+/// Amount of a command whose currency is only known once the account or
+/// user it applies to is loaded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Amount {
+    /// Minor units, used for amounts that are already stored.
+    Minor(i64),
+    /// Decimal in major units as sent by clients, see [`Money::parse`].
+    Decimal(String),
+}
+
+impl Amount {
+    pub fn to_money(&self, currency: &Currency) -> Result<Money, MoneyError> {
+        match self {
+            Self::Minor(minor) => Ok(Money::new(*minor, currency.clone())),
+            Self::Decimal(amount) => Money::parse(amount, currency.clone()),
+        }
+    }
+}
+
+impl From<i64> for Amount {
+    fn from(minor: i64) -> Self {
+        Self::Minor(minor)
+    }
+}
+
+/// Price of one unit of `base` in `quote` from `valid_on` until the next
+/// rate of the same pair.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExchangeRate {
+    pub id: i64,
+    pub base: Currency,
+    pub quote: Currency,
+    pub rate: f64,
+    pub valid_on: chrono::NaiveDate,
+}
+
+impl ExchangeRate {
+    pub fn new(
+        base: Currency,
+        quote: Currency,
+        rate: f64,
+        valid_on: chrono::NaiveDate,
+    ) -> Result<Self, CurrencyError> {
+        if base == quote {
+            return Err(CurrencyError::SameCurrency);
+        }
+        if !rate.is_finite() || rate <= 0.0 {
+            return Err(CurrencyError::InvalidExchangeRate);
+        }
+
+        Ok(Self {
+            id: 0,
+            base,
+            quote,
+            rate,
+            valid_on,
+        })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum AccountError {
+    #[error("unknown account type")]
+    UnknownAcountType,
+    #[error(transparent)]
+    InvalidInitialBalance(MoneyError),
+}
+
+impl AccountError {
+    /// Request fields the error is about, with the message for each.
+    pub fn field_errors(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Self::UnknownAcountType => vec![("account_type", self.to_string())],
+            Self::InvalidInitialBalance(_) => vec![("initial_balance", self.to_string())],
+        }
+    }
+}
+
+/// Soft deleted item waiting in the trash.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Deleted<T> {
+    pub item: T,
+    pub deleted_at: chrono::DateTime<Local>,
+}
+
+/// Number of items permanently removed from the trash.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PurgeReport {
+    pub records: u64,
+    pub accounts: u64,
+    pub categories: u64,
+}
+
+#[derive(Debug, Error)]
+pub enum DeletePolicyError {
+    #[error("reassign policy requires a target_id")]
+    TargetRequired,
+    #[error("target_id is only allowed with the reassign policy")]
+    UnexpectedTarget,
+    #[error("records cannot be reassigned to the entity being deleted")]
+    ReassignToItself,
+    #[error("records in {from} cannot be reassigned to an account in {to}")]
+    ReassignToOtherCurrency { from: Currency, to: Currency },
+}
+
+impl DeletePolicyError {
+    /// Request fields the error is about, with the message for each.
+    pub fn field_errors(&self) -> Vec<(&'static str, String)> {
+        vec![("target_id", self.to_string())]
+    }
+}
+
+/// What happens to records referencing an account or category that is
+/// being deleted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DeletePolicy {
+    /// Refuse to delete while anything references it.
+    #[default]
+    Refuse,
+    /// Move the records to the account or category with this id.
+    Reassign(i64),
+    /// Delete the records as well.
+    Cascade,
+}
+
+#[derive(Debug, EnumString, Clone, strum_macros::Display, PartialEq, Eq)]
+pub enum AccountType {
+    Cash,
+    DebitCard,
+    CreditCard,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Account {
+    pub id: i64,
+    pub name: String,
+    pub account_type: AccountType,
+    /// Currency of the balance and of the amounts of the account's records.
+    pub currency: Currency,
+    pub balance: i64,
+}
+
+impl Account {
+    pub fn new(
+        name: String,
+        balance: i64,
+        account_type: String,
+        currency: Currency,
+    ) -> Result<Self, AccountError> {
+        Ok(Self {
+            id: 0,
+            name,
+            balance,
+            account_type: AccountType::from_str(&account_type)
+                .map_err(|_| AccountError::UnknownAcountType)?,
+            currency,
+        })
+    }
+}
+
+const MAX_USERNAME_LENGTH: usize = 64;
+const MIN_PASSWORD_LENGTH: usize = 8;
+
+#[derive(Debug, Error)]
+pub enum UserError {
+    #[error("username must not be empty or longer than 64 characters")]
+    InvalidUsername,
+    #[error("password must be at least 8 characters long")]
+    PasswordTooShort,
+    #[error("reporting currency cannot change while categories have budgets in it")]
+    BudgetsInReportingCurrency,
+}
+
+impl UserError {
+    /// Request fields the error is about, with the message for each.
+    pub fn field_errors(&self) -> Vec<(&'static str, String)> {
+        let field = match self {
+            Self::InvalidUsername => "username",
+            Self::PasswordTooShort => "password",
+            Self::BudgetsInReportingCurrency => "reporting_currency",
+        };
+
+        vec![(field, self.to_string())]
+    }
+}
+
+pub fn validate_password(password: &str) -> Result<(), UserError> {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(UserError::PasswordTooShort);
+    }
+
+    Ok(())
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct User {
+    pub id: i64,
+    pub username: String,
+    /// Currency reports are converted to unless another one is requested,
+    /// also the default currency of new accounts.
+    pub reporting_currency: Currency,
+    pub created_at: chrono::DateTime<Local>,
+}
+
+impl User {
+    pub fn new(username: String) -> Result<Self, UserError> {
+        let mut user = Self {
+            id: 0,
+            username: String::new(),
+            reporting_currency: Currency::default(),
+            created_at: Local::now(),
+        };
+        user.set_username(username)?;
+
+        Ok(user)
+    }
+
+    pub fn set_username(&mut self, username: String) -> Result<(), UserError> {
+        let username = username.trim().to_owned();
+        if username.is_empty() || username.chars().count() > MAX_USERNAME_LENGTH {
+            return Err(UserError::InvalidUsername);
+        }
+
+        self.username = username;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, EnumString, Clone, strum_macros::Display, PartialEq, Eq)]
+pub enum TokenKind {
+    /// Short-lived token issued on login.
+    Session,
+    /// Long-lived token created by the user for scripts.
+    Api,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token {
+    pub id: i64,
+    pub user_id: i64,
+    pub kind: TokenKind,
+    pub name: Option<String>,
+    pub created_at: chrono::DateTime<Local>,
+    pub expires_at: Option<chrono::DateTime<Local>>,
+}
+
+impl Token {
+    pub fn new(
+        user_id: i64,
+        kind: TokenKind,
+        name: Option<String>,
+        expires_at: Option<chrono::DateTime<Local>>,
+    ) -> Self {
+        Self {
+            id: 0,
+            user_id,
+            kind,
+            name,
+            created_at: Local::now(),
+            expires_at,
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at <= Local::now())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum RecurrenceError {
+    #[error("recurrence interval must be greater than zero")]
+    InvalidInterval,
+    #[error("day of month must be between 1 and 31")]
+    InvalidDayOfMonth,
+    #[error("day of month can only be set for monthly and yearly recurrences")]
+    UnexpectedDayOfMonth,
+    #[error("recurrence must end after it starts")]
+    EndBeforeStart,
+    #[error("{}", join_errors(.0))]
+    Multiple(Vec<RecurrenceError>),
+}
+
+impl RecurrenceError {
+    /// Request fields the error is about, with the message for each.
+    pub fn field_errors(&self) -> Vec<(&'static str, String)> {
+        let field = match self {
+            Self::Multiple(errors) => return errors.iter().flat_map(Self::field_errors).collect(),
+            Self::InvalidInterval => "interval",
+            Self::InvalidDayOfMonth | Self::UnexpectedDayOfMonth => "day_of_month",
+            Self::EndBeforeStart => "ends_at",
+        };
+
+        vec![(field, self.to_string())]
+    }
+}
+
+#[derive(Debug, EnumString, Clone, Copy, strum_macros::Display, PartialEq, Eq)]
+pub enum RecurrenceUnit {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+/// Repeats every `interval` units, monthly and yearly occurrences fall on
+/// `day_of_month` or the start day, clamped to the end of shorter months.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Recurrence {
+    pub unit: RecurrenceUnit,
+    pub interval: u32,
+    pub day_of_month: Option<u32>,
+}
+
+impl Recurrence {
+    pub fn new(
+        unit: RecurrenceUnit,
+        interval: u32,
+        day_of_month: Option<u32>,
+    ) -> Result<Self, RecurrenceError> {
+        let interval_error = (interval == 0).then_some(RecurrenceError::InvalidInterval);
+        let day_error = match day_of_month {
+            Some(_) if !matches!(unit, RecurrenceUnit::Month | RecurrenceUnit::Year) => {
+                Some(RecurrenceError::UnexpectedDayOfMonth)
+            }
+            Some(day) if !(1..=31).contains(&day) => Some(RecurrenceError::InvalidDayOfMonth),
+            _ => None,
+        };
+        if interval_error.is_some() || day_error.is_some() {
+            return Err(combine(
+                [interval_error, day_error],
+                RecurrenceError::Multiple,
+            ));
+        }
+
+        Ok(Self {
+            unit,
+            interval,
+            day_of_month,
+        })
+    }
+
+    /// Returns the date `n` intervals and `offset` units after `start`,
+    /// `None` on overflow.
+    fn nth(
+        &self,
+        start: chrono::DateTime<Local>,
+        n: u32,
+        offset: u32,
+    ) -> Option<chrono::DateTime<Local>> {
+        let start = start.naive_local();
+        let steps = n.checked_mul(self.interval)?.checked_add(offset)?;
+
+        let date = match self.unit {
+            RecurrenceUnit::Day => start.date().checked_add_days(Days::new(steps.into()))?,
+            RecurrenceUnit::Week => start
+                .date()
+                .checked_add_days(Days::new(u64::from(steps) * 7))?,
+            RecurrenceUnit::Month | RecurrenceUnit::Year => {
+                let months = match self.unit {
+                    RecurrenceUnit::Year => steps.checked_mul(12)?,
+                    _ => steps,
+                };
+                let month = start
+                    .date()
+                    .with_day(1)?
+                    .checked_add_months(Months::new(months))?;
+                let day = self.day_of_month.unwrap_or(start.day());
+                (1..=day).rev().find_map(|day| month.with_day(day))?
+            }
+        };
+
+        let datetime = date.and_time(start.time());
+        datetime.and_local_timezone(Local).earliest().or_else(|| {
+            (datetime + Duration::from_secs(60 * 60))
+                .and_local_timezone(Local)
+                .earliest()
+        })
+    }
+}
+
+/// Template of a record repeated on a schedule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurringRecord {
+    pub id: i64,
+    pub user_id: i64,
+    pub account_id: i64,
+    pub record_type: RecordType,
+    pub amount: i64,
+    /// Currency of the account.
+    pub currency: Currency,
+    pub description: Option<String>,
+    pub category_id: Option<i64>,
+    pub destination_account_id: Option<i64>,
+    pub received_amount: Option<i64>,
+    /// Currency of the destination account of a transfer.
+    pub destination_currency: Option<Currency>,
+    pub recurrence: Recurrence,
+    pub starts_at: chrono::DateTime<Local>,
+    pub ends_at: Option<chrono::DateTime<Local>>,
+    /// Number of occurrences already turned into records.
+    pub occurrence_count: u32,
+    /// Date of the next occurrence, `None` once the recurrence has ended.
+    pub next_occurrence_at: Option<chrono::DateTime<Local>>,
+    pub created_at: chrono::DateTime<Local>,
+    pub updated_at: chrono::DateTime<Local>,
+}
+
+impl RecurringRecord {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        user_id: i64,
+        account_id: i64,
+        record_type: RecordType,
+        amount: Money,
+        description: Option<String>,
+        category_id: Option<i64>,
+        destination_account_id: Option<i64>,
+        received_amount: Option<Money>,
+        recurrence: Recurrence,
+        starts_at: chrono::DateTime<Local>,
+        ends_at: Option<chrono::DateTime<Local>>,
+    ) -> Result<Self, RecurrenceError> {
+        let mut recurring = Self {
+            id: 0,
+            user_id,
+            account_id,
+            record_type,
+            amount: amount.minor,
+            currency: amount.currency,
+            description,
+            category_id,
+            destination_account_id,
+            received_amount: received_amount.as_ref().map(Money::minor),
+            destination_currency: received_amount.map(|amount| amount.currency),
+            recurrence,
+            starts_at,
+            ends_at: None,
+            occurrence_count: 0,
+            next_occurrence_at: None,
+            created_at: Local::now(),
+            updated_at: Local::now(),
+        };
+        recurring.set_ends_at(ends_at)?;
+
+        Ok(recurring)
+    }
+
+    /// Checks whether the template refers to the account on either side.
+    pub fn uses_account(&self, account_id: i64) -> bool {
+        self.account_id == account_id || self.destination_account_id == Some(account_id)
+    }
+
+    /// Moves the template from one account to another, see
+    /// [`Record::replace_account`].
+    pub fn replace_account(
+        &mut self,
+        account_id: i64,
+        new_account_id: i64,
+    ) -> Result<(), RecordError> {
+        if self.account_id == account_id {
+            self.account_id = new_account_id;
+        }
+        if self.destination_account_id == Some(account_id) {
+            self.destination_account_id = Some(new_account_id);
+        }
+        if self.destination_account_id == Some(self.account_id) {
+            return Err(RecordError::TransferToSameAccount);
+        }
+
+        Ok(())
+    }
+
+    pub fn set_ends_at(
+        &mut self,
+        ends_at: Option<chrono::DateTime<Local>>,
+    ) -> Result<(), RecurrenceError> {
+        if ends_at.is_some_and(|ends_at| ends_at < self.starts_at) {
+            return Err(RecurrenceError::EndBeforeStart);
+        }
+
+        self.ends_at = ends_at;
+        self.next_occurrence_at = self.occurrence(self.occurrence_count);
+
+        Ok(())
+    }
+
+    /// Returns the date of the occurrence with the given index, `None` when
+    /// it falls after the end of the recurrence.
+    pub fn occurrence(&self, index: u32) -> Option<chrono::DateTime<Local>> {
+        // The day of month may fall before `starts_at` in the start month, the
+        // recurrence then begins one unit later.
+        let offset = match self.recurrence.nth(self.starts_at, 0, 0) {
+            Some(first) if first < self.starts_at => 1,
+            _ => 0,
+        };
+
+        self.recurrence
+            .nth(self.starts_at, index, offset)
+            .filter(|at| self.ends_at.is_none_or(|ends_at| *at <= ends_at))
+    }
+
+    /// Returns up to `count` dates of occurrences not yet turned into records.
+    pub fn upcoming(&self, count: usize) -> Vec<chrono::DateTime<Local>> {
+        (self.occurrence_count..)
+            .map_while(|index| self.occurrence(index))
+            .take(count)
+            .collect()
+    }
+
+    /// Marks the next occurrence as turned into a record.
+    pub fn advance(&mut self) {
+        self.occurrence_count += 1;
+        self.next_occurrence_at = self.occurrence(self.occurrence_count);
+    }
+}
+
+/// Kinds of entities whose changes are audited. Of the tokens only API
+/// tokens are, session tokens come and go with every login.
+#[derive(Debug, EnumString, Clone, Copy, strum_macros::Display, PartialEq, Eq)]
+#[strum(serialize_all = "snake_case")]
+pub enum AuditEntity {
+    Account,
+    Category,
+    Record,
+    RecurringRecord,
+    Tag,
+    User,
+    ExchangeRate,
+    Token,
+}
+
+#[derive(Debug, EnumString, Clone, Copy, strum_macros::Display, PartialEq, Eq)]
+#[strum(serialize_all = "snake_case")]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+    Restore,
+}
+
+/// Entity whose changes are written to the audit log.
+pub trait Auditable {
+    const ENTITY: AuditEntity;
+
+    fn entity_id(&self) -> i64;
+    /// State of the entity stored with the audit entry.
+    fn snapshot(&self) -> serde_json::Value;
+}
+
+/// Append-only audit log entry describing one change of an entity.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AuditEntry {
+    pub id: i64,
+    /// Owner of the changed entity.
+    pub user_id: i64,
+    /// User who made the change, `None` for changes made by the server itself
+    /// such as records created from recurring records.
+    pub actor_id: Option<i64>,
+    pub entity_type: AuditEntity,
+    pub entity_id: i64,
+    pub action: AuditAction,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+    pub created_at: chrono::DateTime<Local>,
+}
+
+impl AuditEntry {
+    pub fn new<E: Auditable>(
+        user_id: i64,
+        actor_id: Option<i64>,
+        action: AuditAction,
+        before: Option<&E>,
+        after: Option<&E>,
+    ) -> Self {
+        Self {
+            id: 0,
+            user_id,
+            actor_id,
+            entity_type: E::ENTITY,
+            entity_id: after.or(before).map(E::entity_id).unwrap_or_default(),
+            action,
+            before: before.map(E::snapshot),
+            after: after.map(E::snapshot),
+            created_at: Local::now(),
+        }
+    }
+}
+
+impl Auditable for Account {
+    const ENTITY: AuditEntity = AuditEntity::Account;
+
+    fn entity_id(&self) -> i64 {
+        self.id
+    }
+
+    fn snapshot(&self) -> serde_json::Value {
+        serde_json::json!({
+            "id": self.id,
+            "name": self.name,
+            "account_type": self.account_type.to_string(),
+            "currency": self.currency.code(),
+            "balance": self.balance,
+        })
+    }
+}
+
+impl Auditable for Category {
+    const ENTITY: AuditEntity = AuditEntity::Category;
+
+    fn entity_id(&self) -> i64 {
+        self.id
+    }
+
+    fn snapshot(&self) -> serde_json::Value {
+        serde_json::json!({
+            "id": self.id,
+            "name": self.name,
+            "budget": self.budget,
+            "parent_id": self.parent_id,
+        })
+    }
+}
+
+impl Auditable for Tag {
+    const ENTITY: AuditEntity = AuditEntity::Tag;
+
+    fn entity_id(&self) -> i64 {
+        self.id
+    }
+
+    fn snapshot(&self) -> serde_json::Value {
+        serde_json::json!({
+            "id": self.id,
+            "name": self.name,
+        })
+    }
+}
+
+impl Auditable for Record {
+    const ENTITY: AuditEntity = AuditEntity::Record;
+
+    fn entity_id(&self) -> i64 {
+        self.id
+    }
+
+    fn snapshot(&self) -> serde_json::Value {
+        let splits: Vec<_> = self
+            .splits
+            .iter()
+            .map(|split| {
+                serde_json::json!({
+                    "category_id": split.category.id,
+                    "amount": split.amount.get(),
+                    "note": split.note,
+                })
+            })
+            .collect();
+
+        serde_json::json!({
+            "id": self.id,
+            "account_id": self.account_id,
+            "record_type": self.record_type.to_string(),
+            "amount": self.amount.get(),
+            "description": self.description,
+            "category_id": self.category.as_ref().map(|c| c.id),
+            "splits": splits,
+            "tag_ids": self.tags.iter().map(|t| t.id).collect::<Vec<_>>(),
+            "destination_account_id": self.transfer.as_ref().map(|t| t.account_id),
+            "received_amount": self.transfer.as_ref().map(|t| t.amount.get()),
+            "occurred_at": self.occurred_at.to_rfc3339(),
+            "posted_at": self.posted_at.map(|posted_at| posted_at.to_rfc3339()),
+        })
+    }
+}
+
+impl Auditable for RecurringRecord {
+    const ENTITY: AuditEntity = AuditEntity::RecurringRecord;
+
+    fn entity_id(&self) -> i64 {
+        self.id
+    }
+
+    fn snapshot(&self) -> serde_json::Value {
+        serde_json::json!({
+            "id": self.id,
+            "account_id": self.account_id,
+            "record_type": self.record_type.to_string(),
+            "amount": self.amount,
+            "description": self.description,
+            "category_id": self.category_id,
+            "destination_account_id": self.destination_account_id,
+            "received_amount": self.received_amount,
+            "unit": self.recurrence.unit.to_string(),
+            "interval": self.recurrence.interval,
+            "day_of_month": self.recurrence.day_of_month,
+            "starts_at": self.starts_at.to_rfc3339(),
+            "ends_at": self.ends_at.map(|ends_at| ends_at.to_rfc3339()),
+        })
+    }
+}
+
+impl Auditable for User {
+    const ENTITY: AuditEntity = AuditEntity::User;
+
+    fn entity_id(&self) -> i64 {
+        self.id
+    }
+
+    fn snapshot(&self) -> serde_json::Value {
+        serde_json::json!({
+            "id": self.id,
+            "username": self.username,
+            "reporting_currency": self.reporting_currency.code(),
+        })
+    }
+}
+
+impl Auditable for ExchangeRate {
+    const ENTITY: AuditEntity = AuditEntity::ExchangeRate;
+
+    fn entity_id(&self) -> i64 {
+        self.id
+    }
+
+    fn snapshot(&self) -> serde_json::Value {
+        serde_json::json!({
+            "id": self.id,
+            "base": self.base.code(),
+            "quote": self.quote.code(),
+            "rate": self.rate,
+            "valid_on": self.valid_on.to_string(),
+        })
+    }
+}
+
+/// Only the details of a token are stored, never its secret or digest.
+impl Auditable for Token {
+    const ENTITY: AuditEntity = AuditEntity::Token;
+
+    fn entity_id(&self) -> i64 {
+        self.id
+    }
+
+    fn snapshot(&self) -> serde_json::Value {
+        serde_json::json!({
+            "id": self.id,
+            "kind": self.kind.to_string(),
+            "name": self.name,
+            "expires_at": self.expires_at.map(|expires_at| expires_at.to_rfc3339()),
+        })
+    }
+}
+
+/// Largest number of items a client can ask for in one page.
+pub const MAX_PAGE_LIMIT: u64 = 1000;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PageError {
+    #[error("invalid cursor, request the first page again")]
+    InvalidCursor,
+    #[error("limit must be between 1 and {MAX_PAGE_LIMIT}")]
+    InvalidLimit,
+    #[error("offset is not supported, pass the next_cursor of the previous page as cursor")]
+    OffsetUnsupported,
+}
+
+impl PageError {
+    /// Request fields the error is about, with the message for each.
+    pub fn field_errors(&self) -> Vec<(&'static str, String)> {
+        let field = match self {
+            Self::InvalidCursor => "cursor",
+            Self::InvalidLimit => "limit",
+            Self::OffsetUnsupported => "offset",
+        };
+
+        vec![(field, self.to_string())]
+    }
+}
+
+/// Sort key of the last item of a page.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CursorKey {
+    /// Lists ordered by id alone.
+    Id,
+    Date(chrono::DateTime<Local>),
+    /// Calendar day, such as the day an exchange rate is valid on.
+    Day(chrono::NaiveDate),
+    Amount(i64),
+    /// Name of the item or of its category, `None` sorts last.
+    Name(Option<String>),
+}
+
+/// Keyset position a page resumes after: the sort key of the last item
+/// returned and its id, which breaks ties. Unlike an offset it stays valid
+/// when items are added or removed between requests.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Cursor {
+    pub key: CursorKey,
+    pub id: i64,
+}
+
+impl Cursor {
+    pub fn new(key: CursorKey, id: i64) -> Self {
+        Self { key, id }
+    }
+
+    /// Whether the cursor was taken from a list sorted by the same kind of
+    /// key as `other`.
+    pub fn same_kind(&self, other: &CursorKey) -> bool {
+        std::mem::discriminant(&self.key) == std::mem::discriminant(other)
+    }
+}
+
+/// One page of a list.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Position of the last item, `None` on the last page.
+    pub next_cursor: Option<Cursor>,
+    /// Number of items on all pages, when requested.
+    pub total_count: Option<u64>,
+}
+
+impl<T> Page<T> {
+    /// Builds a page from up to `limit + 1` items, the extra item only tells
+    /// that another page follows.
+    pub fn new(
+        mut items: Vec<T>,
+        limit: Option<u64>,
+        cursor: impl Fn(&T) -> Cursor,
+        total_count: Option<u64>,
+    ) -> Self {
+        let limit = limit.map_or(usize::MAX, |limit| limit as usize);
+        let next_cursor = if items.len() > limit {
+            items.truncate(limit);
+            items.last().map(cursor)
+        } else {
+            None
+        };
+
+        Self {
+            items,
+            next_cursor,
+            total_count,
+        }
+    }
+
+    pub fn has_more(&self) -> bool {
+        self.next_cursor.is_some()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn category(id: i64) -> Category {
+        Category {
+            id,
+            name: format!("category {id}"),
+            budget: None,
+            parent_id: None,
+        }
+    }
+
+    #[test]
+    fn test_category_tree() {
+        let category = |id, name: &str, parent_id| Category {
+            id,
+            name: name.into(),
+            budget: None,
+            parent_id,
+        };
+        let categories = vec![
+            category(1, "food", None),
+            category(2, "groceries", Some(1)),
+            category(3, "bakery", Some(2)),
+            category(4, "bars", Some(1)),
+            category(5, "salary", None),
+        ];
+
+        let tree = CategoryTree::build(categories.clone());
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree[0].category.id, 1);
+        let children: Vec<i64> = tree[0].children.iter().map(|t| t.category.id).collect();
+        assert_eq!(children, vec![4, 2]);
+        assert_eq!(tree[0].children[1].children[0].category.id, 3);
+
+        let subtree = CategoryTree::subtree(categories, 2).unwrap();
+        assert_eq!(subtree.category.id, 2);
+        assert_eq!(subtree.children.len(), 1);
+        assert!(subtree.children[0].children.is_empty());
+    }
+
+    #[test]
+    fn test_record_splits() {
+        let mut record = Record::new(1, "Outcome".into(), usd(1000), None, None).unwrap();
+        let splits = vec![
+            RecordSplit::new(category(1), 700, Some("food".into())).unwrap(),
+            RecordSplit::new(category(2), 300, None).unwrap(),
+        ];
+        assert!(record.set_splits(splits.clone()).is_ok());
+
+        assert!(matches!(
+            record.set_amount(usd(-1200)),
+            Err(RecordError::AmountCannotBeLessOrEqualToZero)
+        ));
+        record.set_amount(usd(1200)).unwrap();
+        assert!(matches!(
+            record.set_splits(splits.clone()),
+            Err(RecordError::SplitAmountMismatch {
+                expected: 1200,
+                actual: 1000
+            })
+        ));
+
+        let mut record =
+            Record::new(1, "Outcome".into(), usd(1000), Some(category(3)), None).unwrap();
+        assert!(matches!(
+            record.set_splits(splits.clone()),
+            Err(RecordError::SplitWithCategory)
+        ));
+
+        let mut transfer = Record::new_transfer(1, 2, usd(1000), None, None, None).unwrap();
+        assert!(matches!(
+            transfer.set_splits(splits),
+            Err(RecordError::SplitTransfer)
+        ));
+
+        assert!(RecordSplit::new(category(1), -5, None).is_err());
+    }
+
+    fn usd(minor: i64) -> Money {
+        Money::new(minor, Currency::default())
+    }
+
+    fn date(s: &str) -> chrono::DateTime<Local> {
+        chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M")
+            .unwrap()
+            .and_local_timezone(Local)
+            .unwrap()
+    }
+
+    fn recurring(
+        recurrence: Recurrence,
+        starts_at: &str,
+        ends_at: Option<&str>,
+    ) -> RecurringRecord {
+        RecurringRecord::new(
+            1,
+            1,
+            RecordType::Outcome,
+            usd(100),
+            None,
+            None,
+            None,
+            None,
+            recurrence,
+            date(starts_at),
+            ends_at.map(date),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_monthly_recurrence_clamps_to_month_end() {
+        let recurrence = Recurrence::new(RecurrenceUnit::Month, 1, Some(31)).unwrap();
+        let recurring = recurring(recurrence, "2026-01-15 09:00", None);
+
+        assert_eq!(
+            recurring.upcoming(4),
+            vec![
+                date("2026-01-31 09:00"),
+                date("2026-02-28 09:00"),
+                date("2026-03-31 09:00"),
+                date("2026-04-30 09:00"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_recurrence_skips_day_before_start() {
+        let recurrence = Recurrence::new(RecurrenceUnit::Month, 2, Some(1)).unwrap();
+        let recurring = recurring(recurrence, "2026-01-15 00:00", Some("2026-07-01 00:00"));
+
+        assert_eq!(
+            recurring.upcoming(10),
+            vec![
+                date("2026-02-01 00:00"),
+                date("2026-04-01 00:00"),
+                date("2026-06-01 00:00"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_weekly_recurrence_advance() {
+        let recurrence = Recurrence::new(RecurrenceUnit::Week, 1, None).unwrap();
+        let mut recurring = recurring(recurrence, "2026-03-02 08:00", Some("2026-03-16 08:00"));
+        assert_eq!(recurring.next_occurrence_at, Some(date("2026-03-02 08:00")));
+
+        recurring.advance();
+        recurring.advance();
+        assert_eq!(recurring.next_occurrence_at, Some(date("2026-03-16 08:00")));
+
+        recurring.advance();
+        assert_eq!(recurring.next_occurrence_at, None);
+    }
+
+    #[test]
+    fn test_recurrence_validation() {
+        assert!(matches!(
+            Recurrence::new(RecurrenceUnit::Day, 0, None),
+            Err(RecurrenceError::InvalidInterval)
+        ));
+        assert!(matches!(
+            Recurrence::new(RecurrenceUnit::Week, 1, Some(3)),
+            Err(RecurrenceError::UnexpectedDayOfMonth)
+        ));
+        assert!(matches!(
+            Recurrence::new(RecurrenceUnit::Month, 1, Some(32)),
+            Err(RecurrenceError::InvalidDayOfMonth)
+        ));
+    }
+
+    #[test]
+    fn test_currency_code() {
+        assert_eq!(Currency::from_str(" eur ").unwrap().code(), "EUR");
+        assert!(matches!(
+            Currency::from_str("EURO"),
+            Err(CurrencyError::InvalidCurrencyCode(_))
+        ));
+        assert!(Currency::from_str("E1R").is_err());
+        for unknown in ["XYZ", "ABC", "XAU"] {
+            assert!(matches!(
+                Currency::from_str(unknown),
+                Err(CurrencyError::InvalidCurrencyCode(_))
+            ));
+        }
+        assert!(CURRENCY_CODES.is_sorted());
+        for (_, codes) in MINOR_UNITS {
+            assert!(codes.iter().all(|code| Currency::from_str(code).is_ok()));
+        }
+
+        let date = chrono::NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        let eur = Currency::from_str("EUR").unwrap();
+        assert!(matches!(
+            ExchangeRate::new(eur.clone(), eur.clone(), 1.0, date),
+            Err(CurrencyError::SameCurrency)
+        ));
+        assert!(matches!(
+            ExchangeRate::new(eur, Currency::default(), 0.0, date),
+            Err(CurrencyError::InvalidExchangeRate)
+        ));
+    }
+
+    #[test]
+    fn test_money_parse_and_format() {
+        let jpy = Currency::from_str("JPY").unwrap();
+        let kwd = Currency::from_str("KWD").unwrap();
+
+        assert_eq!(Money::parse("12.50", Currency::default()), Ok(usd(1250)));
+        assert_eq!(Money::parse("12", Currency::default()), Ok(usd(1200)));
+        assert_eq!(Money::parse("-0.05", Currency::default()), Ok(usd(-5)));
+        assert_eq!(Money::parse("3.100", Currency::default()), Ok(usd(310)));
+        assert_eq!(Money::parse("1200", jpy.clone()).unwrap().minor(), 1200);
+        assert_eq!(Money::parse("1.5", kwd.clone()).unwrap().minor(), 1500);
+
+        assert!(matches!(
+            Money::parse("12.505", Currency::default()),
+            Err(MoneyError::TooManyDecimalPlaces { minor_units: 2, .. })
+        ));
+        assert!(matches!(
+            Money::parse("1.5", jpy.clone()),
+            Err(MoneyError::TooManyDecimalPlaces { minor_units: 0, .. })
+        ));
+        for invalid in ["", "12,50", ".5", "12.", "1e3", "--1", "12.5.0"] {
+            assert!(
+                matches!(
+                    Money::parse(invalid, Currency::default()),
+                    Err(MoneyError::InvalidAmount(_))
+                ),
+                "{invalid}"
+            );
+        }
+        assert_eq!(
+            Money::parse("92233720368547758.08", Currency::default()),
+            Err(MoneyError::Overflow)
+        );
+
+        assert_eq!(usd(1250).to_string(), "12.50");
+        assert_eq!(usd(-5).to_string(), "-0.05");
+        assert_eq!(usd(i64::MIN).to_string(), "-92233720368547758.08");
+        assert_eq!(Money::new(1200, jpy).to_string(), "1200");
+        assert_eq!(Money::new(1500, kwd).to_string(), "1.500");
+    }
+
+    #[test]
+    fn test_money_checked_arithmetic() {
+        let eur = Currency::from_str("EUR").unwrap();
+
+        assert_eq!(usd(1250).checked_add(&usd(-250)), Ok(usd(1000)));
+        assert_eq!(usd(1000).checked_sub(&usd(1250)), Ok(usd(-250)));
+        assert_eq!(
+            usd(i64::MAX).checked_add(&usd(1)),
+            Err(MoneyError::Overflow)
+        );
+        assert_eq!(usd(i64::MIN).checked_neg(), Err(MoneyError::Overflow));
+        assert!(matches!(
+            usd(100).checked_add(&Money::new(100, eur)),
+            Err(MoneyError::CurrencyMismatch(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_money_convert() {
+        let jpy = Currency::from_str("JPY").unwrap();
+        let kwd = Currency::from_str("KWD").unwrap();
+
+        assert_eq!(
+            usd(1000).convert(150.0, jpy.clone()),
+            Ok(Money::new(1500, jpy.clone()))
+        );
+        assert_eq!(
+            Money::new(1500, jpy.clone()).convert(1.0 / 150.0, Currency::default()),
+            Ok(usd(1000))
+        );
+        assert_eq!(
+            usd(1000).convert(0.3, kwd.clone()),
+            Ok(Money::new(3000, kwd))
+        );
+        assert_eq!(usd(i64::MAX).convert(150.0, jpy), Err(MoneyError::Overflow));
+    }
+
+    #[test]
+    fn test_validation_reports_every_field() {
+        let result = Record::new(1, "Gift".into(), usd(-100), None, None);
+        let Err(e) = result else {
+            panic!("record must be invalid");
+        };
+        let fields: Vec<_> = e.field_errors().into_iter().map(|(f, _)| f).collect();
+        assert_eq!(fields, vec!["transaction_type", "amount"]);
+
+        let result = Record::new_transfer(1, 1, usd(100), Some(usd(0)), None, None);
+        let fields: Vec<_> = result
+            .unwrap_err()
+            .field_errors()
+            .into_iter()
+            .map(|(f, _)| f)
+            .collect();
+        assert_eq!(fields, vec!["destination_account_id", "received_amount"]);
+
+        // A single failure is reported as itself.
+        assert!(matches!(
+            Record::new(1, "Income".into(), usd(0), None, None),
+            Err(RecordError::AmountCannotBeLessOrEqualToZero)
+        ));
+
+        let result = Category::new(String::new(), Some(-1), None);
+        let Err(CategoryError::Multiple(errors)) = result else {
+            panic!("category must be invalid");
+        };
+        assert!(matches!(
+            errors[..],
+            [
+                CategoryError::InvalidCategoryName,
+                CategoryError::NegativeBudget
+            ]
+        ));
+
+        let result = Recurrence::new(RecurrenceUnit::Day, 0, Some(1));
+        let fields: Vec<_> = result
+            .unwrap_err()
+            .field_errors()
+            .into_iter()
+            .map(|(f, _)| f)
+            .collect();
+        assert_eq!(fields, vec!["interval", "day_of_month"]);
     }
 }
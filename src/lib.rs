@@ -1,4 +1,6 @@
+pub mod config;
 pub mod domain;
 pub mod repository;
+pub mod scheduler;
 pub mod service;
 pub mod transport;
@@ -1,18 +1,31 @@
+use std::sync::Arc;
+
 use budget_api::{
+    config::Config,
     repository::{SqliteBudgetRepo, migrations},
+    scheduler,
     service::budget::BudgetServiceImpl,
     transport::router,
 };
-use sqlx::SqlitePool;
+use sqlx::sqlite::SqlitePoolOptions;
 
 #[tokio::main]
 async fn main() {
-    // let options = SqliteConnectOptions::new()
-    // .filename("budget.db")
-    // .create_if_missing(true);
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("invalid configuration: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    tracing_subscriber::fmt()
+        .with_max_level(config.log.level().expect("log level is validated"))
+        .init();
 
-    // let pool = SqlitePool::connect_with(options)
-    let pool = SqlitePool::connect("file::memory:?cache=shared")
+    let pool = SqlitePoolOptions::new()
+        .max_connections(config.database.max_connections)
+        .connect_with(config.database.connect_options())
         .await
         .expect("cannot connect to sqlite");
 
@@ -21,9 +34,19 @@ async fn main() {
     let repo = SqliteBudgetRepo::new(pool);
     let svc = BudgetServiceImpl::new(repo);
 
-    let listener = tokio::net::TcpListener::bind("0.0.0.0:4000")
+    if config.scheduler.enabled {
+        scheduler::spawn(
+            Arc::new(svc.clone()),
+            config.scheduler.interval(),
+            config.trash.retention(),
+        );
+    }
+
+    let listener = tokio::net::TcpListener::bind(config.server.bind)
         .await
         .expect("cannot bind to addr");
 
+    tracing::info!("listening on {}", config.server.bind);
+
     axum::serve(listener, router::new(svc)).await.unwrap();
 }
@@ -1,101 +1,145 @@
 use async_trait::async_trait;
+use sqlx::types::chrono::Local;
 
 use crate::{
-    domain::{Result, models::Account},
+    domain::{
+        Result,
+        models::{Account, Cursor, CursorKey, Page},
+    },
     repository::{
         SqliteBudgetRepo,
         dto::{AccountDTO, ReturnedId},
+        errors::not_found,
+        pages::{Keyset, fetch_page},
     },
-    service::budget::AccountRepository,
+    service::budget::{AccountRepository, PageCmd},
 };
 
 #[async_trait]
 impl AccountRepository for SqliteBudgetRepo {
-    async fn list_accounts(&self) -> Result<Vec<Account>> {
-        let mut conn = self.pool.acquire().await?;
+    async fn list_accounts(&self, user_id: i64) -> Result<Vec<Account>> {
+        let page = self
+            .list_accounts_page(user_id, &PageCmd::default())
+            .await?;
 
-        let result = sqlx::query_as::<_, AccountDTO>(
-            r#"
-            SELECT account_id,name,current_balance, name, account_type
-            FROM account
-            "#,
-        )
-        .fetch_all(&mut *conn)
-        .await?;
-
-        let result: Vec<Account> = result.into_iter().map(Account::from).collect();
+        Ok(page.items)
+    }
 
-        Ok(result)
+    async fn list_accounts_page(&self, user_id: i64, page: &PageCmd) -> Result<Page<Account>> {
+        let mut conn = self.acquire().await?;
+
+        fetch_page::<AccountDTO, Account>(
+            &mut conn,
+            &Keyset::by_id("account_id"),
+            page,
+            |query| {
+                query.push(
+                    r#"
+                    SELECT account_id, name, current_balance, account_type, currency
+                    FROM account
+                    WHERE deleted_at IS NULL AND user_id = "#,
+                );
+                query.push_bind(user_id);
+            },
+            |account| Cursor::new(CursorKey::Id, account.id),
+        )
+        .await
     }
 
-    async fn create_account(&self, acc: Account) -> Result<i64> {
-        let mut conn = self.pool.acquire().await?;
+    async fn create_account(&self, user_id: i64, acc: Account) -> Result<i64> {
+        let mut conn = self.acquire().await?;
 
         let result = sqlx::query_as::<_, ReturnedId>(
             r#"
             INSERT INTO account
-            (name,current_balance,account_type)
-            VALUES(?,?,?)
+            (user_id,name,current_balance,account_type,currency)
+            VALUES(?,?,?,?,?)
             RETURNING account_id as id;
             "#,
         )
+        .bind(user_id)
         .bind(acc.name)
         .bind(acc.balance)
         .bind(acc.account_type.to_string())
+        .bind(acc.currency.code())
         .fetch_one(&mut *conn)
         .await?;
 
         Ok(result.id)
     }
 
-    async fn get_account_by_id(&self, id: i64) -> Result<Account> {
-        let mut conn = self.pool.acquire().await?;
+    async fn get_account_by_id(&self, user_id: i64, id: i64) -> Result<Account> {
+        let mut conn = self.acquire().await?;
 
         let result = sqlx::query_as::<_, AccountDTO>(
             r#"
-            SELECT account_id,name,current_balance, name, account_type
+            SELECT account_id, name, current_balance, account_type, currency
             FROM account
-            WHERE account_id = ?
+            WHERE account_id = ? AND user_id = ? AND deleted_at IS NULL
             "#,
         )
         .bind(id)
+        .bind(user_id)
         .fetch_one(&mut *conn)
-        .await?;
+        .await
+        .map_err(not_found("account", id))?;
 
         Ok(result.into())
     }
 
-    async fn update_account(&self, acc: Account) -> Result<()> {
-        let mut conn = self.pool.acquire().await?;
+    async fn update_account(&self, user_id: i64, acc: Account) -> Result<()> {
+        let mut conn = self.acquire().await?;
 
         sqlx::query(
             r#"
             UPDATE account
                 SET name = ?,
                     account_type = ?
-            WHERE account_id = ?
+            WHERE account_id = ? AND user_id = ?
             "#,
         )
         .bind(acc.name)
         .bind(acc.account_type.to_string())
         .bind(acc.id)
+        .bind(user_id)
         .execute(&mut *conn)
         .await?;
 
         Ok(())
     }
 
-    async fn delete_account(&self, id: i64) -> Result<()> {
-        let mut conn = self.pool.acquire().await?;
+    async fn update_account_balance(&self, user_id: i64, id: i64, delta: i64) -> Result<()> {
+        let mut conn = self.acquire().await?;
 
         sqlx::query(
             r#"
-            DELETE 
-            FROM account
-            WHERE account_id = ?
+            UPDATE account
+                SET current_balance = current_balance + ?
+            WHERE account_id = ? AND user_id = ?
+            "#,
+        )
+        .bind(delta)
+        .bind(id)
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_account(&self, user_id: i64, id: i64) -> Result<()> {
+        let mut conn = self.acquire().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE account
+                SET deleted_at = ?
+            WHERE account_id = ? AND user_id = ? AND deleted_at IS NULL
             "#,
         )
+        .bind(Local::now())
         .bind(id)
+        .bind(user_id)
         .execute(&mut *conn)
         .await?;
 
@@ -105,9 +149,11 @@ impl AccountRepository for SqliteBudgetRepo {
 
 #[cfg(test)]
 mod test {
-    use sqlx::types::chrono::NaiveDate;
-
-    use crate::repository::test::test_db;
+    use crate::{
+        domain::models::{Currency, User},
+        repository::test::test_db,
+        service::budget::{RecordRepository, UserRepository},
+    };
 
     use super::*;
 
@@ -116,21 +162,61 @@ mod test {
         let fixture = include_str!("./fixtures/fixture.sql");
         let repo = test_db(Some(fixture)).await;
 
-        let mut account = Account::new("test account".into(), 0, "Cash".into()).unwrap();
+        let mut account =
+            Account::new("test account".into(), 0, "Cash".into(), Currency::default()).unwrap();
         account.id = 1;
         let list = vec![account];
-        let result = repo.list_accounts().await;
+        let result = repo.list_accounts(1).await;
         assert!(result.is_ok(), "{}", result.err().unwrap());
         assert_eq!(result.unwrap(), list)
     }
 
+    #[tokio::test]
+    async fn test_list_accounts_page() {
+        let fixture = include_str!("./fixtures/fixture.sql");
+        let repo = test_db(Some(fixture)).await;
+        for name in ["second", "third"] {
+            let account = Account::new(name.into(), 0, "Cash".into(), Currency::default()).unwrap();
+            repo.create_account(1, account).await.unwrap();
+        }
+
+        let page = PageCmd {
+            limit: Some(2),
+            after: None,
+            include_total: true,
+        };
+        let first = repo.list_accounts_page(1, &page).await.unwrap();
+        assert_eq!(first.items.len(), 2);
+        assert_eq!(first.total_count, Some(3));
+        assert_eq!(
+            first.next_cursor,
+            Some(Cursor::new(CursorKey::Id, first.items[1].id))
+        );
+
+        let page = PageCmd {
+            after: first.next_cursor,
+            ..page
+        };
+        let second = repo.list_accounts_page(1, &page).await.unwrap();
+        assert_eq!(second.items.len(), 1);
+        assert_eq!(second.items[0].name, "third");
+        assert_eq!(second.next_cursor, None);
+
+        let page = PageCmd {
+            after: Some(Cursor::new(CursorKey::Name(Some("second".into())), 1)),
+            ..page
+        };
+        assert!(repo.list_accounts_page(1, &page).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_create_category() {
         let fixture = include_str!("./fixtures/fixture.sql");
         let repo = test_db(Some(fixture)).await;
 
-        let account = Account::new("test account".into(), 0, "Cash".into()).unwrap();
-        let result = repo.create_account(account).await;
+        let account =
+            Account::new("test account".into(), 0, "Cash".into(), Currency::default()).unwrap();
+        let result = repo.create_account(1, account).await;
         assert!(result.is_ok(), "{}", result.err().unwrap());
         assert!(result.unwrap() > 0);
     }
@@ -140,9 +226,13 @@ mod test {
         let fixture = include_str!("./fixtures/fixture.sql");
         let repo = test_db(Some(fixture)).await;
 
-        let mut account = Account::new("test account".into(), 0, "Cash".into()).unwrap();
+        let mut account =
+            Account::new("test account".into(), 0, "Cash".into(), Currency::default()).unwrap();
         account.id = 1;
-        let found_account = repo.get_account_by_id(1).await.expect("must find category");
+        let found_account = repo
+            .get_account_by_id(1, 1)
+            .await
+            .expect("must find category");
 
         assert_eq!(account, found_account);
     }
@@ -152,25 +242,63 @@ mod test {
         let fixture = include_str!("./fixtures/fixture.sql");
         let repo = test_db(Some(fixture)).await;
 
-        let mut account = Account::new("test account".into(), 0, "Cash".into()).unwrap();
+        let mut account =
+            Account::new("test account".into(), 0, "Cash".into(), Currency::default()).unwrap();
         account.id = 1;
-        let result = repo.update_account(account.clone()).await;
+        let result = repo.update_account(1, account.clone()).await;
         assert!(result.is_ok(), "{}", result.err().unwrap());
 
-        let updated_account = repo.get_account_by_id(1).await.expect("must find category");
+        let updated_account = repo
+            .get_account_by_id(1, 1)
+            .await
+            .expect("must find category");
 
         assert_eq!(account, updated_account);
     }
 
+    #[tokio::test]
+    async fn test_accounts_are_isolated_by_user() {
+        let fixture = include_str!("./fixtures/fixture.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let user = User::new("other user".into()).unwrap();
+        let other_user_id = repo.create_user(user).await.unwrap();
+
+        let result = repo.list_accounts(other_user_id).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+        assert!(result.unwrap().is_empty());
+
+        let result = repo.get_account_by_id(other_user_id, 1).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_update_account_balance() {
+        let fixture = include_str!("./fixtures/fixture.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let result = repo.update_account_balance(1, 1, 250).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+        repo.update_account_balance(1, 1, -100).await.unwrap();
+
+        let account = repo
+            .get_account_by_id(1, 1)
+            .await
+            .expect("must find account");
+        assert_eq!(account.balance, 150);
+    }
+
     #[tokio::test]
     async fn test_delete_category() {
         let fixture = include_str!("./fixtures/fixture.sql");
         let repo = test_db(Some(fixture)).await;
 
-        let result = repo.delete_account(1).await;
+        repo.delete_record(1, 1).await.expect("must delete record");
+
+        let result = repo.delete_account(1, 1).await;
         assert!(result.is_ok(), "{}", result.err().unwrap());
 
-        let result = repo.get_account_by_id(1).await;
+        let result = repo.get_account_by_id(1, 1).await;
         assert!(result.is_err(), "{}", result.err().unwrap());
     }
 }
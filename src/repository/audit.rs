@@ -0,0 +1,203 @@
+use async_trait::async_trait;
+use sqlx::{QueryBuilder, Sqlite};
+
+use crate::{
+    domain::{
+        Result,
+        models::{AuditEntry, Cursor, CursorKey, Page},
+    },
+    repository::{
+        SqliteBudgetRepo,
+        dto::{AuditEntryDTO, ReturnedId},
+        pages::{Keyset, SortColumn, fetch_page},
+    },
+    service::{
+        audit::ListAuditEntriesCmd,
+        budget::{AuditRepository, PageCmd},
+    },
+};
+
+#[async_trait]
+impl AuditRepository for SqliteBudgetRepo {
+    async fn append_audit_entry(&self, entry: AuditEntry) -> Result<i64> {
+        let mut conn = self.acquire().await?;
+
+        let result = sqlx::query_as::<_, ReturnedId>(
+            r#"
+            INSERT INTO audit_log
+            (user_id,actor_id,entity_type,entity_id,action,before,after,created_at)
+            VALUES(?,?,?,?,?,?,?,?)
+            RETURNING audit_log_id as id;
+            "#,
+        )
+        .bind(entry.user_id)
+        .bind(entry.actor_id)
+        .bind(entry.entity_type.to_string())
+        .bind(entry.entity_id)
+        .bind(entry.action.to_string())
+        .bind(entry.before.map(|before| before.to_string()))
+        .bind(entry.after.map(|after| after.to_string()))
+        .bind(entry.created_at)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(result.id)
+    }
+
+    async fn list_audit_entries(
+        &self,
+        user_id: i64,
+        cmd: &ListAuditEntriesCmd,
+        page: &PageCmd,
+    ) -> Result<Page<AuditEntry>> {
+        let mut conn = self.acquire().await?;
+        let keyset = Keyset {
+            column: SortColumn::Date("created_at"),
+            id: "audit_log_id",
+            order: cmd.order,
+        };
+
+        fetch_page::<AuditEntryDTO, AuditEntry>(
+            &mut conn,
+            &keyset,
+            page,
+            |query| {
+                query.push(
+                    r#"
+                    SELECT
+                        audit_log_id,
+                        user_id,
+                        actor_id,
+                        entity_type,
+                        entity_id,
+                        action,
+                        before,
+                        after,
+                        created_at
+                    "#,
+                );
+                push_audit_filters(query, user_id, cmd);
+            },
+            |entry| Cursor::new(CursorKey::Date(entry.created_at), entry.id),
+        )
+        .await
+    }
+}
+
+/// Pushes the table and conditions of the listed entries.
+fn push_audit_filters(
+    query: &mut QueryBuilder<'_, Sqlite>,
+    user_id: i64,
+    cmd: &ListAuditEntriesCmd,
+) {
+    query.push(" FROM audit_log WHERE user_id = ");
+    query.push_bind(user_id);
+
+    if let Some(entity_type) = &cmd.entity_type {
+        query.push(" AND entity_type = ");
+        query.push_bind(entity_type.to_string());
+    }
+
+    if let Some(entity_id) = cmd.entity_id {
+        query.push(" AND entity_id = ");
+        query.push_bind(entity_id);
+    }
+
+    if let Some(action) = cmd.action {
+        query.push(" AND action = ");
+        query.push_bind(action.to_string());
+    }
+
+    if let Some(from) = cmd.from {
+        query.push(" AND julianday(created_at) >= julianday(");
+        query.push_bind(from);
+        query.push(")");
+    }
+
+    if let Some(to) = cmd.to {
+        query.push(" AND julianday(created_at) < julianday(");
+        query.push_bind(to);
+        query.push(")");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        domain::models::{AuditAction, AuditEntity, Tag},
+        repository::test::test_db,
+        service::records::SortOrder,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_list_audit_entries() {
+        let fixture = include_str!("./fixtures/records.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let before = Tag {
+            id: 1,
+            name: "vacation".into(),
+        };
+        let after = Tag {
+            id: 1,
+            name: "holiday".into(),
+        };
+        let entries = [
+            AuditEntry::new(1, Some(1), AuditAction::Create, None, Some(&before)),
+            AuditEntry::new(1, Some(1), AuditAction::Update, Some(&before), Some(&after)),
+            AuditEntry::new(2, Some(2), AuditAction::Delete, Some(&after), None),
+        ];
+        for entry in entries {
+            repo.append_audit_entry(entry)
+                .await
+                .expect("must append entry");
+        }
+
+        let all = PageCmd::default();
+        let result = repo
+            .list_audit_entries(1, &ListAuditEntriesCmd::default(), &all)
+            .await
+            .unwrap()
+            .items;
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].action, AuditAction::Update);
+        assert_eq!(result[0].entity_type, AuditEntity::Tag);
+        assert_eq!(result[0].before.as_ref().unwrap()["name"], "vacation");
+        assert_eq!(result[0].after.as_ref().unwrap()["name"], "holiday");
+        assert_eq!(result[1].before, None);
+
+        let cmd = ListAuditEntriesCmd {
+            action: Some(AuditAction::Create),
+            ..Default::default()
+        };
+        let result = repo.list_audit_entries(1, &cmd, &all).await.unwrap();
+        assert_eq!(result.items.len(), 1);
+
+        let cmd = ListAuditEntriesCmd {
+            order: SortOrder::Asc,
+            ..Default::default()
+        };
+        let page = PageCmd {
+            limit: Some(1),
+            ..Default::default()
+        };
+        let first = repo.list_audit_entries(1, &cmd, &page).await.unwrap();
+        assert_eq!(first.items[0].action, AuditAction::Create);
+        let page = PageCmd {
+            after: first.next_cursor,
+            ..page
+        };
+        let second = repo.list_audit_entries(1, &cmd, &page).await.unwrap();
+        assert_eq!(second.items[0].action, AuditAction::Update);
+        assert_eq!(second.next_cursor, None);
+
+        let cmd = ListAuditEntriesCmd {
+            entity_type: Some(AuditEntity::Record),
+            ..Default::default()
+        };
+        let result = repo.list_audit_entries(1, &cmd, &all).await.unwrap();
+        assert!(result.items.is_empty());
+    }
+}
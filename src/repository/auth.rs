@@ -0,0 +1,257 @@
+use async_trait::async_trait;
+use sqlx::types::chrono::Local;
+
+use crate::{
+    domain::{
+        Result,
+        models::{Cursor, CursorKey, Page, Token, TokenKind},
+    },
+    repository::{
+        SqliteBudgetRepo,
+        dto::{PasswordHashDTO, ReturnedId, TokenDTO},
+        errors::not_found,
+        pages::{Keyset, fetch_page},
+    },
+    service::budget::{AuthRepository, PageCmd},
+};
+
+#[async_trait]
+impl AuthRepository for SqliteBudgetRepo {
+    async fn get_password_hash(&self, user_id: i64) -> Result<Option<String>> {
+        let mut conn = self.acquire().await?;
+
+        let result = sqlx::query_as::<_, PasswordHashDTO>(
+            r#"
+            SELECT password_hash
+            FROM user
+            WHERE user_id = ?
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(result.password_hash)
+    }
+
+    async fn set_password_hash(&self, user_id: i64, password_hash: String) -> Result<()> {
+        let mut conn = self.acquire().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE user
+                SET password_hash = ?
+            WHERE user_id = ?
+            "#,
+        )
+        .bind(password_hash)
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn create_token(&self, token: Token, token_hash: String) -> Result<i64> {
+        let mut conn = self.acquire().await?;
+
+        let result = sqlx::query_as::<_, ReturnedId>(
+            r#"
+            INSERT INTO token
+            (user_id,token_hash,kind,name,created_at,expires_at)
+            VALUES(?,?,?,?,?,?)
+            RETURNING token_id as id;
+            "#,
+        )
+        .bind(token.user_id)
+        .bind(token_hash)
+        .bind(token.kind.to_string())
+        .bind(token.name)
+        .bind(token.created_at)
+        .bind(token.expires_at)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(result.id)
+    }
+
+    async fn get_token_by_id(&self, user_id: i64, id: i64) -> Result<Token> {
+        let mut conn = self.acquire().await?;
+
+        let result = sqlx::query_as::<_, TokenDTO>(
+            r#"
+            SELECT token_id, user_id, kind, name, created_at, expires_at
+            FROM token
+            WHERE token_id = ? AND user_id = ?
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(not_found("token", id))?;
+
+        Ok(result.into())
+    }
+
+    async fn get_token_by_hash(&self, token_hash: &str) -> Result<Token> {
+        let mut conn = self.acquire().await?;
+
+        let result = sqlx::query_as::<_, TokenDTO>(
+            r#"
+            SELECT token_id, user_id, kind, name, created_at, expires_at
+            FROM token
+            WHERE token_hash = ?
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(result.into())
+    }
+
+    async fn list_tokens(
+        &self,
+        user_id: i64,
+        kind: TokenKind,
+        page: &PageCmd,
+    ) -> Result<Page<Token>> {
+        let mut conn = self.acquire().await?;
+
+        fetch_page::<TokenDTO, Token>(
+            &mut conn,
+            &Keyset::by_id("token_id"),
+            page,
+            |query| {
+                query.push(
+                    r#"
+                    SELECT token_id, user_id, kind, name, created_at, expires_at
+                    FROM token
+                    WHERE kind = "#,
+                );
+                query.push_bind(kind.to_string());
+                query.push(" AND user_id = ");
+                query.push_bind(user_id);
+            },
+            |token| Cursor::new(CursorKey::Id, token.id),
+        )
+        .await
+    }
+
+    async fn delete_token(&self, user_id: i64, id: i64) -> Result<()> {
+        let mut conn = self.acquire().await?;
+
+        sqlx::query(
+            r#"
+            DELETE
+            FROM token
+            WHERE token_id = ? AND user_id = ?
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_expired_tokens(&self) -> Result<()> {
+        let mut conn = self.acquire().await?;
+
+        sqlx::query(
+            r#"
+            DELETE
+            FROM token
+            WHERE expires_at IS NOT NULL AND expires_at <= ?
+            "#,
+        )
+        .bind(Local::now())
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::repository::test::test_db;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_password_hash() {
+        let fixture = include_str!("./fixtures/fixture.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let result = repo.get_password_hash(1).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+        assert_eq!(result.unwrap(), None);
+
+        let result = repo.set_password_hash(1, "hash".into()).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        let hash = repo.get_password_hash(1).await.unwrap();
+        assert_eq!(hash, Some("hash".into()));
+    }
+
+    #[tokio::test]
+    async fn test_create_token() {
+        let fixture = include_str!("./fixtures/fixture.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let token = Token::new(1, TokenKind::Api, Some("script".into()), None);
+        let result = repo.create_token(token, "digest".into()).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        let token = repo
+            .get_token_by_hash("digest")
+            .await
+            .expect("must find token");
+        assert_eq!(token.id, result.unwrap());
+        assert_eq!(token.kind, TokenKind::Api);
+
+        let tokens = repo
+            .list_tokens(1, TokenKind::Api, &PageCmd::default())
+            .await
+            .unwrap();
+        assert_eq!(tokens.items, vec![token]);
+    }
+
+    #[tokio::test]
+    async fn test_delete_token() {
+        let fixture = include_str!("./fixtures/fixture.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let token = Token::new(1, TokenKind::Api, None, None);
+        let id = repo.create_token(token, "digest".into()).await.unwrap();
+
+        let result = repo.delete_token(1, id).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        let result = repo.get_token_by_hash("digest").await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_delete_expired_tokens() {
+        let fixture = include_str!("./fixtures/fixture.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let expired = Local::now() - Duration::from_secs(60 * 60);
+        let token = Token::new(1, TokenKind::Session, None, Some(expired));
+        repo.create_token(token, "expired".into()).await.unwrap();
+        let token = Token::new(1, TokenKind::Api, None, None);
+        repo.create_token(token, "valid".into()).await.unwrap();
+
+        let result = repo.delete_expired_tokens().await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        assert!(repo.get_token_by_hash("expired").await.is_err());
+        assert!(repo.get_token_by_hash("valid").await.is_ok());
+    }
+}
@@ -1,98 +1,123 @@
 use async_trait::async_trait;
+use sqlx::types::chrono::Local;
 
 use crate::domain::Result;
-use crate::domain::models::Category;
+use crate::domain::models::{Category, Cursor, CursorKey, Page};
 use crate::repository::SqliteBudgetRepo;
 use crate::repository::dto::{CategoryDTO, ReturnedId};
-use crate::service::budget::CategoryRepository;
+use crate::repository::errors::{missing_reference, not_found};
+use crate::repository::pages::{Keyset, fetch_page};
+use crate::service::budget::{CategoryRepository, PageCmd};
 
 #[async_trait]
 impl CategoryRepository for SqliteBudgetRepo {
-    async fn list_categories(&self) -> Result<Vec<Category>> {
-        let mut conn = self.pool.acquire().await?;
+    async fn list_categories(&self, user_id: i64) -> Result<Vec<Category>> {
+        let page = self
+            .list_categories_page(user_id, &PageCmd::default())
+            .await?;
 
-        let result = sqlx::query_as::<_, CategoryDTO>(
-            r#"
-            SELECT category_id,name,budget, parent_id
-            FROM category
-            "#,
-        )
-        .fetch_all(&mut *conn)
-        .await?;
-
-        let result: Vec<Category> = result.into_iter().map(Category::from).collect();
+        Ok(page.items)
+    }
 
-        Ok(result)
+    async fn list_categories_page(&self, user_id: i64, page: &PageCmd) -> Result<Page<Category>> {
+        let mut conn = self.acquire().await?;
+
+        fetch_page::<CategoryDTO, Category>(
+            &mut conn,
+            &Keyset::by_id("category_id"),
+            page,
+            |query| {
+                query.push(
+                    r#"
+                    SELECT category_id,name,budget, parent_id
+                    FROM category
+                    WHERE deleted_at IS NULL AND user_id = "#,
+                );
+                query.push_bind(user_id);
+            },
+            |category| Cursor::new(CursorKey::Id, category.id),
+        )
+        .await
     }
 
-    async fn create_category(&self, category: Category) -> Result<i64> {
-        let mut conn = self.pool.acquire().await?;
+    async fn create_category(&self, user_id: i64, category: Category) -> Result<i64> {
+        let mut conn = self.acquire().await?;
 
         let result = sqlx::query_as::<_, ReturnedId>(
             r#"
             INSERT INTO category
-            (name,budget,parent_id)
-            VALUES(?,?,?)
+            (user_id,name,budget,parent_id)
+            VALUES(?,?,?,?)
             RETURNING category_id as id;
             "#,
         )
+        .bind(user_id)
         .bind(category.name)
         .bind(category.budget)
         .bind(category.parent_id)
         .fetch_one(&mut *conn)
-        .await?;
+        .await
+        .map_err(missing_reference("parent category"))?;
 
         Ok(result.id)
     }
 
-    async fn get_category_by_id(&self, id: i64) -> Result<Category> {
-        let mut conn = self.pool.acquire().await?;
+    async fn get_category_by_id(&self, user_id: i64, id: i64) -> Result<Category> {
+        let mut conn = self.acquire().await?;
 
         let result = sqlx::query_as::<_, CategoryDTO>(
             r#"
             SELECT category_id,name,budget, parent_id
             FROM category
-            where category_id = ?
+            WHERE category_id = ? AND user_id = ? AND deleted_at IS NULL
             "#,
         )
         .bind(id)
+        .bind(user_id)
         .fetch_one(&mut *conn)
-        .await?;
+        .await
+        .map_err(not_found("category", id))?;
 
         Ok(result.into())
     }
 
-    async fn update_category(&self, category: Category) -> Result<()> {
-        let mut conn = self.pool.acquire().await?;
+    async fn update_category(&self, user_id: i64, category: Category) -> Result<()> {
+        let mut conn = self.acquire().await?;
 
         sqlx::query(
             r#"
             UPDATE category
             SET name = ?,
-                budget = ?
-            WHERE category_id = ?
+                budget = ?,
+                parent_id = ?
+            WHERE category_id = ? AND user_id = ?
             "#,
         )
         .bind(category.name)
         .bind(category.budget)
+        .bind(category.parent_id)
         .bind(category.id)
+        .bind(user_id)
         .execute(&mut *conn)
-        .await?;
+        .await
+        .map_err(missing_reference("parent category"))?;
 
         Ok(())
     }
 
-    async fn delete_category(&self, id: i64) -> Result<()> {
-        let mut conn = self.pool.acquire().await?;
+    async fn delete_category(&self, user_id: i64, id: i64) -> Result<()> {
+        let mut conn = self.acquire().await?;
 
         sqlx::query(
             r#"
-            DELETE 
-            FROM category
-            WHERE category_id = ?
+            UPDATE category
+                SET deleted_at = ?
+            WHERE category_id = ? AND user_id = ? AND deleted_at IS NULL
             "#,
         )
+        .bind(Local::now())
         .bind(id)
+        .bind(user_id)
         .execute(&mut *conn)
         .await?;
 
@@ -102,7 +127,7 @@ impl CategoryRepository for SqliteBudgetRepo {
 
 #[cfg(test)]
 mod test {
-    use crate::repository::test::test_db;
+    use crate::{domain::errors::BudgetServiceError, repository::test::test_db};
 
     use super::*;
 
@@ -114,7 +139,7 @@ mod test {
         let mut category = Category::new("test category".into(), None, None).unwrap();
         category.id = 1;
         let list = vec![category];
-        let result = repo.list_categories().await;
+        let result = repo.list_categories(1).await;
         assert!(result.is_ok(), "{}", result.err().unwrap());
         assert_eq!(result.unwrap(), list)
     }
@@ -125,7 +150,7 @@ mod test {
         let repo = test_db(Some(fixture)).await;
 
         let category = Category::new("test".into(), Some(1000), None).unwrap();
-        let result = repo.create_category(category).await;
+        let result = repo.create_category(1, category).await;
         assert!(result.is_ok(), "{}", result.err().unwrap());
         assert!(result.unwrap() > 0);
     }
@@ -138,11 +163,38 @@ mod test {
         let mut category = Category::new("test category".into(), None, None).unwrap();
         category.id = 1;
         let found_category = repo
-            .get_category_by_id(1)
+            .get_category_by_id(1, 1)
             .await
             .expect("must find category");
 
         assert_eq!(category, found_category);
+
+        let result = repo.get_category_by_id(1, 99).await;
+        assert!(matches!(
+            result,
+            Err(BudgetServiceError::EntityNotFoundError {
+                entity: "category",
+                id: 99
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_constraint_violations() {
+        let fixture = include_str!("./fixtures/fixture.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let category = Category::new("test category".into(), None, None).unwrap();
+        let result = repo.create_category(1, category).await;
+        assert!(matches!(result, Err(BudgetServiceError::ConflictError(_))));
+
+        let category = Category::new("orphan".into(), None, Some(99)).unwrap();
+        let result = repo.create_category(1, category).await;
+        assert!(matches!(
+            &result,
+            Err(BudgetServiceError::InvalidReferenceError(message))
+                if message == "parent category does not exist"
+        ));
     }
 
     #[tokio::test]
@@ -152,11 +204,11 @@ mod test {
 
         let mut category = Category::new("test category".into(), Some(1000), None).unwrap();
         category.id = 1;
-        let result = repo.update_category(category.clone()).await;
+        let result = repo.update_category(1, category.clone()).await;
         assert!(result.is_ok(), "{}", result.err().unwrap());
 
         let updated_category = repo
-            .get_category_by_id(1)
+            .get_category_by_id(1, 1)
             .await
             .expect("must find category");
 
@@ -168,10 +220,10 @@ mod test {
         let fixture = include_str!("./fixtures/fixture.sql");
         let repo = test_db(Some(fixture)).await;
 
-        let result = repo.delete_category(1).await;
+        let result = repo.delete_category(1, 1).await;
         assert!(result.is_ok(), "{}", result.err().unwrap());
 
-        let result = repo.get_category_by_id(1).await;
+        let result = repo.get_category_by_id(1, 1).await;
         assert!(result.is_err(), "{}", result.err().unwrap());
     }
 }
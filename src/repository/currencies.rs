@@ -0,0 +1,187 @@
+use async_trait::async_trait;
+use sqlx::types::chrono::NaiveDate;
+
+use crate::{
+    domain::{
+        Result,
+        models::{Currency, Cursor, CursorKey, ExchangeRate, Page},
+    },
+    repository::{
+        SqliteBudgetRepo,
+        dto::{ExchangeRateDTO, ReturnedId},
+        errors::not_found,
+        pages::{Keyset, SortColumn, fetch_page},
+    },
+    service::{
+        budget::{ExchangeRateRepository, PageCmd},
+        currencies::ListExchangeRatesCmd,
+        records::SortOrder,
+    },
+};
+
+#[async_trait]
+impl ExchangeRateRepository for SqliteBudgetRepo {
+    async fn list_exchange_rates(
+        &self,
+        user_id: i64,
+        cmd: &ListExchangeRatesCmd,
+        page: &PageCmd,
+    ) -> Result<Page<ExchangeRate>> {
+        let mut conn = self.acquire().await?;
+        let keyset = Keyset {
+            column: SortColumn::Day("valid_on"),
+            id: "exchange_rate_id",
+            order: SortOrder::Desc,
+        };
+
+        fetch_page::<ExchangeRateDTO, ExchangeRate>(
+            &mut conn,
+            &keyset,
+            page,
+            |query| {
+                query.push(
+                    r#"
+                    SELECT exchange_rate_id, base_currency, quote_currency, rate, valid_on
+                    FROM exchange_rate
+                    WHERE user_id = "#,
+                );
+                query.push_bind(user_id);
+
+                if let Some(base) = &cmd.base {
+                    query.push(" AND base_currency = ");
+                    query.push_bind(base.code().to_owned());
+                }
+
+                if let Some(quote) = &cmd.quote {
+                    query.push(" AND quote_currency = ");
+                    query.push_bind(quote.code().to_owned());
+                }
+            },
+            |rate| Cursor::new(CursorKey::Day(rate.valid_on), rate.id),
+        )
+        .await
+    }
+
+    async fn upsert_exchange_rate(&self, user_id: i64, rate: ExchangeRate) -> Result<i64> {
+        let mut conn = self.acquire().await?;
+
+        let result = sqlx::query_as::<_, ReturnedId>(
+            r#"
+            INSERT INTO exchange_rate
+            (user_id,base_currency,quote_currency,rate,valid_on)
+            VALUES(?,?,?,?,?)
+            ON CONFLICT (user_id, base_currency, quote_currency, valid_on)
+                DO UPDATE SET rate = excluded.rate
+            RETURNING exchange_rate_id as id;
+            "#,
+        )
+        .bind(user_id)
+        .bind(rate.base.code())
+        .bind(rate.quote.code())
+        .bind(rate.rate)
+        .bind(rate.valid_on)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(result.id)
+    }
+
+    async fn get_exchange_rate_by_id(&self, user_id: i64, id: i64) -> Result<ExchangeRate> {
+        let mut conn = self.acquire().await?;
+
+        let result = sqlx::query_as::<_, ExchangeRateDTO>(
+            r#"
+            SELECT exchange_rate_id, base_currency, quote_currency, rate, valid_on
+            FROM exchange_rate
+            WHERE exchange_rate_id = ? AND user_id = ?
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(not_found("exchange rate", id))?;
+
+        Ok(result.into())
+    }
+
+    async fn get_exchange_rate_on(
+        &self,
+        user_id: i64,
+        base: &Currency,
+        quote: &Currency,
+        valid_on: NaiveDate,
+    ) -> Result<Option<ExchangeRate>> {
+        let mut conn = self.acquire().await?;
+
+        let result = sqlx::query_as::<_, ExchangeRateDTO>(
+            r#"
+            SELECT exchange_rate_id, base_currency, quote_currency, rate, valid_on
+            FROM exchange_rate
+            WHERE user_id = ? AND base_currency = ? AND quote_currency = ? AND valid_on = ?
+            "#,
+        )
+        .bind(user_id)
+        .bind(base.code())
+        .bind(quote.code())
+        .bind(valid_on)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        Ok(result.map(ExchangeRate::from))
+    }
+
+    async fn delete_exchange_rate(&self, user_id: i64, id: i64) -> Result<()> {
+        let mut conn = self.acquire().await?;
+
+        sqlx::query(
+            r#"
+            DELETE
+            FROM exchange_rate
+            WHERE exchange_rate_id = ? AND user_id = ?
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn find_exchange_rate(
+        &self,
+        user_id: i64,
+        base: &Currency,
+        quote: &Currency,
+        on: NaiveDate,
+    ) -> Result<Option<f64>> {
+        let mut conn = self.acquire().await?;
+
+        let result = sqlx::query_scalar::<_, f64>(
+            r#"
+            SELECT rate FROM (
+                SELECT rate, valid_on
+                FROM exchange_rate
+                WHERE user_id = ?1 AND base_currency = ?2 AND quote_currency = ?3
+                    AND valid_on <= ?4
+                UNION ALL
+                SELECT 1.0 / rate, valid_on
+                FROM exchange_rate
+                WHERE user_id = ?1 AND base_currency = ?3 AND quote_currency = ?2
+                    AND valid_on <= ?4
+            )
+            ORDER BY valid_on DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(user_id)
+        .bind(base.code())
+        .bind(quote.code())
+        .bind(on)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        Ok(result)
+    }
+}
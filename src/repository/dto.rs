@@ -1,9 +1,13 @@
 use sqlx::{
     prelude::FromRow,
-    types::chrono::{DateTime, Local},
+    types::chrono::{DateTime, Local, NaiveDate},
 };
 
-use crate::domain::models::{self, Account, AccountType, Category, RecordType};
+use crate::domain::models::{
+    self, Account, AccountType, AuditAction, AuditEntity, AuditEntry, CashFlowBucket, Category,
+    Currency, Deleted, ExchangeRate, RecordType, Recurrence, RecurrenceUnit, RecurringRecord, Tag,
+    Token, TokenKind, User,
+};
 
 use std::str::FromStr;
 
@@ -27,6 +31,7 @@ pub struct AccountDTO {
     account_id: i64,
     name: String,
     account_type: String,
+    currency: String,
     current_balance: i64,
 }
 
@@ -37,6 +42,8 @@ impl From<AccountDTO> for Account {
             name: dto.name,
             account_type: AccountType::from_str(&dto.account_type)
                 .expect("cannot convert account type from database"),
+            currency: Currency::from_str(&dto.currency)
+                .expect("cannot convert account currency from database"),
             balance: dto.current_balance,
         }
     }
@@ -49,6 +56,15 @@ pub struct RecordDTO {
     amount: i64,
     description: Option<String>,
     record_type: String,
+    #[sqlx(default)]
+    transfer_account_id: Option<i64>,
+    #[sqlx(default)]
+    transfer_amount: Option<i64>,
+    currency: String,
+    #[sqlx(default)]
+    transfer_currency: Option<String>,
+    occurred_at: DateTime<Local>,
+    posted_at: Option<DateTime<Local>>,
     created_at: DateTime<Local>,
     updated_at: DateTime<Local>,
 }
@@ -71,10 +87,33 @@ impl From<FullRecordDTO> for models::Record {
                 .amount
                 .try_into()
                 .expect("cannot convert i64 to NonZeroI64"),
+            currency: Currency::from_str(&dto.record.currency)
+                .expect("cannot convert record currency from db"),
             description: dto.record.description,
             category: dto.category.into(),
+            splits: Vec::new(),
+            tags: Vec::new(),
+            transfer: dto
+                .record
+                .transfer_account_id
+                .map(|account_id| models::Transfer {
+                    account_id,
+                    amount: dto
+                        .record
+                        .transfer_amount
+                        .and_then(|amount| amount.try_into().ok())
+                        .expect("cannot convert transfer amount from db"),
+                    currency: dto
+                        .record
+                        .transfer_currency
+                        .as_deref()
+                        .and_then(|currency| Currency::from_str(currency).ok())
+                        .expect("cannot convert transfer currency from db"),
+                }),
             record_type: RecordType::from_str(&dto.record.record_type)
                 .expect("cannot convert transaction type from db"),
+            occurred_at: dto.record.occurred_at,
+            posted_at: dto.record.posted_at,
             created_at: dto.record.created_at,
             updated_at: dto.record.updated_at,
         }
@@ -126,3 +165,308 @@ impl From<OptionalCategoryDTO> for Option<Category> {
         })
     }
 }
+
+#[derive(FromRow, Debug)]
+pub struct RecordSplitDTO {
+    pub record_id: i64,
+    record_split_id: i64,
+    amount: i64,
+    note: Option<String>,
+    #[sqlx(flatten)]
+    category: CategoryDTO,
+}
+
+impl From<RecordSplitDTO> for models::RecordSplit {
+    fn from(dto: RecordSplitDTO) -> Self {
+        Self {
+            id: dto.record_split_id,
+            category: dto.category.into(),
+            amount: dto
+                .amount
+                .try_into()
+                .expect("cannot convert i64 to NonZeroI64"),
+            note: dto.note,
+        }
+    }
+}
+
+#[derive(FromRow, Debug)]
+pub struct UserDTO {
+    user_id: i64,
+    username: String,
+    reporting_currency: String,
+    created_at: DateTime<Local>,
+}
+
+impl From<UserDTO> for User {
+    fn from(dto: UserDTO) -> Self {
+        Self {
+            id: dto.user_id,
+            username: dto.username,
+            reporting_currency: Currency::from_str(&dto.reporting_currency)
+                .expect("cannot convert reporting currency from database"),
+            created_at: dto.created_at,
+        }
+    }
+}
+
+#[derive(FromRow, Debug)]
+pub struct PasswordHashDTO {
+    pub password_hash: Option<String>,
+}
+
+#[derive(FromRow, Debug)]
+pub struct TokenDTO {
+    token_id: i64,
+    user_id: i64,
+    kind: String,
+    name: Option<String>,
+    created_at: DateTime<Local>,
+    expires_at: Option<DateTime<Local>>,
+}
+
+impl From<TokenDTO> for Token {
+    fn from(dto: TokenDTO) -> Self {
+        Self {
+            id: dto.token_id,
+            user_id: dto.user_id,
+            kind: TokenKind::from_str(&dto.kind).expect("cannot convert token kind from database"),
+            name: dto.name,
+            created_at: dto.created_at,
+            expires_at: dto.expires_at,
+        }
+    }
+}
+
+#[derive(FromRow, Debug)]
+pub struct CategoryTotalDTO {
+    pub category_id: i64,
+    pub total: i64,
+}
+
+#[derive(FromRow, Debug)]
+pub struct RecurringRecordDTO {
+    recurring_record_id: i64,
+    user_id: i64,
+    account_id: i64,
+    record_type: String,
+    amount: i64,
+    description: Option<String>,
+    category_id: Option<i64>,
+    transfer_account_id: Option<i64>,
+    transfer_amount: Option<i64>,
+    currency: String,
+    transfer_currency: Option<String>,
+    recurrence_unit: String,
+    recurrence_interval: i64,
+    recurrence_day_of_month: Option<i64>,
+    starts_at: DateTime<Local>,
+    ends_at: Option<DateTime<Local>>,
+    occurrence_count: i64,
+    next_occurrence_at: Option<DateTime<Local>>,
+    created_at: DateTime<Local>,
+    updated_at: DateTime<Local>,
+}
+
+impl From<RecurringRecordDTO> for RecurringRecord {
+    fn from(dto: RecurringRecordDTO) -> Self {
+        Self {
+            id: dto.recurring_record_id,
+            user_id: dto.user_id,
+            account_id: dto.account_id,
+            record_type: RecordType::from_str(&dto.record_type)
+                .expect("cannot convert transaction type from db"),
+            amount: dto.amount,
+            currency: Currency::from_str(&dto.currency)
+                .expect("cannot convert recurring record currency from db"),
+            description: dto.description,
+            category_id: dto.category_id,
+            destination_account_id: dto.transfer_account_id,
+            received_amount: dto.transfer_amount,
+            destination_currency: dto.transfer_currency.map(|currency| {
+                Currency::from_str(&currency).expect("cannot convert transfer currency from db")
+            }),
+            recurrence: Recurrence {
+                unit: RecurrenceUnit::from_str(&dto.recurrence_unit)
+                    .expect("cannot convert recurrence unit from db"),
+                interval: dto
+                    .recurrence_interval
+                    .try_into()
+                    .expect("cannot convert recurrence interval from db"),
+                day_of_month: dto
+                    .recurrence_day_of_month
+                    .map(|day| day.try_into().expect("cannot convert day of month from db")),
+            },
+            starts_at: dto.starts_at,
+            ends_at: dto.ends_at,
+            occurrence_count: dto
+                .occurrence_count
+                .try_into()
+                .expect("cannot convert occurrence count from db"),
+            next_occurrence_at: dto.next_occurrence_at,
+            created_at: dto.created_at,
+            updated_at: dto.updated_at,
+        }
+    }
+}
+
+#[derive(FromRow, Debug)]
+pub struct TagDTO {
+    tag_id: i64,
+    name: String,
+}
+
+impl From<TagDTO> for Tag {
+    fn from(dto: TagDTO) -> Self {
+        Self {
+            id: dto.tag_id,
+            name: dto.name,
+        }
+    }
+}
+
+#[derive(FromRow, Debug)]
+pub struct RecordTagDTO {
+    pub record_id: i64,
+    #[sqlx(flatten)]
+    pub tag: TagDTO,
+}
+
+#[derive(FromRow, Debug)]
+pub struct TagTotalDTO {
+    pub tag_id: i64,
+    pub total: i64,
+}
+
+#[derive(FromRow, Debug)]
+pub struct CashFlowBucketDTO {
+    start: NaiveDate,
+    income: i64,
+    outcome: i64,
+    net: i64,
+    running_balance: i64,
+}
+
+impl From<CashFlowBucketDTO> for CashFlowBucket {
+    fn from(dto: CashFlowBucketDTO) -> Self {
+        Self {
+            start: dto.start,
+            income: dto.income,
+            outcome: dto.outcome,
+            net: dto.net,
+            running_balance: dto.running_balance,
+        }
+    }
+}
+
+#[derive(FromRow, Debug)]
+pub struct DeletedRecordDTO {
+    #[sqlx(flatten)]
+    record: FullRecordDTO,
+    deleted_at: DateTime<Local>,
+}
+
+impl From<DeletedRecordDTO> for Deleted<models::Record> {
+    fn from(dto: DeletedRecordDTO) -> Self {
+        Self {
+            item: dto.record.into(),
+            deleted_at: dto.deleted_at,
+        }
+    }
+}
+
+#[derive(FromRow, Debug)]
+pub struct DeletedAccountDTO {
+    #[sqlx(flatten)]
+    account: AccountDTO,
+    deleted_at: DateTime<Local>,
+}
+
+impl From<DeletedAccountDTO> for Deleted<Account> {
+    fn from(dto: DeletedAccountDTO) -> Self {
+        Self {
+            item: dto.account.into(),
+            deleted_at: dto.deleted_at,
+        }
+    }
+}
+
+#[derive(FromRow, Debug)]
+pub struct DeletedCategoryDTO {
+    #[sqlx(flatten)]
+    category: CategoryDTO,
+    deleted_at: DateTime<Local>,
+}
+
+impl From<DeletedCategoryDTO> for Deleted<Category> {
+    fn from(dto: DeletedCategoryDTO) -> Self {
+        Self {
+            item: dto.category.into(),
+            deleted_at: dto.deleted_at,
+        }
+    }
+}
+
+#[derive(FromRow, Debug)]
+pub struct AuditEntryDTO {
+    audit_log_id: i64,
+    user_id: i64,
+    actor_id: Option<i64>,
+    entity_type: String,
+    entity_id: i64,
+    action: String,
+    before: Option<String>,
+    after: Option<String>,
+    created_at: DateTime<Local>,
+}
+
+impl From<AuditEntryDTO> for AuditEntry {
+    fn from(dto: AuditEntryDTO) -> Self {
+        let snapshot = |json: String| {
+            serde_json::from_str(&json).expect("cannot convert audit snapshot from db")
+        };
+
+        Self {
+            id: dto.audit_log_id,
+            user_id: dto.user_id,
+            actor_id: dto.actor_id,
+            entity_type: AuditEntity::from_str(&dto.entity_type)
+                .expect("cannot convert audit entity type from db"),
+            entity_id: dto.entity_id,
+            action: AuditAction::from_str(&dto.action)
+                .expect("cannot convert audit action from db"),
+            before: dto.before.map(snapshot),
+            after: dto.after.map(snapshot),
+            created_at: dto.created_at,
+        }
+    }
+}
+
+#[derive(FromRow, Debug)]
+pub struct ExchangeRateDTO {
+    exchange_rate_id: i64,
+    base_currency: String,
+    quote_currency: String,
+    rate: f64,
+    valid_on: NaiveDate,
+}
+
+impl From<ExchangeRateDTO> for ExchangeRate {
+    fn from(dto: ExchangeRateDTO) -> Self {
+        Self {
+            id: dto.exchange_rate_id,
+            base: Currency::from_str(&dto.base_currency)
+                .expect("cannot convert base currency from db"),
+            quote: Currency::from_str(&dto.quote_currency)
+                .expect("cannot convert quote currency from db"),
+            rate: dto.rate,
+            valid_on: dto.valid_on,
+        }
+    }
+}
+
+#[derive(FromRow, Debug)]
+pub struct MissingExchangeRateDTO {
+    pub currency: String,
+    pub date: NaiveDate,
+}
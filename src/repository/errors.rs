@@ -1,5 +1,8 @@
+use sqlx::error::ErrorKind;
 use thiserror::Error;
 
+use crate::domain::errors::BudgetServiceError;
+
 #[derive(Debug, Error)]
 pub enum RepositoryError {
     #[error("database error {0}")]
@@ -7,3 +10,100 @@ pub enum RepositoryError {
     #[error("{entityname} not found")]
     NotFound { entityname: String },
 }
+
+/// Translates constraint violations into errors clients can act on, any
+/// other database error stays an internal one.
+impl From<sqlx::Error> for BudgetServiceError {
+    fn from(e: sqlx::Error) -> Self {
+        let Some(db_error) = e.as_database_error() else {
+            return Self::DatabaseError(e);
+        };
+
+        match db_error.kind() {
+            ErrorKind::UniqueViolation => {
+                Self::ConflictError(duplicate_message(db_error.message()))
+            }
+            ErrorKind::ForeignKeyViolation => Self::InvalidReferenceError(
+                "a referenced entity does not exist or is still referenced".into(),
+            ),
+            _ => Self::DatabaseError(e),
+        }
+    }
+}
+
+/// Maps `RowNotFound` of a lookup by id to a not-found error naming the
+/// entity, other errors are translated as usual.
+pub(crate) fn not_found(
+    entity: &'static str,
+    id: i64,
+) -> impl FnOnce(sqlx::Error) -> BudgetServiceError {
+    move |e| match e {
+        sqlx::Error::RowNotFound => BudgetServiceError::EntityNotFoundError { entity, id },
+        e => e.into(),
+    }
+}
+
+/// Maps a FOREIGN KEY failure of an insert or update to an error naming the
+/// `reference` that does not exist, other errors are translated as usual.
+pub(crate) fn missing_reference(
+    reference: &'static str,
+) -> impl FnOnce(sqlx::Error) -> BudgetServiceError {
+    move |e| match is_foreign_key_violation(&e) {
+        true => BudgetServiceError::InvalidReferenceError(format!("{reference} does not exist")),
+        false => e.into(),
+    }
+}
+
+/// Maps a FOREIGN KEY failure of a delete to a conflict, the `entity` is
+/// still referenced by other rows. Other errors are translated as usual.
+pub(crate) fn still_referenced(
+    entity: &'static str,
+) -> impl FnOnce(sqlx::Error) -> BudgetServiceError {
+    move |e| match is_foreign_key_violation(&e) {
+        true => BudgetServiceError::ConflictError(format!("{entity} is still in use")),
+        false => e.into(),
+    }
+}
+
+fn is_foreign_key_violation(e: &sqlx::Error) -> bool {
+    e.as_database_error()
+        .is_some_and(|db_error| db_error.kind() == ErrorKind::ForeignKeyViolation)
+}
+
+/// SQLite names the columns of a violated UNIQUE constraint as
+/// `table.column` after a colon, the owning user is left out.
+fn duplicate_message(message: &str) -> String {
+    let columns = message.rsplit(": ").next().unwrap_or_default();
+    let mut table = None;
+    let mut names = Vec::new();
+    for column in columns.split(", ") {
+        if let Some((column_table, name)) = column.split_once('.') {
+            table = Some(column_table);
+            if name != "user_id" {
+                names.push(name);
+            }
+        }
+    }
+
+    match table {
+        Some(table) => format!("{table} with the same {} already exists", names.join(", ")),
+        None => "entity already exists".into(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_duplicate_message() {
+        assert_eq!(
+            duplicate_message("UNIQUE constraint failed: category.user_id, category.name"),
+            "category with the same name already exists"
+        );
+        assert_eq!(
+            duplicate_message("UNIQUE constraint failed: user.username"),
+            "user with the same username already exists"
+        );
+    }
+}
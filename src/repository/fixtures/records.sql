@@ -0,0 +1,62 @@
+INSERT INTO
+  user (user_id, username, created_at)
+VALUES
+  (1, "test user", '2025-08-24 00:00:00 +00:00'),
+  (2, "other user", '2025-08-24 00:00:00 +00:00');
+
+INSERT INTO
+  account (account_id, user_id, name, account_type, current_balance)
+VALUES
+  (1, 1, "wallet", "Cash", 0),
+  (2, 1, "card", "DebitCard", 0),
+  (3, 2, "other wallet", "Cash", 0);
+
+INSERT INTO
+  category (category_id, user_id, name, budget, parent_id)
+VALUES
+  (1, 1, "food", NULL, NULL),
+  (2, 1, "groceries", NULL, 1),
+  (3, 1, "restaurants", NULL, 1),
+  (4, 1, "bakery", NULL, 2),
+  (5, 1, "salary", NULL, NULL),
+  (6, 2, "other food", NULL, NULL);
+
+INSERT INTO
+  record (
+    record_id,
+    account_id,
+    user_id,
+    record_type,
+    amount,
+    category_id,
+    description,
+    transfer_account_id,
+    transfer_amount,
+    occurred_at,
+    created_at,
+    updated_at
+  )
+VALUES
+  (1, 1, 1, 2, 300, 2, "Weekly groceries", NULL, NULL, '2026-02-27 10:00:00 +00:00', '2026-02-27 10:00:00 +00:00', '2026-02-27 10:00:00 +00:00'),
+  (2, 1, 1, 2, 120, 4, "Croissants 100%", NULL, NULL, '2026-03-01 08:00:00 +00:00', '2026-03-01 08:00:00 +00:00', '2026-03-01 08:00:00 +00:00'),
+  (3, 2, 1, 2, 2500, 3, "Dinner", NULL, NULL, '2026-03-15 20:00:00 +00:00', '2026-03-15 20:00:00 +00:00', '2026-03-15 20:00:00 +00:00'),
+  (4, 1, 1, 1, 50000, 5, "Salary", NULL, NULL, '2026-03-20 09:00:00 +00:00', '2026-03-20 09:00:00 +00:00', '2026-03-20 09:00:00 +00:00'),
+  (5, 2, 1, 3, 1000, NULL, "Cash withdrawal", 1, 1000, '2026-03-25 12:00:00 +00:00', '2026-03-25 12:00:00 +00:00', '2026-03-25 12:00:00 +00:00'),
+  (6, 1, 1, 2, 700, 2, "weekly groceries", NULL, NULL, '2026-04-01 00:00:00 +00:00', '2026-04-01 00:00:00 +00:00', '2026-04-01 00:00:00 +00:00'),
+  (7, 3, 2, 2, 900, 6, "Weekly groceries", NULL, NULL, '2026-03-10 10:00:00 +00:00', '2026-03-10 10:00:00 +00:00', '2026-03-10 10:00:00 +00:00');
+
+INSERT INTO
+  tag (tag_id, user_id, name)
+VALUES
+  (1, 1, "vacation"),
+  (2, 1, "business"),
+  (3, 2, "other tag");
+
+INSERT INTO
+  record_tag (record_id, tag_id)
+VALUES
+  (1, 1),
+  (3, 1),
+  (3, 2),
+  (2, 2),
+  (7, 3);
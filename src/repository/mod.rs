@@ -1,24 +1,35 @@
 pub mod accounts;
+pub mod audit;
+pub mod auth;
 pub mod categories;
+pub mod currencies;
 mod dto;
 pub mod errors;
 pub mod migrations;
+mod pages;
 pub mod records;
-mod test;
+pub mod recurring;
+pub mod reports;
+pub mod tags;
+#[cfg(test)]
+pub(crate) mod test;
+mod transaction;
+pub mod trash;
+pub mod users;
 
-use sqlx::SqlitePool;
+use std::sync::Arc;
 
-use crate::service::budget::BudgetRepository;
+use sqlx::{Sqlite, SqlitePool, Transaction};
+use tokio::sync::Mutex;
 
 #[derive(Clone)]
 pub struct SqliteBudgetRepo {
     pool: SqlitePool,
+    tx: Option<Arc<Mutex<Option<Transaction<'static, Sqlite>>>>>,
 }
 
 impl SqliteBudgetRepo {
     pub fn new(pool: SqlitePool) -> Self {
-        Self { pool }
+        Self { pool, tx: None }
     }
 }
-
-impl BudgetRepository for SqliteBudgetRepo {}
@@ -0,0 +1,147 @@
+use sqlx::{QueryBuilder, Sqlite, SqliteConnection, sqlite::SqliteRow};
+
+use crate::{
+    domain::{
+        Result,
+        models::{Cursor, CursorKey, Page, PageError},
+    },
+    service::{budget::PageCmd, records::SortOrder},
+};
+
+/// Column a paged list is sorted by before its id, which breaks ties.
+pub(super) enum SortColumn {
+    /// Sorted by id alone.
+    Id,
+    /// Timestamp column, compared as julian days like the other queries.
+    Date(&'static str),
+    /// Calendar day column stored as `YYYY-MM-DD`, which sorts as text.
+    Day(&'static str),
+    Name(&'static str),
+}
+
+/// Order of a paged list. A page resumes after the sort key and id of the
+/// last item of the previous one.
+pub(super) struct Keyset {
+    pub column: SortColumn,
+    pub id: &'static str,
+    pub order: SortOrder,
+}
+
+impl Keyset {
+    pub fn by_id(id: &'static str) -> Self {
+        Self {
+            column: SortColumn::Id,
+            id,
+            order: SortOrder::Asc,
+        }
+    }
+
+    /// Pushes the condition keeping the rows after `after`, to follow a
+    /// `WHERE` clause.
+    fn push_after(&self, query: &mut QueryBuilder<'_, Sqlite>, after: &Cursor) -> Result<()> {
+        let cmp = match self.order {
+            SortOrder::Asc => ">",
+            SortOrder::Desc => "<",
+        };
+        let id = self.id;
+
+        match (&self.column, &after.key) {
+            (SortColumn::Id, CursorKey::Id) => {
+                query.push(format!(" AND {id} {cmp} "));
+                query.push_bind(after.id);
+                return Ok(());
+            }
+            (SortColumn::Date(column), CursorKey::Date(date)) => {
+                query.push(format!(" AND (julianday({column}) {cmp} julianday("));
+                query.push_bind(*date);
+                query.push(format!(") OR (julianday({column}) = julianday("));
+                query.push_bind(*date);
+                query.push(")");
+            }
+            (SortColumn::Day(column), CursorKey::Day(day)) => {
+                query.push(format!(" AND ({column} {cmp} "));
+                query.push_bind(*day);
+                query.push(format!(" OR ({column} = "));
+                query.push_bind(*day);
+            }
+            (SortColumn::Name(column), CursorKey::Name(Some(name))) => {
+                query.push(format!(" AND ({column} {cmp} "));
+                query.push_bind(name.clone());
+                query.push(format!(" OR ({column} = "));
+                query.push_bind(name.clone());
+            }
+            _ => return Err(PageError::InvalidCursor.into()),
+        }
+        query.push(format!(" AND {id} {cmp} "));
+        query.push_bind(after.id);
+        query.push("))");
+
+        Ok(())
+    }
+
+    fn push_order(&self, query: &mut QueryBuilder<'_, Sqlite>) {
+        let order = match self.order {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        };
+        let id = self.id;
+
+        query.push(match self.column {
+            SortColumn::Id => format!(" ORDER BY {id} {order}"),
+            SortColumn::Date(column) => {
+                format!(" ORDER BY julianday({column}) {order}, {id} {order}")
+            }
+            SortColumn::Day(column) | SortColumn::Name(column) => {
+                format!(" ORDER BY {column} {order}, {id} {order}")
+            }
+        });
+    }
+}
+
+/// Reads one page of the rows selected by `select`, which pushes a query
+/// ending in a `WHERE` clause. `cursor` gives the position of an item in
+/// the order of `keyset`.
+pub(super) async fn fetch_page<D, T>(
+    conn: &mut SqliteConnection,
+    keyset: &Keyset,
+    page: &PageCmd,
+    select: impl Fn(&mut QueryBuilder<'_, Sqlite>),
+    cursor: impl Fn(&T) -> Cursor,
+) -> Result<Page<T>>
+where
+    D: for<'r> sqlx::FromRow<'r, SqliteRow> + Send + Unpin,
+    T: From<D>,
+{
+    let total_count = match page.include_total {
+        true => {
+            let mut query = QueryBuilder::new("SELECT COUNT(*) FROM (");
+            select(&mut query);
+            query.push(")");
+            let count: i64 = query.build_query_scalar().fetch_one(&mut *conn).await?;
+            Some(count as u64)
+        }
+        false => None,
+    };
+
+    let mut query = QueryBuilder::new("");
+    select(&mut query);
+    if let Some(after) = &page.after {
+        keyset.push_after(&mut query, after)?;
+    }
+    keyset.push_order(&mut query);
+    // One row past the page tells whether another page follows.
+    if let Some(limit) = page.limit {
+        query.push(" LIMIT ");
+        query.push_bind(limit.saturating_add(1).min(i64::MAX as u64) as i64);
+    }
+
+    let items = query
+        .build_query_as::<D>()
+        .fetch_all(&mut *conn)
+        .await?
+        .into_iter()
+        .map(T::from)
+        .collect();
+
+    Ok(Page::new(items, page.limit, cursor, total_count))
+}
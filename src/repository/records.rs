@@ -1,43 +1,67 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
-use sqlx::QueryBuilder;
+use sqlx::{QueryBuilder, Sqlite, SqliteConnection, types::chrono::Local};
 
 use crate::{
-    domain::{Result, models::Record},
+    domain::{
+        Result,
+        models::{Cursor, CursorKey, Record, RecordSplit, Tag},
+    },
     repository::{
         SqliteBudgetRepo,
-        dto::{FullRecordDTO, ReturnedId},
+        dto::{FullRecordDTO, RecordSplitDTO, RecordTagDTO, ReturnedId},
+        errors::{missing_reference, not_found},
+        reports::push_rate,
+    },
+    service::{
+        budget::RecordRepository,
+        records::{ListRecordsCmd, RecordSortField, SortOrder, TagMatch},
     },
-    service::{budget::RecordRepository, records::ListRecordsCmd},
 };
 
+/// Ids bound in a single `IN` list, well below the SQLite variable limit.
+const MAX_BOUND_IDS: usize = 500;
+
 #[async_trait]
 impl RecordRepository for SqliteBudgetRepo {
-    async fn create_record(&self, record: Record) -> Result<i64> {
-        let mut conn = self.pool.acquire().await?;
+    async fn create_record(&self, user_id: i64, record: Record) -> Result<i64> {
+        let mut conn = self.acquire().await?;
 
         let result = sqlx::query_as::<_, ReturnedId>(
             r#"
             INSERT INTO record 
-                (account_id,amount,record_type,category_id,created_at,updated_at)
+                (user_id,account_id,amount,description,record_type,category_id,
+                transfer_account_id,transfer_amount,occurred_at,posted_at,created_at,updated_at)
             VALUES
-                (?,?,?,?,?,?)
+                (?,?,?,?,?,?,?,?,?,?,?,?)
             RETURNING record_id as id;
             "#,
         )
+        .bind(user_id)
         .bind(record.account_id)
         .bind(record.amount)
-        .bind(Into::<i64>::into(record.record_type))
-        .bind(record.category.map(|c| c.id))
+        .bind(&record.description)
+        .bind(Into::<i64>::into(record.record_type.clone()))
+        .bind(record.category.as_ref().map(|c| c.id))
+        .bind(record.transfer.as_ref().map(|t| t.account_id))
+        .bind(record.transfer.as_ref().map(|t| t.amount))
+        .bind(record.occurred_at)
+        .bind(record.posted_at)
         .bind(record.created_at)
         .bind(record.updated_at)
         .fetch_one(&mut *conn)
-        .await?;
+        .await
+        .map_err(missing_reference("account or category"))?;
+
+        set_record_splits(&mut conn, result.id, &record.splits).await?;
+        set_record_tags(&mut conn, result.id, &record.tags).await?;
 
         Ok(result.id)
     }
 
-    async fn get_record_by_id(&self, id: i64) -> Result<Record> {
-        let mut conn = self.pool.acquire().await?;
+    async fn get_record_by_id(&self, user_id: i64, id: i64) -> Result<Record> {
+        let mut conn = self.acquire().await?;
 
         let record = sqlx::query_as::<_, FullRecordDTO>(
             r#"
@@ -47,26 +71,41 @@ impl RecordRepository for SqliteBudgetRepo {
                 record.amount,
                 record.description,
                 record_type.name as 'record_type',
+                record.transfer_account_id,
+                record.transfer_amount,
+                account.currency,
+                destination.currency as 'transfer_currency',
+                record.occurred_at,
+                record.posted_at,
                 record.created_at,
                 record.updated_at,
                 category.category_id,
                 category.name,
+                category.budget,
                 category.parent_id
             FROM record
             JOIN record_type ON record.record_type = record_type.record_type_id
-            JOIN category ON record.category_id = category.category_id
-            WHERE record.record_id = ?
+            JOIN account ON record.account_id = account.account_id
+            LEFT JOIN account AS destination ON record.transfer_account_id = destination.account_id
+            LEFT JOIN category ON record.category_id = category.category_id
+            WHERE record.record_id = ? AND record.user_id = ? AND record.deleted_at IS NULL
             "#,
         )
         .bind(id)
+        .bind(user_id)
         .fetch_one(&mut *conn)
-        .await?;
+        .await
+        .map_err(not_found("record", id))?;
+
+        let mut record = Record::from(record);
+        load_splits(&mut conn, std::slice::from_mut(&mut record)).await?;
+        load_tags(&mut conn, std::slice::from_mut(&mut record)).await?;
 
-        Ok(record.into())
+        Ok(record)
     }
 
-    async fn list_records(&self, req: ListRecordsCmd) -> Result<Vec<Record>> {
-        let mut conn = self.pool.acquire().await?;
+    async fn list_records(&self, user_id: i64, req: ListRecordsCmd) -> Result<Vec<Record>> {
+        let mut conn = self.acquire().await?;
 
         let mut query = QueryBuilder::new(
             r#"
@@ -76,30 +115,48 @@ impl RecordRepository for SqliteBudgetRepo {
                 record.amount,
                 record.description,
                 record_type.name as 'record_type',
+                record.transfer_account_id,
+                record.transfer_amount,
+                account.currency,
+                destination.currency as 'transfer_currency',
+                record.occurred_at,
+                record.posted_at,
                 record.created_at,
                 record.updated_at,
                 category.category_id,
                 category.name,
+                category.budget,
                 category.parent_id
-            FROM record
-            JOIN record_type ON record.record_type = record_type.record_type_id
-            LEFT JOIN category ON record.category_id = category.category_id
             "#,
         );
+        push_record_filters(&mut query, user_id, &req);
 
-        if let Some(category_id) = req.category_id {
-            query.push("WHERE record.category_id = ");
-            query.push_bind(category_id);
+        if let Some(after) = &req.after {
+            push_after(&mut query, &req, after);
         }
 
-        if let Some(limit) = req.limit {
-            query.push("LIMIT ");
-            query.push_bind(limit as i64);
+        let order = match req.sort_order {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        };
+        match req.sort_by {
+            RecordSortField::Date => {
+                query.push(format!(" ORDER BY julianday(record.occurred_at) {order}"));
+            }
+            RecordSortField::Amount => {
+                query.push(format!(" ORDER BY record.amount {order}"));
+            }
+            RecordSortField::Category => {
+                query.push(format!(
+                    " ORDER BY category.name IS NULL, category.name {order}"
+                ));
+            }
         }
+        query.push(format!(", record.record_id {order}"));
 
-        if let Some(offset) = req.offset {
-            query.push("OFFSET ");
-            query.push_bind(offset as i64);
+        if let Some(limit) = req.limit {
+            query.push(" LIMIT ");
+            query.push_bind(limit as i64);
         }
 
         let result = query
@@ -107,46 +164,78 @@ impl RecordRepository for SqliteBudgetRepo {
             .fetch_all(&mut *conn)
             .await?;
 
-        Ok(result.into_iter().map(Record::from).collect())
+        let mut records: Vec<Record> = result.into_iter().map(Record::from).collect();
+        load_splits(&mut conn, &mut records).await?;
+        load_tags(&mut conn, &mut records).await?;
+
+        Ok(records)
     }
 
-    async fn update_record(&self, record: Record) -> Result<()> {
-        let mut conn = self.pool.acquire().await?;
+    async fn count_records(&self, user_id: i64, req: &ListRecordsCmd) -> Result<u64> {
+        let mut conn = self.acquire().await?;
+
+        let mut query = QueryBuilder::new("SELECT COUNT(*)");
+        push_record_filters(&mut query, user_id, req);
+
+        let count: i64 = query.build_query_scalar().fetch_one(&mut *conn).await?;
+
+        Ok(count as u64)
+    }
+
+    async fn update_record(&self, user_id: i64, record: Record) -> Result<()> {
+        let mut conn = self.acquire().await?;
 
         sqlx::query(
             r#"
             UPDATE record
-                SET amount = ?
-                    description = ?
-                    category_id = ?
-                    record_type = ?
+                SET account_id = ?,
+                    amount = ?,
+                    description = ?,
+                    category_id = ?,
+                    record_type = ?,
+                    transfer_account_id = ?,
+                    transfer_amount = ?,
+                    occurred_at = ?,
+                    posted_at = ?,
                     updated_at = ?
-            WHERE category_id = ?
+            WHERE record_id = ? AND user_id = ?
             "#,
         )
+        .bind(record.account_id)
         .bind(record.amount)
-        .bind(record.description)
-        .bind(record.category.map(|c| c.id))
-        .bind(Into::<i64>::into(record.record_type))
+        .bind(&record.description)
+        .bind(record.category.as_ref().map(|c| c.id))
+        .bind(Into::<i64>::into(record.record_type.clone()))
+        .bind(record.transfer.as_ref().map(|t| t.account_id))
+        .bind(record.transfer.as_ref().map(|t| t.amount))
+        .bind(record.occurred_at)
+        .bind(record.posted_at)
         .bind(record.updated_at)
         .bind(record.id)
+        .bind(user_id)
         .execute(&mut *conn)
-        .await?;
+        .await
+        .map_err(missing_reference("account or category"))?;
+
+        set_record_splits(&mut conn, record.id, &record.splits).await?;
+        set_record_tags(&mut conn, record.id, &record.tags).await?;
 
         Ok(())
     }
 
-    async fn delete_record(&self, id: i64) -> Result<()> {
-        let mut conn = self.pool.acquire().await?;
+    async fn delete_record(&self, user_id: i64, id: i64) -> Result<()> {
+        let mut conn = self.acquire().await?;
 
         sqlx::query(
             r#"
-            DELETE 
-            FROM record
-            WHERE record_id = ?
+            UPDATE record
+                SET deleted_at = ?
+            WHERE record_id = ? AND user_id = ? AND deleted_at IS NULL
             "#,
         )
+        .bind(Local::now())
         .bind(id)
+        .bind(user_id)
         .execute(&mut *conn)
         .await?;
 
@@ -154,11 +243,348 @@ impl RecordRepository for SqliteBudgetRepo {
     }
 }
 
+/// Replaces the tags of a record.
+async fn set_record_tags(conn: &mut SqliteConnection, record_id: i64, tags: &[Tag]) -> Result<()> {
+    sqlx::query(
+        r#"
+        DELETE FROM record_tag
+        WHERE record_id = ?
+        "#,
+    )
+    .bind(record_id)
+    .execute(&mut *conn)
+    .await?;
+
+    if tags.is_empty() {
+        return Ok(());
+    }
+
+    let mut query = QueryBuilder::new("INSERT OR IGNORE INTO record_tag (record_id, tag_id) ");
+    query.push_values(tags, |mut row, tag| {
+        row.push_bind(record_id).push_bind(tag.id);
+    });
+    query
+        .build()
+        .execute(&mut *conn)
+        .await
+        .map_err(missing_reference("tag"))?;
+
+    Ok(())
+}
+
+/// Replaces the split lines of a record.
+async fn set_record_splits(
+    conn: &mut SqliteConnection,
+    record_id: i64,
+    splits: &[RecordSplit],
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        DELETE FROM record_split
+        WHERE record_id = ?
+        "#,
+    )
+    .bind(record_id)
+    .execute(&mut *conn)
+    .await?;
+
+    if splits.is_empty() {
+        return Ok(());
+    }
+
+    let mut query =
+        QueryBuilder::new("INSERT INTO record_split (record_id, category_id, amount, note) ");
+    query.push_values(splits, |mut row, split| {
+        row.push_bind(record_id)
+            .push_bind(split.category.id)
+            .push_bind(split.amount)
+            .push_bind(&split.note);
+    });
+    query
+        .build()
+        .execute(&mut *conn)
+        .await
+        .map_err(missing_reference("split category"))?;
+
+    Ok(())
+}
+
+/// Fills in the split lines of already loaded records.
+pub(super) async fn load_splits(conn: &mut SqliteConnection, records: &mut [Record]) -> Result<()> {
+    let mut splits: HashMap<i64, Vec<RecordSplit>> = HashMap::new();
+
+    let ids: Vec<i64> = records.iter().map(|r| r.id).collect();
+    for chunk in ids.chunks(MAX_BOUND_IDS) {
+        let mut query = QueryBuilder::new(
+            r#"
+            SELECT
+                record_split.record_id,
+                record_split.record_split_id,
+                record_split.amount,
+                record_split.note,
+                category.category_id,
+                category.name,
+                category.budget,
+                category.parent_id
+            FROM record_split
+            JOIN category ON record_split.category_id = category.category_id
+            WHERE record_split.record_id IN (
+            "#,
+        );
+        push_ids(&mut query, chunk);
+        query.push(") ORDER BY record_split.record_split_id");
+
+        let result = query
+            .build_query_as::<RecordSplitDTO>()
+            .fetch_all(&mut *conn)
+            .await?;
+        for dto in result {
+            splits.entry(dto.record_id).or_default().push(dto.into());
+        }
+    }
+
+    for record in records {
+        record.splits = splits.remove(&record.id).unwrap_or_default();
+    }
+
+    Ok(())
+}
+
+/// Fills in the tags of already loaded records.
+pub(super) async fn load_tags(conn: &mut SqliteConnection, records: &mut [Record]) -> Result<()> {
+    let mut tags: HashMap<i64, Vec<Tag>> = HashMap::new();
+
+    let ids: Vec<i64> = records.iter().map(|r| r.id).collect();
+    for chunk in ids.chunks(MAX_BOUND_IDS) {
+        let mut query = QueryBuilder::new(
+            r#"
+            SELECT record_tag.record_id, tag.tag_id, tag.name
+            FROM record_tag
+            JOIN tag ON record_tag.tag_id = tag.tag_id
+            WHERE record_tag.record_id IN (
+            "#,
+        );
+        push_ids(&mut query, chunk);
+        query.push(") ORDER BY tag.name");
+
+        let result = query
+            .build_query_as::<RecordTagDTO>()
+            .fetch_all(&mut *conn)
+            .await?;
+        for dto in result {
+            tags.entry(dto.record_id).or_default().push(dto.tag.into());
+        }
+    }
+
+    for record in records {
+        record.tags = tags.remove(&record.id).unwrap_or_default();
+    }
+
+    Ok(())
+}
+
+/// Pushes the tables and conditions shared by listing and counting records.
+fn push_record_filters(query: &mut QueryBuilder<'_, Sqlite>, user_id: i64, req: &ListRecordsCmd) {
+    query.push(
+        r#"
+        FROM record
+        JOIN record_type ON record.record_type = record_type.record_type_id
+        JOIN account ON record.account_id = account.account_id
+        LEFT JOIN account AS destination ON record.transfer_account_id = destination.account_id
+        LEFT JOIN category ON record.category_id = category.category_id
+        WHERE record.deleted_at IS NULL AND record.user_id = 
+        "#,
+    );
+    query.push_bind(user_id);
+
+    if !req.category_ids.is_empty() {
+        // Split records match through any of their lines.
+        query.push(" AND (record.category_id IN (");
+        push_category_ids(query, user_id, req);
+        query.push(
+            ") OR record.record_id IN (SELECT record_id FROM record_split WHERE category_id IN (",
+        );
+        push_category_ids(query, user_id, req);
+        query.push(")))");
+    }
+
+    if !req.tag_ids.is_empty() {
+        let mut tag_ids = req.tag_ids.clone();
+        tag_ids.sort_unstable();
+        tag_ids.dedup();
+
+        query.push(" AND record.record_id IN (SELECT record_id FROM record_tag WHERE tag_id IN (");
+        push_ids(query, &tag_ids);
+        query.push(")");
+        if req.tag_match == TagMatch::All {
+            query.push(" GROUP BY record_id HAVING COUNT(tag_id) = ");
+            query.push_bind(tag_ids.len() as i64);
+        }
+        query.push(")");
+    }
+
+    if let Some(account_id) = req.account_id {
+        query.push(" AND (record.account_id = ");
+        query.push_bind(account_id);
+        query.push(" OR record.transfer_account_id = ");
+        query.push_bind(account_id);
+        query.push(")");
+    }
+
+    if let Some(record_type) = req.record_type.clone() {
+        query.push(" AND record.record_type = ");
+        query.push_bind(Into::<i64>::into(record_type));
+    }
+
+    if let Some(from) = req.from {
+        query.push(" AND julianday(record.occurred_at) >= julianday(");
+        query.push_bind(from);
+        query.push(")");
+    }
+
+    if let Some(to) = req.to {
+        query.push(" AND julianday(record.occurred_at) < julianday(");
+        query.push_bind(to);
+        query.push(")");
+    }
+
+    // Amounts of accounts in another currency than the bounds are converted
+    // to minor units of the bound with the rate of the record's date,
+    // records without a rate never match.
+    if let Some(min_amount) = &req.min_amount {
+        query.push(" AND record.amount * ");
+        push_rate(query, min_amount.currency());
+        query.push(" >= ");
+        query.push_bind(min_amount.minor());
+    }
+
+    if let Some(max_amount) = &req.max_amount {
+        query.push(" AND record.amount * ");
+        push_rate(query, max_amount.currency());
+        query.push(" <= ");
+        query.push_bind(max_amount.minor());
+    }
+
+    if let Some(description) = &req.description {
+        query.push(r" AND record.description LIKE '%' || ");
+        query.push_bind(escape_like(description));
+        query.push(r" || '%' ESCAPE '\'");
+    }
+}
+
+/// Keeps the records after the cursor in the requested order, `after` must
+/// hold the key of the sort field.
+fn push_after(query: &mut QueryBuilder<'_, Sqlite>, req: &ListRecordsCmd, after: &Cursor) {
+    let cmp = match req.sort_order {
+        SortOrder::Asc => ">",
+        SortOrder::Desc => "<",
+    };
+    match &after.key {
+        CursorKey::Date(occurred_at) => {
+            query.push(format!(
+                " AND (julianday(record.occurred_at) {cmp} julianday("
+            ));
+            query.push_bind(*occurred_at);
+            query.push(") OR (julianday(record.occurred_at) = julianday(");
+            query.push_bind(*occurred_at);
+            query.push(format!(") AND record.record_id {cmp} "));
+            query.push_bind(after.id);
+            query.push("))");
+        }
+        CursorKey::Amount(amount) => {
+            query.push(format!(" AND (record.amount {cmp} "));
+            query.push_bind(*amount);
+            query.push(" OR (record.amount = ");
+            query.push_bind(*amount);
+            query.push(format!(" AND record.record_id {cmp} "));
+            query.push_bind(after.id);
+            query.push("))");
+        }
+        // Uncategorized records come last in both orders.
+        CursorKey::Name(Some(name)) => {
+            query.push(format!(
+                " AND (category.name IS NULL OR category.name {cmp} "
+            ));
+            query.push_bind(name.clone());
+            query.push(" OR (category.name = ");
+            query.push_bind(name.clone());
+            query.push(format!(" AND record.record_id {cmp} "));
+            query.push_bind(after.id);
+            query.push("))");
+        }
+        CursorKey::Name(None) => {
+            query.push(format!(
+                " AND category.name IS NULL AND record.record_id {cmp} "
+            ));
+            query.push_bind(after.id);
+        }
+        // Records are never sorted by day, the service rejects such cursors.
+        CursorKey::Id | CursorKey::Day(_) => {
+            query.push(format!(" AND record.record_id {cmp} "));
+            query.push_bind(after.id);
+        }
+    }
+}
+
+/// Pushes a select of the category ids to filter by, including descendants
+/// when requested.
+fn push_category_ids(query: &mut QueryBuilder<'_, Sqlite>, user_id: i64, req: &ListRecordsCmd) {
+    if !req.include_subcategories {
+        push_ids(query, &req.category_ids);
+        return;
+    }
+
+    query.push(
+        r#"
+        WITH RECURSIVE selected_category(category_id) AS (
+            SELECT category_id
+            FROM category
+            WHERE category_id IN (
+        "#,
+    );
+    push_ids(query, &req.category_ids);
+    query.push(
+        r#"
+            )
+            UNION
+            SELECT category.category_id
+            FROM category
+            JOIN selected_category ON category.parent_id = selected_category.category_id
+            WHERE category.user_id = "#,
+    );
+    query.push_bind(user_id);
+    query.push(
+        r#"
+        )
+        SELECT category_id FROM selected_category
+        "#,
+    );
+}
+
+fn push_ids(query: &mut QueryBuilder<'_, Sqlite>, ids: &[i64]) {
+    let mut separated = query.separated(", ");
+    for id in ids {
+        separated.push_bind(*id);
+    }
+}
+
+/// Escapes `LIKE` wildcards so the value is matched literally.
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
 #[cfg(test)]
 mod test {
-    use sqlx::types::chrono::DateTime;
+    use sqlx::types::chrono::{DateTime, Local};
 
-    use crate::repository::test::test_db;
+    use crate::{
+        domain::models::{Account, Currency, Money, RecordType},
+        repository::test::test_db,
+        service::budget::AccountRepository,
+    };
 
     use super::*;
 
@@ -167,34 +593,362 @@ mod test {
         let fixture = include_str!("./fixtures/fixture.sql");
         let repo = test_db(Some(fixture)).await;
 
-        let mut record = Record::new(1, "Outcome".into(), 1000, None, Some("test record".into()))
-            .expect("error");
+        let mut record = Record::new(
+            1,
+            "Outcome".into(),
+            Money::new(1000, Currency::default()),
+            None,
+            Some("test record".into()),
+        )
+        .expect("error");
 
         let d =
             DateTime::parse_from_str("2025-08-24 00:00:00 +00:00", "%Y-%m-%d %H:%M:%S %z").unwrap();
         record.id = 1;
+        record.occurred_at = d.into();
         record.created_at = d.into();
         record.updated_at = d.into();
         let list = vec![record];
-        let result = repo
-            .list_records(ListRecordsCmd {
-                limit: None,
-                offset: None,
-                category_id: None,
-            })
-            .await;
+        let result = repo.list_records(1, ListRecordsCmd::default()).await;
         assert!(result.is_ok(), "{}", result.err().unwrap());
         assert_eq!(result.unwrap(), list)
     }
 
+    async fn list_ids(repo: &SqliteBudgetRepo, cmd: ListRecordsCmd) -> Vec<i64> {
+        repo.list_records(1, cmd)
+            .await
+            .expect("must list records")
+            .iter()
+            .map(|r| r.id)
+            .collect()
+    }
+
+    fn date(s: &str) -> DateTime<Local> {
+        DateTime::parse_from_rfc3339(s).unwrap().into()
+    }
+
+    #[tokio::test]
+    async fn test_list_records_filters() {
+        let fixture = include_str!("./fixtures/records.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let ids = list_ids(
+            &repo,
+            ListRecordsCmd {
+                account_id: Some(1),
+                record_type: Some(RecordType::Outcome),
+                from: Some(date("2026-03-01T00:00:00Z")),
+                to: Some(date("2026-04-01T00:00:00Z")),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(ids, vec![2]);
+
+        let ids = list_ids(
+            &repo,
+            ListRecordsCmd {
+                account_id: Some(1),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(ids, vec![6, 5, 4, 2, 1]);
+
+        let ids = list_ids(
+            &repo,
+            ListRecordsCmd {
+                min_amount: Some(Money::new(300, Currency::default())),
+                max_amount: Some(Money::new(2500, Currency::default())),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(ids, vec![6, 5, 3, 1]);
+
+        let ids = list_ids(
+            &repo,
+            ListRecordsCmd {
+                description: Some("WEEKLY".into()),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(ids, vec![6, 1]);
+
+        let ids = list_ids(
+            &repo,
+            ListRecordsCmd {
+                description: Some("100%".into()),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(ids, vec![2]);
+    }
+
+    #[tokio::test]
+    async fn test_list_records_categories() {
+        let fixture = include_str!("./fixtures/records.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let ids = list_ids(
+            &repo,
+            ListRecordsCmd {
+                category_ids: vec![2, 5],
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(ids, vec![6, 4, 1]);
+
+        let ids = list_ids(
+            &repo,
+            ListRecordsCmd {
+                category_ids: vec![1],
+                include_subcategories: true,
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(ids, vec![6, 3, 2, 1]);
+
+        let ids = list_ids(
+            &repo,
+            ListRecordsCmd {
+                category_ids: vec![6],
+                include_subcategories: true,
+                ..Default::default()
+            },
+        )
+        .await;
+        assert!(ids.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_records_tags() {
+        let fixture = include_str!("./fixtures/records.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let ids = list_ids(
+            &repo,
+            ListRecordsCmd {
+                tag_ids: vec![1],
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(ids, vec![3, 1]);
+
+        let ids = list_ids(
+            &repo,
+            ListRecordsCmd {
+                tag_ids: vec![1, 2],
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(ids, vec![3, 2, 1]);
+
+        let ids = list_ids(
+            &repo,
+            ListRecordsCmd {
+                tag_ids: vec![1, 2],
+                tag_match: TagMatch::All,
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(ids, vec![3]);
+
+        let ids = list_ids(
+            &repo,
+            ListRecordsCmd {
+                tag_ids: vec![3],
+                ..Default::default()
+            },
+        )
+        .await;
+        assert!(ids.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_update_record_tags() {
+        let fixture = include_str!("./fixtures/records.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let mut record = repo.get_record_by_id(1, 3).await.expect("must find record");
+        let names: Vec<&str> = record.tags.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["business", "vacation"]);
+
+        record.tags.retain(|t| t.id == 1);
+        let result = repo.update_record(1, record.clone()).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        let updated_record = repo.get_record_by_id(1, 3).await.expect("must find record");
+        assert_eq!(record, updated_record);
+    }
+
+    #[tokio::test]
+    async fn test_list_records_sorting() {
+        let fixture = include_str!("./fixtures/records.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let ids = list_ids(
+            &repo,
+            ListRecordsCmd {
+                sort_by: RecordSortField::Amount,
+                sort_order: SortOrder::Asc,
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(ids, vec![2, 1, 6, 5, 3, 4]);
+
+        let ids = list_ids(
+            &repo,
+            ListRecordsCmd {
+                sort_by: RecordSortField::Category,
+                sort_order: SortOrder::Asc,
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(ids, vec![2, 1, 6, 3, 4, 5]);
+
+        let all = repo
+            .list_records(
+                1,
+                ListRecordsCmd {
+                    sort_order: SortOrder::Asc,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let after =
+            |index: usize| Cursor::new(CursorKey::Date(all[index].occurred_at), all[index].id);
+
+        let ids = list_ids(
+            &repo,
+            ListRecordsCmd {
+                sort_order: SortOrder::Asc,
+                limit: Some(2),
+                after: Some(after(0)),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(ids, vec![2, 3]);
+
+        let ids = list_ids(
+            &repo,
+            ListRecordsCmd {
+                sort_order: SortOrder::Asc,
+                after: Some(after(3)),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(ids, vec![5, 6]);
+    }
+
+    #[tokio::test]
+    async fn test_create_record() {
+        let fixture = include_str!("./fixtures/fixture.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let record = Record::new(
+            1,
+            "Income".into(),
+            Money::new(1500, Currency::default()),
+            None,
+            Some("salary".into()),
+        )
+        .unwrap();
+        let id = repo
+            .create_record(1, record)
+            .await
+            .expect("must create record");
+
+        let record = repo
+            .get_record_by_id(1, id)
+            .await
+            .expect("must find record");
+        assert_eq!(record.description, Some("salary".into()));
+    }
+
+    #[tokio::test]
+    async fn test_update_record() {
+        let fixture = include_str!("./fixtures/fixture.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let mut record = repo.get_record_by_id(1, 1).await.expect("must find record");
+        record
+            .set_amount(Money::new(500, record.currency.clone()))
+            .unwrap();
+        record.description = None;
+        let result = repo.update_record(1, record.clone()).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        let updated_record = repo.get_record_by_id(1, 1).await.expect("must find record");
+        assert_eq!(record, updated_record);
+    }
+
+    #[tokio::test]
+    async fn test_create_transfer() {
+        let fixture = include_str!("./fixtures/fixture.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let eur = "EUR".parse::<Currency>().unwrap();
+        let account = Account::new("savings".into(), 0, "DebitCard".into(), eur.clone()).unwrap();
+        let destination_id = repo.create_account(1, account).await.unwrap();
+
+        let record = Record::new_transfer(
+            1,
+            destination_id,
+            Money::new(1000, Currency::default()),
+            Some(Money::new(900, eur.clone())),
+            None,
+            None,
+        )
+        .unwrap();
+        let id = repo
+            .create_record(1, record)
+            .await
+            .expect("must create record");
+
+        let record = repo
+            .get_record_by_id(1, id)
+            .await
+            .expect("must find record");
+        let transfer = record.transfer.expect("must be a transfer");
+        assert_eq!(transfer.account_id, destination_id);
+        assert_eq!(i64::from(transfer.amount), 900);
+        assert_eq!(transfer.currency, eur);
+        assert_eq!(record.currency, Currency::default());
+    }
+
+    #[tokio::test]
+    async fn test_delete_record() {
+        let fixture = include_str!("./fixtures/fixture.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let result = repo.delete_record(1, 1).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        let result = repo.get_record_by_id(1, 1).await;
+        assert!(result.is_err());
+    }
+
     // #[tokio::test]
     // async fn test_create_record() {
     //     let fixture = include_str!("./fixtures/fixture.sql");
     //     let repo = test_db(Some(fixture)).await;
     //
-    //     let record = Record::new(1, "Income".into(), 1000, None, Some("text".into())).unwrap();
+    //     let record = Record::new(1, "Income".into(), Money::new(1000, Currency::default()), None, Some("text".into())).unwrap();
     //
-    //     let result = repo.create_record(record).await;
+    //     let result = repo.create_record(1, record).await;
     //     assert!(result.is_ok(), "{}", result.err().unwrap());
     //     assert!(result.unwrap() > 0);
     // }
@@ -204,7 +958,7 @@ mod test {
     //     let repo = test_db(Some(fixture)).await;
     //
     //     let account = Account::new("test account".into(), 0, "Cash".into()).unwrap();
-    //     let result = repo.create_account(account).await;
+    //     let result = repo.create_account(1, account).await;
     //     assert!(result.is_ok(), "{}", result.err().unwrap());
     //     assert!(result.unwrap() > 0);
     // }
@@ -0,0 +1,325 @@
+use async_trait::async_trait;
+use sqlx::types::chrono::{DateTime, Local};
+
+use crate::{
+    domain::{
+        Result,
+        models::{Cursor, CursorKey, Page, RecurringRecord},
+    },
+    repository::{
+        SqliteBudgetRepo,
+        dto::{RecurringRecordDTO, ReturnedId},
+        errors::{missing_reference, not_found},
+        pages::{Keyset, fetch_page},
+    },
+    service::budget::{PageCmd, RecurringRecordRepository},
+};
+
+#[async_trait]
+impl RecurringRecordRepository for SqliteBudgetRepo {
+    async fn list_recurring_records(&self, user_id: i64) -> Result<Vec<RecurringRecord>> {
+        let page = self
+            .list_recurring_records_page(user_id, &PageCmd::default())
+            .await?;
+
+        Ok(page.items)
+    }
+
+    async fn list_recurring_records_page(
+        &self,
+        user_id: i64,
+        page: &PageCmd,
+    ) -> Result<Page<RecurringRecord>> {
+        let mut conn = self.acquire().await?;
+
+        fetch_page::<RecurringRecordDTO, RecurringRecord>(
+            &mut conn,
+            &Keyset::by_id("recurring_record.recurring_record_id"),
+            page,
+            |query| {
+                query.push(
+                    r#"
+                    SELECT
+                        recurring_record.recurring_record_id,
+                        recurring_record.user_id,
+                        recurring_record.account_id,
+                        record_type.name as 'record_type',
+                        recurring_record.amount,
+                        recurring_record.description,
+                        recurring_record.category_id,
+                        recurring_record.transfer_account_id,
+                        recurring_record.transfer_amount,
+                        account.currency,
+                        destination.currency as 'transfer_currency',
+                        recurring_record.recurrence_unit,
+                        recurring_record.recurrence_interval,
+                        recurring_record.recurrence_day_of_month,
+                        recurring_record.starts_at,
+                        recurring_record.ends_at,
+                        recurring_record.occurrence_count,
+                        recurring_record.next_occurrence_at,
+                        recurring_record.created_at,
+                        recurring_record.updated_at
+                    FROM recurring_record
+                    JOIN record_type ON recurring_record.record_type = record_type.record_type_id
+                    JOIN account ON recurring_record.account_id = account.account_id
+                    LEFT JOIN account AS destination
+                        ON recurring_record.transfer_account_id = destination.account_id
+                    WHERE recurring_record.user_id = "#,
+                );
+                query.push_bind(user_id);
+            },
+            |recurring| Cursor::new(CursorKey::Id, recurring.id),
+        )
+        .await
+    }
+
+    async fn create_recurring_record(&self, recurring: RecurringRecord) -> Result<i64> {
+        let mut conn = self.acquire().await?;
+
+        let result = sqlx::query_as::<_, ReturnedId>(
+            r#"
+            INSERT INTO recurring_record
+                (user_id,account_id,record_type,amount,description,category_id,
+                transfer_account_id,transfer_amount,recurrence_unit,recurrence_interval,
+                recurrence_day_of_month,starts_at,ends_at,occurrence_count,next_occurrence_at,
+                created_at,updated_at)
+            VALUES
+                (?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?)
+            RETURNING recurring_record_id as id;
+            "#,
+        )
+        .bind(recurring.user_id)
+        .bind(recurring.account_id)
+        .bind(Into::<i64>::into(recurring.record_type))
+        .bind(recurring.amount)
+        .bind(recurring.description)
+        .bind(recurring.category_id)
+        .bind(recurring.destination_account_id)
+        .bind(recurring.received_amount)
+        .bind(recurring.recurrence.unit.to_string())
+        .bind(recurring.recurrence.interval)
+        .bind(recurring.recurrence.day_of_month)
+        .bind(recurring.starts_at)
+        .bind(recurring.ends_at)
+        .bind(recurring.occurrence_count)
+        .bind(recurring.next_occurrence_at)
+        .bind(recurring.created_at)
+        .bind(recurring.updated_at)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(missing_reference("account or category"))?;
+
+        Ok(result.id)
+    }
+
+    async fn get_recurring_record_by_id(&self, user_id: i64, id: i64) -> Result<RecurringRecord> {
+        let mut conn = self.acquire().await?;
+
+        let result = sqlx::query_as::<_, RecurringRecordDTO>(
+            r#"
+            SELECT
+                recurring_record.recurring_record_id,
+                recurring_record.user_id,
+                recurring_record.account_id,
+                record_type.name as 'record_type',
+                recurring_record.amount,
+                recurring_record.description,
+                recurring_record.category_id,
+                recurring_record.transfer_account_id,
+                recurring_record.transfer_amount,
+                account.currency,
+                destination.currency as 'transfer_currency',
+                recurring_record.recurrence_unit,
+                recurring_record.recurrence_interval,
+                recurring_record.recurrence_day_of_month,
+                recurring_record.starts_at,
+                recurring_record.ends_at,
+                recurring_record.occurrence_count,
+                recurring_record.next_occurrence_at,
+                recurring_record.created_at,
+                recurring_record.updated_at
+            FROM recurring_record
+            JOIN record_type ON recurring_record.record_type = record_type.record_type_id
+            JOIN account ON recurring_record.account_id = account.account_id
+            LEFT JOIN account AS destination
+                ON recurring_record.transfer_account_id = destination.account_id
+            WHERE recurring_record.recurring_record_id = ? AND recurring_record.user_id = ?
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(not_found("recurring record", id))?;
+
+        Ok(result.into())
+    }
+
+    async fn update_recurring_record(&self, recurring: RecurringRecord) -> Result<()> {
+        let mut conn = self.acquire().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE recurring_record
+            SET account_id = ?,
+                amount = ?,
+                description = ?,
+                category_id = ?,
+                transfer_account_id = ?,
+                transfer_amount = ?,
+                ends_at = ?,
+                occurrence_count = ?,
+                next_occurrence_at = ?,
+                updated_at = ?
+            WHERE recurring_record_id = ? AND user_id = ?
+            "#,
+        )
+        .bind(recurring.account_id)
+        .bind(recurring.amount)
+        .bind(recurring.description)
+        .bind(recurring.category_id)
+        .bind(recurring.destination_account_id)
+        .bind(recurring.received_amount)
+        .bind(recurring.ends_at)
+        .bind(recurring.occurrence_count)
+        .bind(recurring.next_occurrence_at)
+        .bind(recurring.updated_at)
+        .bind(recurring.id)
+        .bind(recurring.user_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(missing_reference("account or category"))?;
+
+        Ok(())
+    }
+
+    async fn delete_recurring_record(&self, user_id: i64, id: i64) -> Result<()> {
+        let mut conn = self.acquire().await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM recurring_record
+            WHERE recurring_record_id = ? AND user_id = ?
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn list_due_recurring_records(
+        &self,
+        now: DateTime<Local>,
+    ) -> Result<Vec<RecurringRecord>> {
+        let mut conn = self.acquire().await?;
+
+        let result = sqlx::query_as::<_, RecurringRecordDTO>(
+            r#"
+            SELECT
+                recurring_record.recurring_record_id,
+                recurring_record.user_id,
+                recurring_record.account_id,
+                record_type.name as 'record_type',
+                recurring_record.amount,
+                recurring_record.description,
+                recurring_record.category_id,
+                recurring_record.transfer_account_id,
+                recurring_record.transfer_amount,
+                account.currency,
+                destination.currency as 'transfer_currency',
+                recurring_record.recurrence_unit,
+                recurring_record.recurrence_interval,
+                recurring_record.recurrence_day_of_month,
+                recurring_record.starts_at,
+                recurring_record.ends_at,
+                recurring_record.occurrence_count,
+                recurring_record.next_occurrence_at,
+                recurring_record.created_at,
+                recurring_record.updated_at
+            FROM recurring_record
+            JOIN record_type ON recurring_record.record_type = record_type.record_type_id
+            JOIN account ON recurring_record.account_id = account.account_id
+            LEFT JOIN account AS destination
+                ON recurring_record.transfer_account_id = destination.account_id
+            WHERE recurring_record.next_occurrence_at IS NOT NULL
+                AND julianday(recurring_record.next_occurrence_at) <= julianday(?)
+            ORDER BY julianday(recurring_record.next_occurrence_at)
+            "#,
+        )
+        .bind(now)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok(result.into_iter().map(RecurringRecord::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        domain::models::{Currency, Money, RecordType, Recurrence, RecurrenceUnit},
+        repository::test::test_db,
+    };
+
+    use super::*;
+
+    fn recurring(starts_at: DateTime<Local>) -> RecurringRecord {
+        RecurringRecord::new(
+            1,
+            1,
+            RecordType::Outcome,
+            Money::new(1200, Currency::default()),
+            Some("lunch".into()),
+            Some(1),
+            None,
+            None,
+            Recurrence::new(RecurrenceUnit::Day, 1, None).unwrap(),
+            starts_at,
+            None,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_create_recurring_record() {
+        let fixture = include_str!("./fixtures/fixture.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let recurring = recurring(Local::now());
+        let id = repo
+            .create_recurring_record(recurring.clone())
+            .await
+            .expect("must create recurring record");
+
+        let result = repo.get_recurring_record_by_id(1, id).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+        assert_eq!(result.unwrap(), RecurringRecord { id, ..recurring });
+
+        let result = repo.get_recurring_record_by_id(2, id).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_list_due_recurring_records() {
+        let fixture = include_str!("./fixtures/fixture.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let now = Local::now();
+        let due = repo
+            .create_recurring_record(recurring(now - std::time::Duration::from_secs(60)))
+            .await
+            .unwrap();
+        repo.create_recurring_record(recurring(now + std::time::Duration::from_secs(60)))
+            .await
+            .unwrap();
+
+        let result = repo.list_due_recurring_records(now).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+        let ids: Vec<i64> = result.unwrap().iter().map(|r| r.id).collect();
+        assert_eq!(ids, vec![due]);
+    }
+}
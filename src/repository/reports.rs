@@ -0,0 +1,631 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use chrono::TimeDelta;
+use sqlx::{
+    QueryBuilder, Sqlite,
+    query::QueryAs,
+    sqlite::SqliteArguments,
+    types::chrono::{DateTime, Local, NaiveDate},
+};
+
+use crate::{
+    domain::{
+        Result,
+        models::{CashFlowBucket, Currency, MINOR_UNITS},
+    },
+    repository::{
+        SqliteBudgetRepo,
+        dto::{CashFlowBucketDTO, CategoryTotalDTO, MissingExchangeRateDTO, TagTotalDTO},
+    },
+    service::{
+        budget::ReportRepository,
+        reports::{CashFlowCmd, CashFlowInterval},
+    },
+};
+
+/// Factor converting the amount of `record` from the currency of `account`
+/// to the currency bound as `?2`, using the latest rate on or before the
+/// record's date. The inverse of the opposite pair is used when only that
+/// one is known, NULL when there is no rate at all. Rates are quoted in
+/// major units, so the factor also scales between the decimal places of
+/// the two currencies.
+fn rate() -> String {
+    rate_on("record.user_id", "substr(record.occurred_at, 1, 10)")
+}
+
+/// Like [`rate`], for the rates of `user_id` on or before `day`.
+fn rate_on(user_id: &str, day: &str) -> String {
+    let account_scale = minor_scale("account.currency");
+    let target_scale = minor_scale("?2");
+
+    format!(
+        r#"
+        CASE WHEN account.currency = ?2 THEN 1.0 ELSE (
+            SELECT rate * {target_scale} / {account_scale} FROM (
+                SELECT rate, valid_on
+                FROM exchange_rate
+                WHERE exchange_rate.user_id = {user_id}
+                    AND base_currency = account.currency
+                    AND quote_currency = ?2
+                    AND valid_on <= {day}
+                UNION ALL
+                SELECT 1.0 / rate, valid_on
+                FROM exchange_rate
+                WHERE exchange_rate.user_id = {user_id}
+                    AND base_currency = ?2
+                    AND quote_currency = account.currency
+                    AND valid_on <= {day}
+            )
+            ORDER BY valid_on DESC
+            LIMIT 1
+        ) END
+        "#
+    )
+}
+
+/// Number of minor units in a major unit of the currency `code` evaluates
+/// to, such as 100.0 for `USD` and 1.0 for `JPY`.
+fn minor_scale(code: &str) -> String {
+    let mut sql = String::from("(CASE");
+    for (minor_units, codes) in MINOR_UNITS {
+        let codes = codes
+            .iter()
+            .map(|code| format!("'{code}'"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        sql.push_str(&format!(
+            " WHEN {code} IN ({codes}) THEN {}.0",
+            10u64.pow(minor_units)
+        ));
+    }
+    sql.push_str(" ELSE 100.0 END)");
+
+    sql
+}
+
+/// Pushes [`rate`] with `currency` bound in place of `?2`, for queries
+/// built with positional parameters.
+pub(super) fn push_rate(query: &mut QueryBuilder<'_, Sqlite>, currency: &Currency) {
+    let rate = rate();
+    let mut parts = rate.split("?2");
+    query.push(parts.next().unwrap_or_default());
+    for part in parts {
+        query.push_bind(currency.code().to_owned());
+        query.push(part);
+    }
+}
+
+/// Conditions shared by every record of a cash flow query, bound as `?1`
+/// user id, `?3` and `?4` period.
+const CASH_FLOW_PERIOD: &str = r#"
+    record.user_id = ?1
+    AND record.deleted_at IS NULL
+    AND julianday(record.occurred_at) >= julianday(?3)
+    AND julianday(record.occurred_at) < julianday(?4)
+"#;
+
+/// `selected_category`, `flow(day, currency, record_type, amount, rate)` and
+/// `opening(currency, balance, rate)` common table expressions of a cash flow
+/// query, with the optional account as `?7` and category as `?8`. Split
+/// records count through their lines, transfers only when limited to an
+/// account and as an outcome of the source and an income of the destination.
+/// `opening` holds the balance of every selected account at the start of the
+/// period, its current balance without the records since.
+fn cash_flow_ctes() -> String {
+    let rate = rate();
+    let opening_rate = rate_on("account.user_id", "?5");
+
+    format!(
+        r#"
+        selected_category(category_id) AS (
+            SELECT category_id
+            FROM category
+            WHERE category_id = ?8 AND user_id = ?1
+            UNION
+            SELECT category.category_id
+            FROM category
+            JOIN selected_category ON category.parent_id = selected_category.category_id
+        ),
+        flow(day, currency, record_type, amount, rate) AS (
+            SELECT
+                substr(record.occurred_at, 1, 10),
+                account.currency,
+                record.record_type,
+                record.amount,
+                {rate}
+            FROM record
+            JOIN account ON record.account_id = account.account_id
+            WHERE {CASH_FLOW_PERIOD}
+                AND record.record_type IN (1, 2)
+                AND (?7 IS NULL OR record.account_id = ?7)
+                AND (?8 IS NULL OR record.category_id IN (SELECT category_id FROM selected_category))
+                AND NOT EXISTS (
+                    SELECT 1 FROM record_split WHERE record_split.record_id = record.record_id
+                )
+            UNION ALL
+            SELECT
+                substr(record.occurred_at, 1, 10),
+                account.currency,
+                record.record_type,
+                record_split.amount,
+                {rate}
+            FROM record_split
+            JOIN record ON record_split.record_id = record.record_id
+            JOIN account ON record.account_id = account.account_id
+            WHERE {CASH_FLOW_PERIOD}
+                AND record.record_type IN (1, 2)
+                AND (?7 IS NULL OR record.account_id = ?7)
+                AND (?8 IS NULL OR record_split.category_id IN (SELECT category_id FROM selected_category))
+            UNION ALL
+            SELECT
+                substr(record.occurred_at, 1, 10),
+                account.currency,
+                2,
+                record.amount,
+                {rate}
+            FROM record
+            JOIN account ON record.account_id = account.account_id
+            WHERE {CASH_FLOW_PERIOD}
+                AND record.record_type = 3
+                AND record.account_id = ?7
+                AND (?8 IS NULL OR record.category_id IN (SELECT category_id FROM selected_category))
+            UNION ALL
+            SELECT
+                substr(record.occurred_at, 1, 10),
+                account.currency,
+                1,
+                record.transfer_amount,
+                {rate}
+            FROM record
+            JOIN account ON record.transfer_account_id = account.account_id
+            WHERE {CASH_FLOW_PERIOD}
+                AND record.record_type = 3
+                AND record.transfer_account_id = ?7
+                AND (?8 IS NULL OR record.category_id IN (SELECT category_id FROM selected_category))
+        ),
+        opening(currency, balance, rate) AS (
+            SELECT
+                account.currency,
+                account.current_balance - COALESCE((
+                    SELECT SUM(CASE
+                        WHEN record.account_id <> account.account_id THEN record.transfer_amount
+                        WHEN record.record_type = 1 THEN record.amount
+                        ELSE -record.amount
+                    END)
+                    FROM record
+                    WHERE record.deleted_at IS NULL
+                        AND julianday(record.occurred_at) >= julianday(?3)
+                        AND (
+                            record.account_id = account.account_id
+                            OR (record.record_type = 3 AND record.transfer_account_id = account.account_id)
+                        )
+                ), 0),
+                {opening_rate}
+            FROM account
+            WHERE account.user_id = ?1
+                AND account.deleted_at IS NULL
+                AND (?7 IS NULL OR account.account_id = ?7)
+        )
+        "#
+    )
+}
+
+/// Date modifiers truncating a day to the start of its interval and the
+/// step to the start of the next one.
+fn interval_modifiers(interval: CashFlowInterval) -> (&'static str, &'static str) {
+    match interval {
+        CashFlowInterval::Day => ("'start of day'", "'+1 day'"),
+        CashFlowInterval::Week => ("'-6 days', 'weekday 1'", "'+7 days'"),
+        CashFlowInterval::Month => ("'start of month'", "'+1 month'"),
+        CashFlowInterval::Year => ("'start of year'", "'+1 year'"),
+    }
+}
+
+/// Binds the parameters of a cash flow query in the order the common table
+/// expressions and the interval series expect them.
+fn bind_cash_flow<'q, O>(
+    query: QueryAs<'q, Sqlite, O, SqliteArguments<'q>>,
+    user_id: i64,
+    cmd: &CashFlowCmd,
+) -> QueryAs<'q, Sqlite, O, SqliteArguments<'q>> {
+    let last_day = (cmd.to - TimeDelta::nanoseconds(1)).date_naive();
+
+    query
+        .bind(user_id)
+        .bind(cmd.currency.code().to_owned())
+        .bind(cmd.from)
+        .bind(cmd.to)
+        .bind(cmd.from.date_naive())
+        .bind(last_day)
+        .bind(cmd.account_id)
+        .bind(cmd.category_id)
+}
+
+#[async_trait]
+impl ReportRepository for SqliteBudgetRepo {
+    async fn spending_by_category(
+        &self,
+        user_id: i64,
+        currency: &Currency,
+        from: DateTime<Local>,
+        to: DateTime<Local>,
+    ) -> Result<Vec<(i64, i64)>> {
+        let mut conn = self.acquire().await?;
+
+        let rate = rate();
+        let query = format!(
+            r#"
+            SELECT category_id, CAST(ROUND(SUM(amount * rate)) AS INTEGER) as total
+            FROM (
+                SELECT record.category_id, record.amount, {rate} as rate
+                FROM record
+                JOIN account ON record.account_id = account.account_id
+                WHERE record.user_id = ?1
+                    AND record.deleted_at IS NULL
+                    AND record.record_type = 2
+                    AND record.category_id IS NOT NULL
+                    AND julianday(record.occurred_at) >= julianday(?3)
+                    AND julianday(record.occurred_at) < julianday(?4)
+                UNION ALL
+                SELECT record_split.category_id, record_split.amount, {rate} as rate
+                FROM record_split
+                JOIN record ON record_split.record_id = record.record_id
+                JOIN account ON record.account_id = account.account_id
+                WHERE record.user_id = ?1
+                    AND record.deleted_at IS NULL
+                    AND record.record_type = 2
+                    AND julianday(record.occurred_at) >= julianday(?3)
+                    AND julianday(record.occurred_at) < julianday(?4)
+            )
+            GROUP BY category_id
+            "#
+        );
+        let result = sqlx::query_as::<_, CategoryTotalDTO>(&query)
+            .bind(user_id)
+            .bind(currency.code())
+            .bind(from)
+            .bind(to)
+            .fetch_all(&mut *conn)
+            .await?;
+
+        Ok(result
+            .into_iter()
+            .map(|dto| (dto.category_id, dto.total))
+            .collect())
+    }
+
+    async fn spending_by_tag(
+        &self,
+        user_id: i64,
+        currency: &Currency,
+        from: DateTime<Local>,
+        to: DateTime<Local>,
+    ) -> Result<Vec<(i64, i64)>> {
+        let mut conn = self.acquire().await?;
+
+        let rate = rate();
+        let query = format!(
+            r#"
+            SELECT
+                record_tag.tag_id,
+                CAST(ROUND(SUM(record.amount * {rate})) AS INTEGER) as total
+            FROM record_tag
+            JOIN record ON record_tag.record_id = record.record_id
+            JOIN account ON record.account_id = account.account_id
+            WHERE record.user_id = ?1
+                AND record.deleted_at IS NULL
+                AND record.record_type = 2
+                AND julianday(record.occurred_at) >= julianday(?3)
+                AND julianday(record.occurred_at) < julianday(?4)
+            GROUP BY record_tag.tag_id
+            "#
+        );
+        let result = sqlx::query_as::<_, TagTotalDTO>(&query)
+            .bind(user_id)
+            .bind(currency.code())
+            .bind(from)
+            .bind(to)
+            .fetch_all(&mut *conn)
+            .await?;
+
+        Ok(result
+            .into_iter()
+            .map(|dto| (dto.tag_id, dto.total))
+            .collect())
+    }
+
+    async fn find_missing_exchange_rate(
+        &self,
+        user_id: i64,
+        currency: &Currency,
+        from: DateTime<Local>,
+        to: DateTime<Local>,
+    ) -> Result<Option<(Currency, NaiveDate)>> {
+        let mut conn = self.acquire().await?;
+
+        let rate = rate();
+        let query = format!(
+            r#"
+            SELECT account.currency, substr(record.occurred_at, 1, 10) as date
+            FROM record
+            JOIN account ON record.account_id = account.account_id
+            WHERE record.user_id = ?1
+                AND record.deleted_at IS NULL
+                AND record.record_type = 2
+                AND julianday(record.occurred_at) >= julianday(?3)
+                AND julianday(record.occurred_at) < julianday(?4)
+                AND ({rate}) IS NULL
+            ORDER BY julianday(record.occurred_at)
+            LIMIT 1
+            "#
+        );
+        let result = sqlx::query_as::<_, MissingExchangeRateDTO>(&query)
+            .bind(user_id)
+            .bind(currency.code())
+            .bind(from)
+            .bind(to)
+            .fetch_optional(&mut *conn)
+            .await?;
+
+        Ok(result.map(|dto| {
+            let currency =
+                Currency::from_str(&dto.currency).expect("cannot convert currency from db");
+            (currency, dto.date)
+        }))
+    }
+
+    async fn cash_flow(&self, user_id: i64, cmd: &CashFlowCmd) -> Result<Vec<CashFlowBucket>> {
+        let mut conn = self.acquire().await?;
+
+        let (truncate, step) = interval_modifiers(cmd.interval);
+        let ctes = cash_flow_ctes();
+        let query = format!(
+            r#"
+            WITH RECURSIVE
+            bucket(start) AS (
+                SELECT date(?5, {truncate})
+                UNION ALL
+                SELECT date(start, {step})
+                FROM bucket
+                WHERE date(start, {step}) <= ?6
+            ),
+            {ctes},
+            total(start, income, outcome) AS (
+                SELECT
+                    date(day, {truncate}),
+                    CAST(ROUND(SUM(CASE WHEN record_type = 1 THEN amount * rate END)) AS INTEGER),
+                    CAST(ROUND(SUM(CASE WHEN record_type = 2 THEN amount * rate END)) AS INTEGER)
+                FROM flow
+                GROUP BY 1
+            )
+            SELECT
+                bucket.start,
+                COALESCE(total.income, 0) as income,
+                COALESCE(total.outcome, 0) as outcome,
+                COALESCE(total.income, 0) - COALESCE(total.outcome, 0) as net,
+                (SELECT CAST(ROUND(COALESCE(SUM(balance * rate), 0)) AS INTEGER) FROM opening)
+                    + SUM(COALESCE(total.income, 0) - COALESCE(total.outcome, 0))
+                    OVER (ORDER BY bucket.start) as running_balance
+            FROM bucket
+            LEFT JOIN total ON total.start = bucket.start
+            ORDER BY bucket.start
+            "#
+        );
+        let result = bind_cash_flow(sqlx::query_as::<_, CashFlowBucketDTO>(&query), user_id, cmd)
+            .fetch_all(&mut *conn)
+            .await?;
+
+        Ok(result.into_iter().map(CashFlowBucket::from).collect())
+    }
+
+    async fn find_missing_cash_flow_rate(
+        &self,
+        user_id: i64,
+        cmd: &CashFlowCmd,
+    ) -> Result<Option<(Currency, NaiveDate)>> {
+        let mut conn = self.acquire().await?;
+
+        let ctes = cash_flow_ctes();
+        let query = format!(
+            r#"
+            WITH RECURSIVE
+            {ctes}
+            SELECT currency, day as date
+            FROM flow
+            WHERE rate IS NULL
+            UNION ALL
+            SELECT currency, ?5
+            FROM opening
+            WHERE rate IS NULL AND balance <> 0
+            ORDER BY 2
+            LIMIT 1
+            "#
+        );
+        let result = bind_cash_flow(
+            sqlx::query_as::<_, MissingExchangeRateDTO>(&query),
+            user_id,
+            cmd,
+        )
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        Ok(result.map(|dto| {
+            let currency =
+                Currency::from_str(&dto.currency).expect("cannot convert currency from db");
+            (currency, dto.date)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        domain::models::{Money, Record, RecordSplit},
+        repository::test::test_db,
+        service::budget::{CategoryRepository, RecordRepository},
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_spending_by_category() {
+        let fixture = include_str!("./fixtures/records.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let from = DateTime::parse_from_rfc3339("2026-03-01T00:00:00Z").unwrap();
+        let to = DateTime::parse_from_rfc3339("2026-04-01T00:00:00Z").unwrap();
+        let result = repo
+            .spending_by_category(1, &Currency::default(), from.into(), to.into())
+            .await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        let mut totals = result.unwrap();
+        totals.sort();
+        assert_eq!(totals, vec![(3, 2500), (4, 120)]);
+    }
+
+    #[tokio::test]
+    async fn test_cash_flow() {
+        let fixture = include_str!("./fixtures/records.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let cmd = |from: &str, to: &str, interval| CashFlowCmd {
+            from: DateTime::parse_from_rfc3339(from).unwrap().into(),
+            to: DateTime::parse_from_rfc3339(to).unwrap().into(),
+            currency: Currency::default(),
+            interval,
+            account_id: None,
+            category_id: None,
+        };
+        let totals = |buckets: Vec<CashFlowBucket>| {
+            buckets
+                .into_iter()
+                .map(|b| (b.start.to_string(), b.income, b.outcome, b.running_balance))
+                .collect::<Vec<_>>()
+        };
+        let months = cmd(
+            "2026-02-01T00:00:00Z",
+            "2026-05-01T00:00:00Z",
+            CashFlowInterval::Month,
+        );
+
+        // The fixture accounts are empty now, so they held -463.80 before
+        // the records of the period.
+        let result = repo.cash_flow(1, &months).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+        assert_eq!(
+            totals(result.unwrap()),
+            vec![
+                ("2026-02-01".into(), 0, 300, -46680),
+                ("2026-03-01".into(), 50000, 2620, 700),
+                ("2026-04-01".into(), 0, 700, 0),
+            ]
+        );
+
+        // Transfers count once the report is limited to an account.
+        let result = repo
+            .cash_flow(
+                1,
+                &CashFlowCmd {
+                    account_id: Some(1),
+                    ..months
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(result[1].income, 51000);
+        assert_eq!(result[1].outcome, 120);
+        assert_eq!(result[1].net, 50880);
+
+        let months = cmd(
+            "2026-02-01T00:00:00Z",
+            "2026-05-01T00:00:00Z",
+            CashFlowInterval::Month,
+        );
+        let result = repo
+            .cash_flow(
+                1,
+                &CashFlowCmd {
+                    category_id: Some(2),
+                    ..months
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            result.iter().map(|b| b.outcome).collect::<Vec<_>>(),
+            vec![300, 120, 700]
+        );
+
+        // 2026-03-01 is a Sunday, its week starts on the Monday before.
+        let weeks = cmd(
+            "2026-03-01T00:00:00Z",
+            "2026-03-15T00:00:00Z",
+            CashFlowInterval::Week,
+        );
+        let result = repo.cash_flow(1, &weeks).await.unwrap();
+        assert_eq!(
+            totals(result),
+            vec![
+                ("2026-02-23".into(), 0, 120, -46800),
+                ("2026-03-02".into(), 0, 0, -46800),
+                ("2026-03-09".into(), 0, 0, -46800),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_spending_by_category_counts_split_lines() {
+        let fixture = include_str!("./fixtures/records.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let from = DateTime::parse_from_rfc3339("2026-03-01T00:00:00Z").unwrap();
+        let to = DateTime::parse_from_rfc3339("2026-04-01T00:00:00Z").unwrap();
+
+        let mut record = Record::new(
+            1,
+            "Outcome".into(),
+            Money::new(1000, Currency::default()),
+            None,
+            None,
+        )
+        .unwrap();
+        record.occurred_at = DateTime::parse_from_rfc3339("2026-03-05T00:00:00Z")
+            .unwrap()
+            .into();
+        record
+            .set_splits(vec![
+                RecordSplit::new(repo.get_category_by_id(1, 2).await.unwrap(), 600, None).unwrap(),
+                RecordSplit::new(repo.get_category_by_id(1, 3).await.unwrap(), 400, None).unwrap(),
+            ])
+            .unwrap();
+        repo.create_record(1, record).await.unwrap();
+
+        let result = repo
+            .spending_by_category(1, &Currency::default(), from.into(), to.into())
+            .await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        let mut totals = result.unwrap();
+        totals.sort();
+        assert_eq!(totals, vec![(2, 600), (3, 2900), (4, 120)]);
+    }
+
+    #[tokio::test]
+    async fn test_spending_by_tag() {
+        let fixture = include_str!("./fixtures/records.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let from = DateTime::parse_from_rfc3339("2026-03-01T00:00:00Z").unwrap();
+        let to = DateTime::parse_from_rfc3339("2026-04-01T00:00:00Z").unwrap();
+        let result = repo
+            .spending_by_tag(1, &Currency::default(), from.into(), to.into())
+            .await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        let mut totals = result.unwrap();
+        totals.sort();
+        assert_eq!(totals, vec![(1, 2500), (2, 2620)]);
+    }
+}
@@ -0,0 +1,209 @@
+use async_trait::async_trait;
+
+use crate::{
+    domain::{
+        Result,
+        models::{Cursor, CursorKey, Page, Tag},
+    },
+    repository::{
+        SqliteBudgetRepo,
+        dto::{ReturnedId, TagDTO},
+        errors::not_found,
+        pages::{Keyset, SortColumn, fetch_page},
+    },
+    service::{
+        budget::{PageCmd, TagRepository},
+        records::SortOrder,
+    },
+};
+
+#[async_trait]
+impl TagRepository for SqliteBudgetRepo {
+    async fn list_tags(&self, user_id: i64) -> Result<Vec<Tag>> {
+        let page = self.list_tags_page(user_id, &PageCmd::default()).await?;
+
+        Ok(page.items)
+    }
+
+    async fn list_tags_page(&self, user_id: i64, page: &PageCmd) -> Result<Page<Tag>> {
+        let mut conn = self.acquire().await?;
+        let keyset = Keyset {
+            column: SortColumn::Name("name"),
+            id: "tag_id",
+            order: SortOrder::Asc,
+        };
+
+        fetch_page::<TagDTO, Tag>(
+            &mut conn,
+            &keyset,
+            page,
+            |query| {
+                query.push("SELECT tag_id, name FROM tag WHERE user_id = ");
+                query.push_bind(user_id);
+            },
+            |tag| Cursor::new(CursorKey::Name(Some(tag.name.clone())), tag.id),
+        )
+        .await
+    }
+
+    async fn create_tag(&self, user_id: i64, tag: Tag) -> Result<i64> {
+        let mut conn = self.acquire().await?;
+
+        let result = sqlx::query_as::<_, ReturnedId>(
+            r#"
+            INSERT INTO tag
+            (user_id,name)
+            VALUES(?,?)
+            RETURNING tag_id as id;
+            "#,
+        )
+        .bind(user_id)
+        .bind(tag.name)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(result.id)
+    }
+
+    async fn get_tag_by_id(&self, user_id: i64, id: i64) -> Result<Tag> {
+        let mut conn = self.acquire().await?;
+
+        let result = sqlx::query_as::<_, TagDTO>(
+            r#"
+            SELECT tag_id, name
+            FROM tag
+            WHERE tag_id = ? AND user_id = ?
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(not_found("tag", id))?;
+
+        Ok(result.into())
+    }
+
+    async fn find_tag_by_name(&self, user_id: i64, name: &str) -> Result<Option<Tag>> {
+        let mut conn = self.acquire().await?;
+
+        let result = sqlx::query_as::<_, TagDTO>(
+            r#"
+            SELECT tag_id, name
+            FROM tag
+            WHERE name = ? AND user_id = ?
+            "#,
+        )
+        .bind(name)
+        .bind(user_id)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        Ok(result.map(Tag::from))
+    }
+
+    async fn update_tag(&self, user_id: i64, tag: Tag) -> Result<()> {
+        let mut conn = self.acquire().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE tag
+            SET name = ?
+            WHERE tag_id = ? AND user_id = ?
+            "#,
+        )
+        .bind(tag.name)
+        .bind(tag.id)
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_tag(&self, user_id: i64, id: i64) -> Result<()> {
+        let mut conn = self.acquire().await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM tag
+            WHERE tag_id = ? AND user_id = ?
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn retag_records(&self, user_id: i64, source_id: i64, target_id: i64) -> Result<()> {
+        let mut conn = self.acquire().await?;
+
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO record_tag (record_id, tag_id)
+            SELECT record_tag.record_id, target.tag_id
+            FROM record_tag
+            JOIN tag source ON record_tag.tag_id = source.tag_id
+            JOIN tag target ON target.tag_id = ? AND target.user_id = source.user_id
+            WHERE source.tag_id = ? AND source.user_id = ?
+            "#,
+        )
+        .bind(target_id)
+        .bind(source_id)
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::repository::test::test_db;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_create_tag() {
+        let fixture = include_str!("./fixtures/fixture.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let id = repo
+            .create_tag(1, Tag::new("vacation-2026".into()).unwrap())
+            .await
+            .expect("must create tag");
+
+        let result = repo.get_tag_by_id(1, id).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+        assert_eq!(result.unwrap().name, "vacation-2026");
+
+        let result = repo.find_tag_by_name(1, "vacation-2026").await.unwrap();
+        assert_eq!(result.map(|t| t.id), Some(id));
+
+        let result = repo.get_tag_by_id(2, id).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_retag_records() {
+        let fixture = include_str!("./fixtures/records.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let result = repo.retag_records(1, 1, 2).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        let result = repo.retag_records(1, 3, 2).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        let tagged: Vec<(i64,)> =
+            sqlx::query_as("SELECT record_id FROM record_tag WHERE tag_id = 2 ORDER BY record_id")
+                .fetch_all(&repo.pool)
+                .await
+                .unwrap();
+        assert_eq!(tagged, vec![(1,), (2,), (3,)]);
+    }
+}
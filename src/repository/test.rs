@@ -1,7 +1,5 @@
-#[cfg(test)]
 use crate::repository::SqliteBudgetRepo;
 
-#[cfg(test)]
 pub async fn test_db(fixture: Option<&str>) -> SqliteBudgetRepo {
     use crate::repository::migrations;
 
@@ -0,0 +1,166 @@
+use std::{
+    ops::{Deref, DerefMut},
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use sqlx::{Sqlite, SqliteConnection, Transaction, pool::PoolConnection};
+use tokio::sync::{Mutex, MutexGuard};
+
+use crate::{
+    domain::{Result, errors::BudgetServiceError},
+    repository::SqliteBudgetRepo,
+    service::budget::BudgetRepository,
+};
+
+/// Connection used by a single repository call, either a pooled connection or
+/// the transaction the repository is bound to.
+pub(crate) enum SqliteConn<'a> {
+    Pool(PoolConnection<Sqlite>),
+    Transaction(MutexGuard<'a, Option<Transaction<'static, Sqlite>>>),
+}
+
+impl Deref for SqliteConn<'_> {
+    type Target = SqliteConnection;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Pool(conn) => conn,
+            Self::Transaction(tx) => tx.as_ref().expect("transaction is finished"),
+        }
+    }
+}
+
+impl DerefMut for SqliteConn<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            Self::Pool(conn) => conn,
+            Self::Transaction(tx) => tx.as_mut().expect("transaction is finished"),
+        }
+    }
+}
+
+impl SqliteBudgetRepo {
+    pub(crate) async fn acquire(&self) -> Result<SqliteConn<'_>> {
+        match &self.tx {
+            None => Ok(SqliteConn::Pool(self.pool.acquire().await?)),
+            Some(tx) => {
+                let guard = tx.lock().await;
+                if guard.is_none() {
+                    return Err(BudgetServiceError::TransactionError(
+                        "transaction is already finished",
+                    ));
+                }
+                Ok(SqliteConn::Transaction(guard))
+            }
+        }
+    }
+
+    async fn take_transaction(self) -> Result<Transaction<'static, Sqlite>> {
+        let tx = self.tx.ok_or(BudgetServiceError::TransactionError(
+            "repository is not in a transaction",
+        ))?;
+
+        tx.lock()
+            .await
+            .take()
+            .ok_or(BudgetServiceError::TransactionError(
+                "transaction is already finished",
+            ))
+    }
+}
+
+#[async_trait]
+impl BudgetRepository for SqliteBudgetRepo {
+    async fn begin(&self) -> Result<Self> {
+        if self.tx.is_some() {
+            return Err(BudgetServiceError::TransactionError(
+                "nested transactions are not supported",
+            ));
+        }
+
+        let tx = self.pool.begin().await?;
+
+        Ok(Self {
+            pool: self.pool.clone(),
+            tx: Some(Arc::new(Mutex::new(Some(tx)))),
+        })
+    }
+
+    async fn commit(self) -> Result<()> {
+        self.take_transaction().await?.commit().await?;
+        Ok(())
+    }
+
+    async fn rollback(self) -> Result<()> {
+        self.take_transaction().await?.rollback().await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        domain::models::{Account, Currency},
+        repository::test::test_db,
+        service::budget::AccountRepository,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_commit() {
+        let fixture = include_str!("./fixtures/fixture.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let tx = repo.begin().await.expect("must begin transaction");
+        let account =
+            Account::new("committed".into(), 0, "Cash".into(), Currency::default()).unwrap();
+        let id = tx.create_account(1, account).await.unwrap();
+        let result = tx.commit().await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        let result = repo.get_account_by_id(1, id).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_rollback() {
+        let fixture = include_str!("./fixtures/fixture.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let tx = repo.begin().await.expect("must begin transaction");
+        let account =
+            Account::new("rolled back".into(), 0, "Cash".into(), Currency::default()).unwrap();
+        let id = tx.create_account(1, account).await.unwrap();
+        let result = tx.rollback().await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        let result = repo.get_account_by_id(1, id).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_drop_rolls_back() {
+        let fixture = include_str!("./fixtures/fixture.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let tx = repo.begin().await.expect("must begin transaction");
+        let account =
+            Account::new("dropped".into(), 0, "Cash".into(), Currency::default()).unwrap();
+        let id = tx.create_account(1, account).await.unwrap();
+        drop(tx);
+
+        let result = repo.get_account_by_id(1, id).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_nested_begin() {
+        let repo = test_db(None).await;
+
+        let tx = repo.begin().await.expect("must begin transaction");
+        let result = tx.begin().await;
+        assert!(result.is_err());
+    }
+}
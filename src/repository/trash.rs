@@ -0,0 +1,501 @@
+use async_trait::async_trait;
+use sqlx::{
+    Sqlite,
+    query::Query,
+    sqlite::SqliteArguments,
+    types::chrono::{DateTime, Local},
+};
+
+use crate::{
+    domain::{
+        Result,
+        models::{Account, Category, Cursor, CursorKey, Deleted, Page, PurgeReport, Record},
+    },
+    repository::{
+        SqliteBudgetRepo,
+        dto::{
+            AccountDTO, CategoryDTO, DeletedAccountDTO, DeletedCategoryDTO, DeletedRecordDTO,
+            FullRecordDTO,
+        },
+        errors::{not_found, still_referenced},
+        pages::{Keyset, SortColumn, fetch_page},
+        records::{load_splits, load_tags},
+    },
+    service::{
+        budget::{PageCmd, TrashRepository},
+        records::SortOrder,
+        trash::PurgeCmd,
+    },
+};
+
+/// Deleted items are listed most recently deleted first.
+fn deleted_keyset(deleted_at: &'static str, id: &'static str) -> Keyset {
+    Keyset {
+        column: SortColumn::Date(deleted_at),
+        id,
+        order: SortOrder::Desc,
+    }
+}
+
+/// Condition selecting the deleted rows of a [`PurgeCmd`], with the user
+/// bound as `?1` and the cutoff as `?2`.
+const PURGED: &str = r#"
+    deleted_at IS NOT NULL
+    AND (?1 IS NULL OR user_id = ?1)
+    AND (?2 IS NULL OR julianday(deleted_at) < julianday(?2))
+"#;
+
+fn bind_purge<'q>(
+    query: Query<'q, Sqlite, SqliteArguments<'q>>,
+    cmd: &PurgeCmd,
+) -> Query<'q, Sqlite, SqliteArguments<'q>> {
+    query.bind(cmd.user_id).bind(cmd.before)
+}
+
+#[async_trait]
+impl TrashRepository for SqliteBudgetRepo {
+    async fn list_deleted_records(
+        &self,
+        user_id: i64,
+        page: &PageCmd,
+    ) -> Result<Page<Deleted<Record>>> {
+        let mut conn = self.acquire().await?;
+
+        let mut page = fetch_page::<DeletedRecordDTO, Deleted<Record>>(
+            &mut conn,
+            &deleted_keyset("record.deleted_at", "record.record_id"),
+            page,
+            |query| {
+                query.push(
+                    r#"
+                    SELECT
+                        record.record_id,
+                        record.account_id,
+                        record.amount,
+                        record.description,
+                        record_type.name as 'record_type',
+                        record.transfer_account_id,
+                        record.transfer_amount,
+                        account.currency,
+                        destination.currency as 'transfer_currency',
+                        record.occurred_at,
+                        record.posted_at,
+                        record.created_at,
+                        record.updated_at,
+                        record.deleted_at,
+                        category.category_id,
+                        category.name,
+                        category.budget,
+                        category.parent_id
+                    FROM record
+                    JOIN record_type ON record.record_type = record_type.record_type_id
+                    JOIN account ON record.account_id = account.account_id
+                    LEFT JOIN account AS destination
+                        ON record.transfer_account_id = destination.account_id
+                    LEFT JOIN category ON record.category_id = category.category_id
+                    WHERE record.deleted_at IS NOT NULL AND record.user_id = "#,
+                );
+                query.push_bind(user_id);
+            },
+            |deleted| Cursor::new(CursorKey::Date(deleted.deleted_at), deleted.item.id),
+        )
+        .await?;
+
+        let (mut records, deleted_at): (Vec<Record>, Vec<DateTime<Local>>) = page
+            .items
+            .into_iter()
+            .map(|deleted| (deleted.item, deleted.deleted_at))
+            .unzip();
+        load_splits(&mut conn, &mut records).await?;
+        load_tags(&mut conn, &mut records).await?;
+        page.items = records
+            .into_iter()
+            .zip(deleted_at)
+            .map(|(item, deleted_at)| Deleted { item, deleted_at })
+            .collect();
+
+        Ok(page)
+    }
+
+    async fn list_deleted_accounts(
+        &self,
+        user_id: i64,
+        page: &PageCmd,
+    ) -> Result<Page<Deleted<Account>>> {
+        let mut conn = self.acquire().await?;
+
+        fetch_page::<DeletedAccountDTO, Deleted<Account>>(
+            &mut conn,
+            &deleted_keyset("deleted_at", "account_id"),
+            page,
+            |query| {
+                query.push(
+                    r#"
+                    SELECT account_id, name, current_balance, account_type, currency, deleted_at
+                    FROM account
+                    WHERE deleted_at IS NOT NULL AND user_id = "#,
+                );
+                query.push_bind(user_id);
+            },
+            |deleted| Cursor::new(CursorKey::Date(deleted.deleted_at), deleted.item.id),
+        )
+        .await
+    }
+
+    async fn list_deleted_categories(
+        &self,
+        user_id: i64,
+        page: &PageCmd,
+    ) -> Result<Page<Deleted<Category>>> {
+        let mut conn = self.acquire().await?;
+
+        fetch_page::<DeletedCategoryDTO, Deleted<Category>>(
+            &mut conn,
+            &deleted_keyset("deleted_at", "category_id"),
+            page,
+            |query| {
+                query.push(
+                    r#"
+                    SELECT category_id, name, budget, parent_id, deleted_at
+                    FROM category
+                    WHERE deleted_at IS NOT NULL AND user_id = "#,
+                );
+                query.push_bind(user_id);
+            },
+            |deleted| Cursor::new(CursorKey::Date(deleted.deleted_at), deleted.item.id),
+        )
+        .await
+    }
+
+    async fn get_deleted_record_by_id(&self, user_id: i64, id: i64) -> Result<Record> {
+        let mut conn = self.acquire().await?;
+
+        let record = sqlx::query_as::<_, FullRecordDTO>(
+            r#"
+            SELECT
+                record.record_id,
+                record.account_id,
+                record.amount,
+                record.description,
+                record_type.name as 'record_type',
+                record.transfer_account_id,
+                record.transfer_amount,
+                account.currency,
+                destination.currency as 'transfer_currency',
+                record.occurred_at,
+                record.posted_at,
+                record.created_at,
+                record.updated_at,
+                category.category_id,
+                category.name,
+                category.budget,
+                category.parent_id
+            FROM record
+            JOIN record_type ON record.record_type = record_type.record_type_id
+            JOIN account ON record.account_id = account.account_id
+            LEFT JOIN account AS destination ON record.transfer_account_id = destination.account_id
+            LEFT JOIN category ON record.category_id = category.category_id
+            WHERE record.record_id = ? AND record.user_id = ? AND record.deleted_at IS NOT NULL
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(not_found("deleted record", id))?;
+
+        let mut record = Record::from(record);
+        load_splits(&mut conn, std::slice::from_mut(&mut record)).await?;
+        load_tags(&mut conn, std::slice::from_mut(&mut record)).await?;
+
+        Ok(record)
+    }
+
+    async fn get_deleted_account_by_id(&self, user_id: i64, id: i64) -> Result<Account> {
+        let mut conn = self.acquire().await?;
+
+        let result = sqlx::query_as::<_, AccountDTO>(
+            r#"
+            SELECT account_id, name, current_balance, account_type, currency
+            FROM account
+            WHERE account_id = ? AND user_id = ? AND deleted_at IS NOT NULL
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(not_found("deleted account", id))?;
+
+        Ok(result.into())
+    }
+
+    async fn get_deleted_category_by_id(&self, user_id: i64, id: i64) -> Result<Category> {
+        let mut conn = self.acquire().await?;
+
+        let result = sqlx::query_as::<_, CategoryDTO>(
+            r#"
+            SELECT category_id, name, budget, parent_id
+            FROM category
+            WHERE category_id = ? AND user_id = ? AND deleted_at IS NOT NULL
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(not_found("deleted category", id))?;
+
+        Ok(result.into())
+    }
+
+    async fn restore_record(&self, user_id: i64, id: i64) -> Result<()> {
+        let mut conn = self.acquire().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE record
+                SET deleted_at = NULL
+            WHERE record_id = ? AND user_id = ?
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn restore_account(&self, user_id: i64, id: i64) -> Result<()> {
+        let mut conn = self.acquire().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE account
+                SET deleted_at = NULL
+            WHERE account_id = ? AND user_id = ?
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn restore_category(&self, user_id: i64, id: i64) -> Result<()> {
+        let mut conn = self.acquire().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE category
+                SET deleted_at = NULL
+            WHERE category_id = ? AND user_id = ?
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn purge_deleted(&self, cmd: &PurgeCmd) -> Result<PurgeReport> {
+        let mut conn = self.acquire().await?;
+
+        // Deleted subcategories of purged categories outlive their parent.
+        let query = format!(
+            r#"
+            UPDATE category
+                SET parent_id = NULL
+            WHERE parent_id IN (
+                SELECT category_id
+                FROM category
+                WHERE {PURGED}
+            )
+            "#
+        );
+        bind_purge(sqlx::query(&query), cmd)
+            .execute(&mut *conn)
+            .await?;
+
+        let query = format!(
+            r#"
+            WITH
+                purged_account(account_id) AS (
+                    SELECT account_id
+                    FROM account
+                    WHERE {PURGED}
+                ),
+                purged_category(category_id) AS (
+                    SELECT category_id
+                    FROM category
+                    WHERE {PURGED}
+                )
+            DELETE FROM record
+            WHERE deleted_at IS NOT NULL
+                AND (
+                    ({PURGED})
+                    OR account_id IN (SELECT account_id FROM purged_account)
+                    OR transfer_account_id IN (SELECT account_id FROM purged_account)
+                    OR category_id IN (SELECT category_id FROM purged_category)
+                    OR record_id IN (
+                        SELECT record_id
+                        FROM record_split
+                        WHERE category_id IN (SELECT category_id FROM purged_category)
+                    )
+                )
+            "#
+        );
+        let records = bind_purge(sqlx::query(&query), cmd)
+            .execute(&mut *conn)
+            .await?;
+
+        let query = format!("DELETE FROM category WHERE {PURGED}");
+        let categories = bind_purge(sqlx::query(&query), cmd)
+            .execute(&mut *conn)
+            .await
+            .map_err(still_referenced("category"))?;
+
+        let query = format!("DELETE FROM account WHERE {PURGED}");
+        let accounts = bind_purge(sqlx::query(&query), cmd)
+            .execute(&mut *conn)
+            .await
+            .map_err(still_referenced("account"))?;
+
+        Ok(PurgeReport {
+            records: records.rows_affected(),
+            accounts: accounts.rows_affected(),
+            categories: categories.rows_affected(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        domain::errors::BudgetServiceError,
+        repository::test::test_db,
+        service::budget::{AccountRepository, CategoryRepository, RecordRepository},
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_restore_record() {
+        let fixture = include_str!("./fixtures/records.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        repo.delete_record(1, 3).await.expect("must delete record");
+        assert!(repo.get_record_by_id(1, 3).await.is_err());
+
+        let all = PageCmd::default();
+        let deleted = repo.list_deleted_records(1, &all).await.unwrap().items;
+        assert_eq!(deleted.len(), 1);
+        assert_eq!(deleted[0].item.id, 3);
+        assert_eq!(deleted[0].item.tags.len(), 2);
+        assert!(
+            repo.list_deleted_records(2, &all)
+                .await
+                .unwrap()
+                .items
+                .is_empty()
+        );
+
+        repo.restore_record(1, 3)
+            .await
+            .expect("must restore record");
+        assert!(repo.get_record_by_id(1, 3).await.is_ok());
+        assert!(repo.get_deleted_record_by_id(1, 3).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_list_deleted_records_page() {
+        let fixture = include_str!("./fixtures/records.sql");
+        let repo = test_db(Some(fixture)).await;
+        repo.delete_record(1, 3).await.unwrap();
+        repo.delete_record(1, 4).await.unwrap();
+
+        let page = PageCmd {
+            limit: Some(1),
+            after: None,
+            include_total: true,
+        };
+        let first = repo.list_deleted_records(1, &page).await.unwrap();
+        assert_eq!(first.items[0].item.id, 4);
+        assert_eq!(first.total_count, Some(2));
+
+        let page = PageCmd {
+            after: first.next_cursor,
+            ..page
+        };
+        let second = repo.list_deleted_records(1, &page).await.unwrap();
+        assert_eq!(second.items[0].item.id, 3);
+        assert_eq!(second.items[0].item.tags.len(), 2);
+        assert_eq!(second.next_cursor, None);
+    }
+
+    #[tokio::test]
+    async fn test_purge_referenced_category() {
+        let fixture = include_str!("./fixtures/records.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        // Record 6 still uses category 2.
+        repo.delete_category(1, 2).await.unwrap();
+
+        let result = repo.purge_deleted(&PurgeCmd::default()).await;
+        assert!(
+            matches!(&result, Err(BudgetServiceError::ConflictError(message)) if message == "category is still in use"),
+            "{result:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_purge_deleted() {
+        let fixture = include_str!("./fixtures/records.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        // Category 5 is referenced only by record 4.
+        repo.delete_record(1, 4).await.unwrap();
+        repo.delete_category(1, 5).await.unwrap();
+        repo.delete_record(1, 3).await.unwrap();
+        repo.delete_record(2, 7).await.unwrap();
+
+        let cmd = PurgeCmd {
+            user_id: None,
+            before: Some(Local::now() - std::time::Duration::from_secs(60)),
+        };
+        let result = repo.purge_deleted(&cmd).await;
+        assert_eq!(result.unwrap(), PurgeReport::default());
+
+        let cmd = PurgeCmd {
+            user_id: Some(1),
+            before: Some(Local::now() + std::time::Duration::from_secs(60)),
+        };
+        let result = repo.purge_deleted(&cmd).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+        assert_eq!(
+            result.unwrap(),
+            PurgeReport {
+                records: 2,
+                accounts: 0,
+                categories: 1,
+            }
+        );
+
+        let all = PageCmd::default();
+        assert!(
+            repo.list_deleted_records(1, &all)
+                .await
+                .unwrap()
+                .items
+                .is_empty()
+        );
+        assert!(repo.get_deleted_category_by_id(1, 5).await.is_err());
+        assert_eq!(repo.list_accounts(1).await.unwrap().len(), 2);
+        assert!(repo.get_deleted_record_by_id(2, 7).await.is_ok());
+    }
+}
@@ -0,0 +1,195 @@
+use async_trait::async_trait;
+
+use crate::{
+    domain::{Result, models::User},
+    repository::{
+        SqliteBudgetRepo,
+        dto::{ReturnedId, UserDTO},
+        errors::not_found,
+    },
+    service::budget::UserRepository,
+};
+
+#[async_trait]
+impl UserRepository for SqliteBudgetRepo {
+    async fn list_users(&self) -> Result<Vec<User>> {
+        let mut conn = self.acquire().await?;
+
+        let result = sqlx::query_as::<_, UserDTO>(
+            r#"
+            SELECT user_id, username, reporting_currency, created_at
+            FROM user
+            "#,
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let result: Vec<User> = result.into_iter().map(User::from).collect();
+
+        Ok(result)
+    }
+
+    async fn create_user(&self, user: User) -> Result<i64> {
+        let mut conn = self.acquire().await?;
+
+        let result = sqlx::query_as::<_, ReturnedId>(
+            r#"
+            INSERT INTO user
+            (username,reporting_currency,created_at)
+            VALUES(?,?,?)
+            RETURNING user_id as id;
+            "#,
+        )
+        .bind(user.username)
+        .bind(user.reporting_currency.code())
+        .bind(user.created_at)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(result.id)
+    }
+
+    async fn get_user_by_id(&self, id: i64) -> Result<User> {
+        let mut conn = self.acquire().await?;
+
+        let result = sqlx::query_as::<_, UserDTO>(
+            r#"
+            SELECT user_id, username, reporting_currency, created_at
+            FROM user
+            WHERE user_id = ?
+            "#,
+        )
+        .bind(id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(not_found("user", id))?;
+
+        Ok(result.into())
+    }
+
+    async fn get_user_by_username(&self, username: &str) -> Result<User> {
+        let mut conn = self.acquire().await?;
+
+        let result = sqlx::query_as::<_, UserDTO>(
+            r#"
+            SELECT user_id, username, reporting_currency, created_at
+            FROM user
+            WHERE username = ?
+            "#,
+        )
+        .bind(username)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(result.into())
+    }
+
+    async fn update_user(&self, user: User) -> Result<()> {
+        let mut conn = self.acquire().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE user
+                SET username = ?, reporting_currency = ?
+            WHERE user_id = ?
+            "#,
+        )
+        .bind(user.username)
+        .bind(user.reporting_currency.code())
+        .bind(user.id)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_user(&self, id: i64) -> Result<()> {
+        let mut conn = self.acquire().await?;
+
+        sqlx::query(
+            r#"
+            DELETE
+            FROM user
+            WHERE user_id = ?
+            "#,
+        )
+        .bind(id)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::repository::test::test_db;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_create_user() {
+        let fixture = include_str!("./fixtures/fixture.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let user = User::new("alice".into()).unwrap();
+        let result = repo.create_user(user).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        let user = repo
+            .get_user_by_id(result.unwrap())
+            .await
+            .expect("must find user");
+        assert_eq!(user.username, "alice");
+    }
+
+    #[tokio::test]
+    async fn test_create_user_with_taken_username() {
+        let fixture = include_str!("./fixtures/fixture.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let user = User::new("test user".into()).unwrap();
+        let result = repo.create_user(user).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_user_by_username() {
+        let fixture = include_str!("./fixtures/fixture.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let user = repo
+            .get_user_by_username("test user")
+            .await
+            .expect("must find user");
+        assert_eq!(user.id, 1);
+    }
+
+    #[tokio::test]
+    async fn test_update_user() {
+        let fixture = include_str!("./fixtures/fixture.sql");
+        let repo = test_db(Some(fixture)).await;
+
+        let mut user = repo.get_user_by_id(1).await.expect("must find user");
+        user.set_username("renamed".into()).unwrap();
+        let result = repo.update_user(user.clone()).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        let updated_user = repo.get_user_by_id(1).await.expect("must find user");
+        assert_eq!(user, updated_user);
+    }
+
+    #[tokio::test]
+    async fn test_delete_user() {
+        let repo = test_db(None).await;
+
+        let user = User::new("bob".into()).unwrap();
+        let id = repo.create_user(user).await.unwrap();
+
+        let result = repo.delete_user(id).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        let result = repo.get_user_by_id(id).await;
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,41 @@
+use std::{sync::Arc, time::Duration};
+
+use sqlx::types::chrono::Local;
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+
+use crate::{domain::models::PurgeReport, service::budget::BudgetService};
+
+/// Spawns a task that creates records of due recurring records and purges
+/// trash older than `retention` every `interval`, the first run happens
+/// immediately.
+pub fn spawn(
+    svc: Arc<dyn BudgetService>,
+    interval: Duration,
+    retention: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+
+            match svc.materialize_recurring_records(Local::now()).await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!("created {count} records from recurring records"),
+                Err(e) => tracing::error!("cannot create records from recurring records: {e}"),
+            }
+
+            match svc.purge_trash(Local::now() - retention).await {
+                Ok(report) if report == PurgeReport::default() => {}
+                Ok(report) => tracing::info!(
+                    "purged {} records, {} accounts and {} categories from the trash",
+                    report.records,
+                    report.accounts,
+                    report.categories
+                ),
+                Err(e) => tracing::error!("cannot purge trash: {e}"),
+            }
+        }
+    })
+}
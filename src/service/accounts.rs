@@ -1,22 +1,38 @@
 use async_trait::async_trait;
 
 use crate::{
-    domain::{Result, models::Account},
-    service::budget::{BudgetRepository, BudgetServiceImpl},
+    domain::{
+        Result,
+        errors::BudgetServiceError,
+        models::{
+            Account, AccountError, Amount, AuditAction, Currency, DeletePolicy, DeletePolicyError,
+            Page,
+        },
+    },
+    service::{
+        audit::audit,
+        budget::{BudgetRepository, BudgetServiceImpl, PageCmd},
+        records::{ListRecordsCmd, apply_balance_changes},
+    },
 };
 
 #[async_trait]
 pub trait BudgetAccountsService: Send + Sync + 'static {
-    async fn list_accounts(&self) -> Result<Vec<Account>>;
-    async fn create_account(&self, cmd: CreateAccountCmd) -> Result<Account>;
-    async fn update_account(&self, cmd: UpdateAccountCmd) -> Result<Account>;
-    async fn delete_account(&self, id: i64) -> Result<()>;
+    /// Accounts ordered by id.
+    async fn list_accounts(&self, user_id: i64, page: PageCmd) -> Result<Page<Account>>;
+    async fn get_account(&self, user_id: i64, id: i64) -> Result<Account>;
+    async fn create_account(&self, user_id: i64, cmd: CreateAccountCmd) -> Result<Account>;
+    async fn update_account(&self, user_id: i64, cmd: UpdateAccountCmd) -> Result<Account>;
+    async fn delete_account(&self, user_id: i64, cmd: DeleteAccountCmd) -> Result<()>;
 }
 
 pub struct CreateAccountCmd {
     pub name: String,
     pub account_type: String,
-    pub initial_balance: i64,
+    /// In the currency of the account.
+    pub initial_balance: Amount,
+    /// Defaults to the reporting currency of the user.
+    pub currency: Option<Currency>,
 }
 
 pub struct UpdateAccountCmd {
@@ -24,27 +40,266 @@ pub struct UpdateAccountCmd {
     pub name: String,
 }
 
+pub struct DeleteAccountCmd {
+    pub id: i64,
+    /// Applies to records and recurring records of the account, including
+    /// transfers into it.
+    pub policy: DeletePolicy,
+}
+
 #[async_trait]
 impl<T: BudgetRepository> BudgetAccountsService for BudgetServiceImpl<T> {
-    async fn list_accounts(&self) -> Result<Vec<Account>> {
-        Ok(self.repo.list_accounts().await?)
+    async fn list_accounts(&self, user_id: i64, page: PageCmd) -> Result<Page<Account>> {
+        Ok(self.repo.list_accounts_page(user_id, &page).await?)
     }
 
-    async fn create_account(&self, cmd: CreateAccountCmd) -> Result<Account> {
-        let acc = Account::new(cmd.name, cmd.initial_balance, cmd.account_type)?;
-        let acc_id = self.repo.create_account(acc).await?;
-        Ok(self.repo.get_account_by_id(acc_id).await?)
+    async fn get_account(&self, user_id: i64, id: i64) -> Result<Account> {
+        Ok(self.repo.get_account_by_id(user_id, id).await?)
     }
 
-    async fn update_account(&self, cmd: UpdateAccountCmd) -> Result<Account> {
-        let mut acc = self.repo.get_account_by_id(cmd.id).await?;
+    async fn create_account(&self, user_id: i64, cmd: CreateAccountCmd) -> Result<Account> {
+        let tx = self.repo.begin().await?;
+        let currency = match cmd.currency {
+            Some(currency) => currency,
+            None => tx.get_user_by_id(user_id).await?.reporting_currency,
+        };
+        let balance = cmd
+            .initial_balance
+            .to_money(&currency)
+            .map_err(AccountError::InvalidInitialBalance)?;
+        let acc = Account::new(cmd.name, balance.minor(), cmd.account_type, currency)?;
+        let acc_id = tx.create_account(user_id, acc).await?;
+        let acc = tx.get_account_by_id(user_id, acc_id).await?;
+        audit(&tx, user_id, AuditAction::Create, None, Some(&acc)).await?;
+        tx.commit().await?;
+
+        Ok(acc)
+    }
+
+    async fn update_account(&self, user_id: i64, cmd: UpdateAccountCmd) -> Result<Account> {
+        let tx = self.repo.begin().await?;
+        let before = tx.get_account_by_id(user_id, cmd.id).await?;
+        let mut acc = before.clone();
         acc.name = cmd.name;
-        self.repo.update_account(acc).await?;
-        Ok(self.repo.get_account_by_id(cmd.id).await?)
+        tx.update_account(user_id, acc).await?;
+        let acc = tx.get_account_by_id(user_id, cmd.id).await?;
+        audit(&tx, user_id, AuditAction::Update, Some(&before), Some(&acc)).await?;
+        tx.commit().await?;
+
+        Ok(acc)
     }
 
-    async fn delete_account(&self, id: i64) -> Result<()> {
-        self.repo.delete_account(id).await?;
+    async fn delete_account(&self, user_id: i64, cmd: DeleteAccountCmd) -> Result<()> {
+        let tx = self.repo.begin().await?;
+        let account = tx.get_account_by_id(user_id, cmd.id).await?;
+
+        let records = tx
+            .list_records(
+                user_id,
+                ListRecordsCmd {
+                    account_id: Some(cmd.id),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        let recurring: Vec<_> = tx
+            .list_recurring_records(user_id)
+            .await?
+            .into_iter()
+            .filter(|r| r.uses_account(cmd.id))
+            .collect();
+
+        match cmd.policy {
+            DeletePolicy::Refuse => {
+                if !records.is_empty() || !recurring.is_empty() {
+                    return Err(BudgetServiceError::ConflictError(format!(
+                        "account {} is used by {} records and {} recurring records",
+                        cmd.id,
+                        records.len(),
+                        recurring.len()
+                    )));
+                }
+            }
+            DeletePolicy::Reassign(target_id) => {
+                if target_id == cmd.id {
+                    return Err(DeletePolicyError::ReassignToItself.into());
+                }
+                let target = tx.get_account_by_id(user_id, target_id).await?;
+                // Amounts are kept in minor units of the account's currency.
+                if target.currency != account.currency {
+                    return Err(DeletePolicyError::ReassignToOtherCurrency {
+                        from: account.currency,
+                        to: target.currency,
+                    }
+                    .into());
+                }
+
+                for before in records {
+                    let mut record = before.clone();
+                    apply_balance_changes(&tx, user_id, record.balance_changes(), -1).await?;
+                    record.replace_account(cmd.id, target_id)?;
+                    apply_balance_changes(&tx, user_id, record.balance_changes(), 1).await?;
+                    tx.update_record(user_id, record.clone()).await?;
+                    audit(
+                        &tx,
+                        user_id,
+                        AuditAction::Update,
+                        Some(&before),
+                        Some(&record),
+                    )
+                    .await?;
+                }
+                for before in recurring {
+                    let mut recurring = before.clone();
+                    recurring.replace_account(cmd.id, target_id)?;
+                    tx.update_recurring_record(recurring.clone()).await?;
+                    audit(
+                        &tx,
+                        user_id,
+                        AuditAction::Update,
+                        Some(&before),
+                        Some(&recurring),
+                    )
+                    .await?;
+                }
+            }
+            DeletePolicy::Cascade => {
+                for record in records {
+                    apply_balance_changes(&tx, user_id, record.balance_changes(), -1).await?;
+                    tx.delete_record(user_id, record.id).await?;
+                    audit(&tx, user_id, AuditAction::Delete, Some(&record), None).await?;
+                }
+                for recurring in recurring {
+                    tx.delete_recurring_record(user_id, recurring.id).await?;
+                    audit(&tx, user_id, AuditAction::Delete, Some(&recurring), None).await?;
+                }
+            }
+        }
+
+        tx.delete_account(user_id, cmd.id).await?;
+        audit(&tx, user_id, AuditAction::Delete, Some(&account), None).await?;
+        tx.commit().await?;
+
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        domain::models::RecordError,
+        repository::test::test_db,
+        service::{budget::AccountRepository, records::BudgetRecordService},
+    };
+
+    use super::*;
+
+    async fn record_ids<T: BudgetRepository>(svc: &BudgetServiceImpl<T>) -> Vec<i64> {
+        svc.list_records(1, ListRecordsCmd::default())
+            .await
+            .unwrap()
+            .items
+            .iter()
+            .map(|r| r.id)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_delete_account_refuses_when_used() {
+        let fixture = include_str!("../repository/fixtures/records.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        let cmd = DeleteAccountCmd {
+            id: 1,
+            policy: DeletePolicy::Refuse,
+        };
+        let result = svc.delete_account(1, cmd).await;
+        assert!(matches!(result, Err(BudgetServiceError::ConflictError(_))));
+        assert!(svc.repo.get_account_by_id(1, 1).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_delete_account_reassigns_records() {
+        let fixture = include_str!("../repository/fixtures/records.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        let cmd = DeleteAccountCmd {
+            id: 2,
+            policy: DeletePolicy::Reassign(1),
+        };
+        let result = svc.delete_account(1, cmd).await;
+        assert!(matches!(
+            result,
+            Err(BudgetServiceError::RecordValidationError(
+                RecordError::TransferToSameAccount
+            ))
+        ));
+
+        let target = svc
+            .create_account(
+                1,
+                CreateAccountCmd {
+                    name: "new card".into(),
+                    account_type: "DebitCard".into(),
+                    initial_balance: 0.into(),
+                    currency: None,
+                },
+            )
+            .await
+            .unwrap();
+        let cmd = DeleteAccountCmd {
+            id: 2,
+            policy: DeletePolicy::Reassign(target.id),
+        };
+        let result = svc.delete_account(1, cmd).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        assert_eq!(record_ids(&svc).await, vec![6, 5, 4, 3, 2, 1]);
+        let target = svc.repo.get_account_by_id(1, target.id).await.unwrap();
+        assert_eq!(target.balance, -3500);
+        let wallet = svc.repo.get_account_by_id(1, 1).await.unwrap();
+        assert_eq!(wallet.balance, 0);
+    }
+
+    #[tokio::test]
+    async fn test_delete_account_cascades() {
+        let fixture = include_str!("../repository/fixtures/records.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        let cmd = DeleteAccountCmd {
+            id: 2,
+            policy: DeletePolicy::Cascade,
+        };
+        let result = svc.delete_account(1, cmd).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        assert_eq!(record_ids(&svc).await, vec![6, 4, 2, 1]);
+        assert!(svc.repo.get_account_by_id(1, 2).await.is_err());
+        let wallet = svc.repo.get_account_by_id(1, 1).await.unwrap();
+        assert_eq!(wallet.balance, -1000);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_initial_balance_field() {
+        let fixture = include_str!("../repository/fixtures/fixture.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        let cmd = CreateAccountCmd {
+            name: "card".into(),
+            account_type: "DebitCard".into(),
+            initial_balance: Amount::Decimal("1.005".into()),
+            currency: None,
+        };
+        let error = svc.create_account(1, cmd).await.unwrap_err();
+        let fields: Vec<_> = error.field_errors().into_iter().map(|(f, _)| f).collect();
+        assert_eq!(fields, vec!["initial_balance"]);
+
+        let cmd = DeleteAccountCmd {
+            id: 1,
+            policy: DeletePolicy::Reassign(1),
+        };
+        let error = svc.delete_account(1, cmd).await.unwrap_err();
+        let fields: Vec<_> = error.field_errors().into_iter().map(|(f, _)| f).collect();
+        assert_eq!(fields, vec!["target_id"]);
+    }
+}
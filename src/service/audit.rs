@@ -0,0 +1,159 @@
+use async_trait::async_trait;
+use sqlx::types::chrono::{DateTime, Local};
+
+use crate::{
+    domain::{
+        Result,
+        models::{AuditAction, AuditEntity, AuditEntry, Auditable, Page},
+    },
+    service::{
+        budget::{BudgetRepository, BudgetServiceImpl, PageCmd},
+        records::SortOrder,
+    },
+};
+
+/// Filters of the activity feed.
+#[derive(Default)]
+pub struct ListAuditEntriesCmd {
+    pub entity_type: Option<AuditEntity>,
+    pub entity_id: Option<i64>,
+    pub action: Option<AuditAction>,
+    /// Inclusive lower bound of the change time.
+    pub from: Option<DateTime<Local>>,
+    /// Exclusive upper bound of the change time.
+    pub to: Option<DateTime<Local>>,
+    /// Order of the change time, newest first by default.
+    pub order: SortOrder,
+}
+
+#[async_trait]
+pub trait BudgetAuditService: Send + Sync + 'static {
+    /// Changes of a single entity, oldest first, including entities that
+    /// have since been deleted.
+    async fn entity_history(
+        &self,
+        user_id: i64,
+        entity_type: AuditEntity,
+        entity_id: i64,
+        page: PageCmd,
+    ) -> Result<Page<AuditEntry>>;
+    async fn activity_feed(
+        &self,
+        user_id: i64,
+        cmd: ListAuditEntriesCmd,
+        page: PageCmd,
+    ) -> Result<Page<AuditEntry>>;
+}
+
+#[async_trait]
+impl<T: BudgetRepository> BudgetAuditService for BudgetServiceImpl<T> {
+    async fn entity_history(
+        &self,
+        user_id: i64,
+        entity_type: AuditEntity,
+        entity_id: i64,
+        page: PageCmd,
+    ) -> Result<Page<AuditEntry>> {
+        let cmd = ListAuditEntriesCmd {
+            entity_type: Some(entity_type),
+            entity_id: Some(entity_id),
+            order: SortOrder::Asc,
+            ..Default::default()
+        };
+
+        Ok(self.repo.list_audit_entries(user_id, &cmd, &page).await?)
+    }
+
+    async fn activity_feed(
+        &self,
+        user_id: i64,
+        cmd: ListAuditEntriesCmd,
+        page: PageCmd,
+    ) -> Result<Page<AuditEntry>> {
+        Ok(self.repo.list_audit_entries(user_id, &cmd, &page).await?)
+    }
+}
+
+/// Appends an audit entry for a change the user made to one of their
+/// entities, `before` is `None` for creations and `after` for deletions.
+pub(crate) async fn audit<T: BudgetRepository, E: Auditable>(
+    repo: &T,
+    user_id: i64,
+    action: AuditAction,
+    before: Option<&E>,
+    after: Option<&E>,
+) -> Result<()> {
+    let entry = AuditEntry::new(user_id, Some(user_id), action, before, after);
+    repo.append_audit_entry(entry).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        repository::test::test_db,
+        service::records::{BudgetRecordService, UpdateRecordCmd},
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_record_history() {
+        let fixture = include_str!("../repository/fixtures/records.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        let result = svc
+            .update_record(
+                1,
+                UpdateRecordCmd {
+                    id: 1,
+                    amount: 450.into(),
+                    description: None,
+                    category_id: Some(2),
+                    destination_account_id: None,
+                    received_amount: None,
+                    splits: None,
+                    tag_ids: None,
+                    occurred_at: None,
+                    posted_at: None,
+                },
+            )
+            .await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+        svc.delete_record(1, 1).await.expect("must delete record");
+
+        let history = svc
+            .entity_history(1, AuditEntity::Record, 1, PageCmd::default())
+            .await
+            .unwrap()
+            .items;
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].action, AuditAction::Update);
+        assert_eq!(history[0].actor_id, Some(1));
+        assert_eq!(history[0].before.as_ref().unwrap()["amount"], 300);
+        assert_eq!(history[0].after.as_ref().unwrap()["amount"], 450);
+        assert_eq!(history[1].action, AuditAction::Delete);
+        assert_eq!(history[1].after, None);
+
+        let history = svc
+            .entity_history(2, AuditEntity::Record, 1, PageCmd::default())
+            .await
+            .unwrap();
+        assert!(history.items.is_empty());
+
+        let feed = svc
+            .activity_feed(
+                1,
+                ListAuditEntriesCmd {
+                    action: Some(AuditAction::Delete),
+                    ..Default::default()
+                },
+                PageCmd::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(feed.items.len(), 1);
+        assert_eq!(feed.items[0].entity_id, 1);
+    }
+}
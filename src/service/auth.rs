@@ -0,0 +1,291 @@
+use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier, password_hash::SaltString};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use sqlx::types::chrono::Local;
+
+use crate::{
+    domain::{
+        Result,
+        errors::BudgetServiceError,
+        models::{AuditAction, Page, Token, TokenKind, User, validate_password},
+    },
+    service::{
+        audit::audit,
+        budget::{BudgetRepository, BudgetServiceImpl, PageCmd},
+    },
+};
+
+const SESSION_TOKEN_TTL: Duration = Duration::from_secs(12 * 60 * 60);
+const TOKEN_SECRET_BYTES: usize = 32;
+
+pub struct LoginCmd {
+    pub username: String,
+    pub password: String,
+}
+
+pub struct CreateApiTokenCmd {
+    pub name: String,
+}
+
+/// Newly issued token, the secret is only available at creation time.
+pub struct IssuedToken {
+    pub secret: String,
+    pub token: Token,
+}
+
+#[async_trait]
+pub trait BudgetAuthService: Send + Sync + 'static {
+    async fn login(&self, cmd: LoginCmd) -> Result<IssuedToken>;
+    async fn authenticate(&self, secret: String) -> Result<(User, Token)>;
+    async fn list_api_tokens(&self, user_id: i64, page: PageCmd) -> Result<Page<Token>>;
+    async fn create_api_token(&self, user_id: i64, cmd: CreateApiTokenCmd) -> Result<IssuedToken>;
+    async fn revoke_token(&self, user_id: i64, id: i64) -> Result<()>;
+}
+
+#[async_trait]
+impl<T: BudgetRepository> BudgetAuthService for BudgetServiceImpl<T> {
+    async fn login(&self, cmd: LoginCmd) -> Result<IssuedToken> {
+        let invalid_credentials = BudgetServiceError::Unauthorized("invalid username or password");
+
+        let user = match self.repo.get_user_by_username(cmd.username.trim()).await {
+            Err(BudgetServiceError::DatabaseError(sqlx::Error::RowNotFound)) => {
+                return Err(invalid_credentials);
+            }
+            result => result?,
+        };
+
+        let Some(password_hash) = self.repo.get_password_hash(user.id).await? else {
+            return Err(invalid_credentials);
+        };
+
+        if !verify_password(cmd.password, password_hash).await {
+            return Err(invalid_credentials);
+        }
+
+        let expires_at = Local::now() + SESSION_TOKEN_TTL;
+        let token = Token::new(user.id, TokenKind::Session, None, Some(expires_at));
+
+        let tx = self.repo.begin().await?;
+        tx.delete_expired_tokens().await?;
+        let issued = issue_token(&tx, token).await?;
+        tx.commit().await?;
+
+        Ok(issued)
+    }
+
+    async fn authenticate(&self, secret: String) -> Result<(User, Token)> {
+        let token = match self.repo.get_token_by_hash(&hash_token(&secret)).await {
+            Err(BudgetServiceError::DatabaseError(sqlx::Error::RowNotFound)) => {
+                return Err(BudgetServiceError::Unauthorized("invalid token"));
+            }
+            result => result?,
+        };
+
+        if token.is_expired() {
+            return Err(BudgetServiceError::Unauthorized("token expired"));
+        }
+
+        let user = self.repo.get_user_by_id(token.user_id).await?;
+
+        Ok((user, token))
+    }
+
+    async fn list_api_tokens(&self, user_id: i64, page: PageCmd) -> Result<Page<Token>> {
+        Ok(self
+            .repo
+            .list_tokens(user_id, TokenKind::Api, &page)
+            .await?)
+    }
+
+    async fn create_api_token(&self, user_id: i64, cmd: CreateApiTokenCmd) -> Result<IssuedToken> {
+        let token = Token::new(user_id, TokenKind::Api, Some(cmd.name), None);
+
+        let tx = self.repo.begin().await?;
+        let issued = issue_token(&tx, token).await?;
+        audit(&tx, user_id, AuditAction::Create, None, Some(&issued.token)).await?;
+        tx.commit().await?;
+
+        Ok(issued)
+    }
+
+    async fn revoke_token(&self, user_id: i64, id: i64) -> Result<()> {
+        let tx = self.repo.begin().await?;
+        let token = tx.get_token_by_id(user_id, id).await?;
+        tx.delete_token(user_id, id).await?;
+        if token.kind == TokenKind::Api {
+            audit(&tx, user_id, AuditAction::Delete, Some(&token), None).await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+async fn issue_token<T: BudgetRepository>(repo: &T, token: Token) -> Result<IssuedToken> {
+    let mut bytes = [0u8; TOKEN_SECRET_BYTES];
+    rand::rng().fill_bytes(&mut bytes);
+    let secret = to_hex(&bytes);
+
+    let user_id = token.user_id;
+    let id = repo.create_token(token, hash_token(&secret)).await?;
+    let token = repo.get_token_by_id(user_id, id).await?;
+
+    Ok(IssuedToken { secret, token })
+}
+
+/// Tokens are stored as SHA-256 digests, they are random so a slow hash is
+/// not needed.
+fn hash_token(secret: &str) -> String {
+    to_hex(&Sha256::digest(secret.as_bytes()))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+pub(crate) async fn hash_password(password: String) -> Result<String> {
+    validate_password(&password)?;
+
+    let hash = tokio::task::spawn_blocking(move || {
+        let mut salt = [0u8; 16];
+        rand::rng().fill_bytes(&mut salt);
+        let salt = SaltString::encode_b64(&salt).expect("salt has a valid length");
+
+        Argon2::default()
+            .hash_password(password.as_bytes(), &salt)
+            .expect("cannot hash password")
+            .to_string()
+    })
+    .await
+    .expect("password hashing task panicked");
+
+    Ok(hash)
+}
+
+async fn verify_password(password: String, password_hash: String) -> bool {
+    tokio::task::spawn_blocking(move || {
+        let Ok(hash) = PasswordHash::new(&password_hash) else {
+            return false;
+        };
+
+        Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_ok()
+    })
+    .await
+    .expect("password verification task panicked")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        domain::models::AuditEntity,
+        repository::{SqliteBudgetRepo, test::test_db},
+        service::{
+            audit::BudgetAuditService,
+            budget::UserRepository,
+            users::{BudgetUsersService, CreateUserCmd},
+        },
+    };
+
+    use super::*;
+
+    async fn svc_with_user() -> BudgetServiceImpl<SqliteBudgetRepo> {
+        let svc = BudgetServiceImpl::new(test_db(None).await);
+        svc.create_user(CreateUserCmd {
+            username: "alice".into(),
+            password: "correct horse".into(),
+        })
+        .await
+        .expect("must create user");
+        svc
+    }
+
+    #[tokio::test]
+    async fn test_login_and_authenticate() {
+        let svc = svc_with_user().await;
+
+        let result = svc
+            .login(LoginCmd {
+                username: "alice".into(),
+                password: "correct horse".into(),
+            })
+            .await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        let issued = result.unwrap();
+        assert_eq!(issued.token.kind, TokenKind::Session);
+        assert!(issued.token.expires_at.is_some());
+
+        let (user, token) = svc
+            .authenticate(issued.secret)
+            .await
+            .expect("must authenticate");
+        assert_eq!(user.username, "alice");
+        assert_eq!(token, issued.token);
+    }
+
+    #[tokio::test]
+    async fn test_login_with_wrong_password() {
+        let svc = svc_with_user().await;
+
+        let result = svc
+            .login(LoginCmd {
+                username: "alice".into(),
+                password: "wrong password".into(),
+            })
+            .await;
+        assert!(matches!(result, Err(BudgetServiceError::Unauthorized(_))));
+
+        let result = svc
+            .login(LoginCmd {
+                username: "bob".into(),
+                password: "correct horse".into(),
+            })
+            .await;
+        assert!(matches!(result, Err(BudgetServiceError::Unauthorized(_))));
+    }
+
+    #[tokio::test]
+    async fn test_revoked_api_token() {
+        let svc = svc_with_user().await;
+        let user = svc.repo.get_user_by_username("alice").await.unwrap();
+
+        let issued = svc
+            .create_api_token(
+                user.id,
+                CreateApiTokenCmd {
+                    name: "import script".into(),
+                },
+            )
+            .await
+            .expect("must create token");
+        assert!(svc.authenticate(issued.secret.clone()).await.is_ok());
+
+        svc.revoke_token(user.id, issued.token.id)
+            .await
+            .expect("must revoke token");
+
+        let result = svc.authenticate(issued.secret).await;
+        assert!(matches!(result, Err(BudgetServiceError::Unauthorized(_))));
+
+        let history = svc
+            .entity_history(
+                user.id,
+                AuditEntity::Token,
+                issued.token.id,
+                PageCmd::default(),
+            )
+            .await
+            .unwrap();
+        let actions: Vec<_> = history.items.iter().map(|e| e.action).collect();
+        assert_eq!(actions, vec![AuditAction::Create, AuditAction::Delete]);
+        assert_eq!(
+            history.items[0].after.as_ref().unwrap()["name"],
+            "import script"
+        );
+    }
+}
@@ -1,51 +1,282 @@
 use async_trait::async_trait;
+use sqlx::types::chrono::{DateTime, Local, NaiveDate};
 
 use crate::{
     domain::{
         Result,
-        models::{Account, Category, Record},
+        models::{
+            Account, AuditEntry, CashFlowBucket, Category, Currency, Cursor, Deleted, ExchangeRate,
+            Page, PurgeReport, Record, RecurringRecord, Tag, Token, TokenKind, User,
+        },
     },
     service::{
         accounts::BudgetAccountsService,
+        audit::{BudgetAuditService, ListAuditEntriesCmd},
+        auth::BudgetAuthService,
         categories::BudgetCategoriesService,
+        currencies::{BudgetCurrenciesService, ListExchangeRatesCmd},
+        imports::BudgetImportsService,
         records::{BudgetRecordService, ListRecordsCmd},
+        recurring::BudgetRecurringService,
+        reports::{BudgetReportsService, CashFlowCmd},
+        tags::BudgetTagsService,
+        trash::{BudgetTrashService, PurgeCmd},
+        users::BudgetUsersService,
     },
 };
 
 #[async_trait]
 pub trait AccountRepository: Clone + Sync + 'static {
-    async fn list_accounts(&self) -> Result<Vec<Account>>;
-    async fn create_account(&self, acc: Account) -> Result<i64>;
-    async fn get_account_by_id(&self, id: i64) -> Result<Account>;
-    async fn update_account(&self, acc: Account) -> Result<()>;
-    async fn delete_account(&self, id: i64) -> Result<()>;
+    async fn list_accounts(&self, user_id: i64) -> Result<Vec<Account>>;
+    /// One page of the accounts ordered by id.
+    async fn list_accounts_page(&self, user_id: i64, page: &PageCmd) -> Result<Page<Account>>;
+    async fn create_account(&self, user_id: i64, acc: Account) -> Result<i64>;
+    async fn get_account_by_id(&self, user_id: i64, id: i64) -> Result<Account>;
+    async fn update_account(&self, user_id: i64, acc: Account) -> Result<()>;
+    async fn update_account_balance(&self, user_id: i64, id: i64, delta: i64) -> Result<()>;
+    async fn delete_account(&self, user_id: i64, id: i64) -> Result<()>;
 }
 
 #[async_trait]
 pub trait RecordRepository: Clone + Send + Sync + 'static {
-    async fn list_records(&self, req: ListRecordsCmd) -> Result<Vec<Record>>;
-    async fn create_record(&self, transaction: Record) -> Result<i64>;
-    async fn get_record_by_id(&self, id: i64) -> Result<Record>;
-    async fn update_record(&self, record: Record) -> Result<()>;
-    async fn delete_record(&self, id: i64) -> Result<()>;
+    async fn list_records(&self, user_id: i64, req: ListRecordsCmd) -> Result<Vec<Record>>;
+    /// Counts the records matching the filters of `req`, ignoring its paging.
+    async fn count_records(&self, user_id: i64, req: &ListRecordsCmd) -> Result<u64>;
+    async fn create_record(&self, user_id: i64, transaction: Record) -> Result<i64>;
+    async fn get_record_by_id(&self, user_id: i64, id: i64) -> Result<Record>;
+    async fn update_record(&self, user_id: i64, record: Record) -> Result<()>;
+    async fn delete_record(&self, user_id: i64, id: i64) -> Result<()>;
 }
 
 #[async_trait]
 pub trait CategoryRepository: Clone + Send + Sync + 'static {
-    async fn list_categories(&self) -> Result<Vec<Category>>;
-    async fn create_category(&self, category: Category) -> Result<i64>;
-    async fn get_category_by_id(&self, id: i64) -> Result<Category>;
-    async fn update_category(&self, category: Category) -> Result<()>;
-    async fn delete_category(&self, id: i64) -> Result<()>;
+    async fn list_categories(&self, user_id: i64) -> Result<Vec<Category>>;
+    /// One page of the categories ordered by id.
+    async fn list_categories_page(&self, user_id: i64, page: &PageCmd) -> Result<Page<Category>>;
+    async fn create_category(&self, user_id: i64, category: Category) -> Result<i64>;
+    async fn get_category_by_id(&self, user_id: i64, id: i64) -> Result<Category>;
+    async fn update_category(&self, user_id: i64, category: Category) -> Result<()>;
+    async fn delete_category(&self, user_id: i64, id: i64) -> Result<()>;
 }
 
 #[async_trait]
-pub trait UserRepository: Clone + Send + Sync + 'static {}
+pub trait TagRepository: Clone + Send + Sync + 'static {
+    async fn list_tags(&self, user_id: i64) -> Result<Vec<Tag>>;
+    /// One page of the tags ordered by name.
+    async fn list_tags_page(&self, user_id: i64, page: &PageCmd) -> Result<Page<Tag>>;
+    async fn create_tag(&self, user_id: i64, tag: Tag) -> Result<i64>;
+    async fn get_tag_by_id(&self, user_id: i64, id: i64) -> Result<Tag>;
+    async fn find_tag_by_name(&self, user_id: i64, name: &str) -> Result<Option<Tag>>;
+    async fn update_tag(&self, user_id: i64, tag: Tag) -> Result<()>;
+    async fn delete_tag(&self, user_id: i64, id: i64) -> Result<()>;
+    /// Adds the target tag to every record tagged with the source tag.
+    async fn retag_records(&self, user_id: i64, source_id: i64, target_id: i64) -> Result<()>;
+}
+
+#[async_trait]
+pub trait RecurringRecordRepository: Clone + Send + Sync + 'static {
+    async fn list_recurring_records(&self, user_id: i64) -> Result<Vec<RecurringRecord>>;
+    /// One page of the recurring records ordered by id.
+    async fn list_recurring_records_page(
+        &self,
+        user_id: i64,
+        page: &PageCmd,
+    ) -> Result<Page<RecurringRecord>>;
+    async fn create_recurring_record(&self, recurring: RecurringRecord) -> Result<i64>;
+    async fn get_recurring_record_by_id(&self, user_id: i64, id: i64) -> Result<RecurringRecord>;
+    async fn update_recurring_record(&self, recurring: RecurringRecord) -> Result<()>;
+    async fn delete_recurring_record(&self, user_id: i64, id: i64) -> Result<()>;
+    /// Recurring records of all users with an occurrence at or before `now`.
+    async fn list_due_recurring_records(
+        &self,
+        now: DateTime<Local>,
+    ) -> Result<Vec<RecurringRecord>>;
+}
+
+#[async_trait]
+pub trait ReportRepository: Clone + Send + Sync + 'static {
+    /// Sum of `Outcome` amounts per category in `[from, to)` converted to
+    /// `currency`, as `(category_id, total)` pairs without subcategory
+    /// rollup.
+    async fn spending_by_category(
+        &self,
+        user_id: i64,
+        currency: &Currency,
+        from: DateTime<Local>,
+        to: DateTime<Local>,
+    ) -> Result<Vec<(i64, i64)>>;
+    /// Sum of `Outcome` amounts per tag in `[from, to)` converted to
+    /// `currency`, as `(tag_id, total)` pairs.
+    async fn spending_by_tag(
+        &self,
+        user_id: i64,
+        currency: &Currency,
+        from: DateTime<Local>,
+        to: DateTime<Local>,
+    ) -> Result<Vec<(i64, i64)>>;
+    /// First `Outcome` record in `[from, to)` that cannot be converted to
+    /// `currency` for lack of a rate, as its currency and date.
+    async fn find_missing_exchange_rate(
+        &self,
+        user_id: i64,
+        currency: &Currency,
+        from: DateTime<Local>,
+        to: DateTime<Local>,
+    ) -> Result<Option<(Currency, NaiveDate)>>;
+    /// Income and outcome per interval of the period, intervals without
+    /// records included.
+    async fn cash_flow(&self, user_id: i64, cmd: &CashFlowCmd) -> Result<Vec<CashFlowBucket>>;
+    /// First record counted by [`ReportRepository::cash_flow`] that cannot
+    /// be converted to the report currency, as its currency and date.
+    async fn find_missing_cash_flow_rate(
+        &self,
+        user_id: i64,
+        cmd: &CashFlowCmd,
+    ) -> Result<Option<(Currency, NaiveDate)>>;
+}
 
-pub trait BudgetRepository: RecordRepository + CategoryRepository + AccountRepository {}
+/// Soft deleted records, accounts and categories.
+#[async_trait]
+pub trait TrashRepository: Clone + Send + Sync + 'static {
+    /// One page of the deleted records, most recently deleted first.
+    async fn list_deleted_records(
+        &self,
+        user_id: i64,
+        page: &PageCmd,
+    ) -> Result<Page<Deleted<Record>>>;
+    async fn list_deleted_accounts(
+        &self,
+        user_id: i64,
+        page: &PageCmd,
+    ) -> Result<Page<Deleted<Account>>>;
+    async fn list_deleted_categories(
+        &self,
+        user_id: i64,
+        page: &PageCmd,
+    ) -> Result<Page<Deleted<Category>>>;
+    async fn get_deleted_record_by_id(&self, user_id: i64, id: i64) -> Result<Record>;
+    async fn get_deleted_account_by_id(&self, user_id: i64, id: i64) -> Result<Account>;
+    async fn get_deleted_category_by_id(&self, user_id: i64, id: i64) -> Result<Category>;
+    async fn restore_record(&self, user_id: i64, id: i64) -> Result<()>;
+    async fn restore_account(&self, user_id: i64, id: i64) -> Result<()>;
+    async fn restore_category(&self, user_id: i64, id: i64) -> Result<()>;
+    /// Permanently removes the deleted items matching `cmd`, together with
+    /// deleted records referring to them.
+    async fn purge_deleted(&self, cmd: &PurgeCmd) -> Result<PurgeReport>;
+}
 
+#[async_trait]
+pub trait ExchangeRateRepository: Clone + Send + Sync + 'static {
+    /// One page of the rates matching `cmd`, latest day first.
+    async fn list_exchange_rates(
+        &self,
+        user_id: i64,
+        cmd: &ListExchangeRatesCmd,
+        page: &PageCmd,
+    ) -> Result<Page<ExchangeRate>>;
+    /// Stores the rate, replacing an existing rate of the same pair and day.
+    async fn upsert_exchange_rate(&self, user_id: i64, rate: ExchangeRate) -> Result<i64>;
+    async fn get_exchange_rate_by_id(&self, user_id: i64, id: i64) -> Result<ExchangeRate>;
+    /// Rate of the pair stored for exactly that day.
+    async fn get_exchange_rate_on(
+        &self,
+        user_id: i64,
+        base: &Currency,
+        quote: &Currency,
+        valid_on: NaiveDate,
+    ) -> Result<Option<ExchangeRate>>;
+    async fn delete_exchange_rate(&self, user_id: i64, id: i64) -> Result<()>;
+    /// Latest rate converting `base` to `quote` on or before `on`, the
+    /// inverse of the opposite pair when only that one is known.
+    async fn find_exchange_rate(
+        &self,
+        user_id: i64,
+        base: &Currency,
+        quote: &Currency,
+        on: NaiveDate,
+    ) -> Result<Option<f64>>;
+}
+
+#[async_trait]
+pub trait AuditRepository: Clone + Send + Sync + 'static {
+    async fn append_audit_entry(&self, entry: AuditEntry) -> Result<i64>;
+    /// One page of the entries of the user matching `cmd`, in its order.
+    async fn list_audit_entries(
+        &self,
+        user_id: i64,
+        cmd: &ListAuditEntriesCmd,
+        page: &PageCmd,
+    ) -> Result<Page<AuditEntry>>;
+}
+
+#[async_trait]
+pub trait UserRepository: Clone + Send + Sync + 'static {
+    async fn list_users(&self) -> Result<Vec<User>>;
+    async fn create_user(&self, user: User) -> Result<i64>;
+    async fn get_user_by_id(&self, id: i64) -> Result<User>;
+    async fn get_user_by_username(&self, username: &str) -> Result<User>;
+    async fn update_user(&self, user: User) -> Result<()>;
+    async fn delete_user(&self, id: i64) -> Result<()>;
+}
+
+#[async_trait]
+pub trait AuthRepository: Clone + Send + Sync + 'static {
+    async fn get_password_hash(&self, user_id: i64) -> Result<Option<String>>;
+    async fn set_password_hash(&self, user_id: i64, password_hash: String) -> Result<()>;
+    async fn create_token(&self, token: Token, token_hash: String) -> Result<i64>;
+    async fn get_token_by_id(&self, user_id: i64, id: i64) -> Result<Token>;
+    async fn get_token_by_hash(&self, token_hash: &str) -> Result<Token>;
+    /// One page of the tokens of a kind, oldest first.
+    async fn list_tokens(
+        &self,
+        user_id: i64,
+        kind: TokenKind,
+        page: &PageCmd,
+    ) -> Result<Page<Token>>;
+    async fn delete_token(&self, user_id: i64, id: i64) -> Result<()>;
+    async fn delete_expired_tokens(&self) -> Result<()>;
+}
+
+/// Repository used by the budget service.
+///
+/// Calls on a repository returned by [`BudgetRepository::begin`] run inside a
+/// single database transaction which is rolled back when the repository is
+/// dropped without calling [`BudgetRepository::commit`].
+#[async_trait]
+pub trait BudgetRepository:
+    RecordRepository
+    + RecurringRecordRepository
+    + CategoryRepository
+    + TagRepository
+    + AccountRepository
+    + ReportRepository
+    + TrashRepository
+    + ExchangeRateRepository
+    + AuditRepository
+    + UserRepository
+    + AuthRepository
+    + Sized
+{
+    async fn begin(&self) -> Result<Self>;
+    async fn commit(self) -> Result<()>;
+    async fn rollback(self) -> Result<()>;
+}
+
+/// Budget service, every method except user management is scoped to the user
+/// passed as `user_id` and never reads or changes data of other users.
 pub trait BudgetService:
-    BudgetAccountsService + BudgetRecordService + BudgetCategoriesService
+    BudgetAccountsService
+    + BudgetRecordService
+    + BudgetRecurringService
+    + BudgetCategoriesService
+    + BudgetCurrenciesService
+    + BudgetTagsService
+    + BudgetTrashService
+    + BudgetAuditService
+    + BudgetImportsService
+    + BudgetReportsService
+    + BudgetUsersService
+    + BudgetAuthService
 {
 }
 
@@ -61,3 +292,14 @@ impl<T: BudgetRepository> BudgetServiceImpl<T> {
         Self { repo }
     }
 }
+
+/// Page of a list to return, see [`Cursor`].
+#[derive(Debug, Default, Clone)]
+pub struct PageCmd {
+    /// Every remaining item when `None`.
+    pub limit: Option<u64>,
+    /// Position to resume after, the first page when `None`.
+    pub after: Option<Cursor>,
+    /// Also count the items on all pages.
+    pub include_total: bool,
+}
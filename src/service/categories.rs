@@ -1,55 +1,512 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 
 use crate::{
-    domain::{Result, models::Category},
-    service::budget::{BudgetRepository, BudgetServiceImpl},
+    domain::{
+        Result,
+        errors::BudgetServiceError,
+        models::{
+            Amount, AuditAction, Category, CategoryError, CategoryTree, DeletePolicy,
+            DeletePolicyError, Page,
+        },
+    },
+    service::{
+        audit::audit,
+        budget::{BudgetRepository, BudgetServiceImpl, PageCmd},
+        records::{ListRecordsCmd, apply_balance_changes},
+    },
 };
 
 pub struct CreateCategoryCmd {
     pub name: String,
-    pub budget: Option<i64>,
+    /// In the reporting currency of the user.
+    pub budget: Option<Amount>,
     pub parent_id: Option<i64>,
 }
 
 pub struct UpdateCategoryCmd {
     pub id: i64,
     pub name: String,
-    pub budget: Option<i64>,
+    /// In the reporting currency of the user.
+    pub budget: Option<Amount>,
+}
+
+pub struct DeleteCategoryCmd {
+    pub id: i64,
+    /// Applies to records, split lines and recurring records in the
+    /// category.
+    pub policy: DeletePolicy,
+    /// Moves subcategories to the parent of the deleted category, deleting a
+    /// category with subcategories is refused otherwise.
+    pub promote_children: bool,
+}
+
+pub struct MoveCategoryCmd {
+    pub id: i64,
+    /// New parent, `None` makes the category a root.
+    pub parent_id: Option<i64>,
 }
 
 #[async_trait]
 pub trait BudgetCategoriesService: Send + Sync + 'static {
-    async fn list_categories(&self) -> Result<Vec<Category>>;
-    async fn create_category(&self, cmd: CreateCategoryCmd) -> Result<Category>;
-    async fn update_category(&self, cmd: UpdateCategoryCmd) -> Result<Category>;
-    async fn delete_category(&self, id: i64) -> Result<()>;
+    /// Categories ordered by id.
+    async fn list_categories(&self, user_id: i64, page: PageCmd) -> Result<Page<Category>>;
+    async fn get_category(&self, user_id: i64, id: i64) -> Result<Category>;
+    async fn create_category(&self, user_id: i64, cmd: CreateCategoryCmd) -> Result<Category>;
+    async fn update_category(&self, user_id: i64, cmd: UpdateCategoryCmd) -> Result<Category>;
+    async fn delete_category(&self, user_id: i64, cmd: DeleteCategoryCmd) -> Result<()>;
+    async fn category_tree(&self, user_id: i64) -> Result<Vec<CategoryTree>>;
+    async fn category_subtree(&self, user_id: i64, id: i64) -> Result<CategoryTree>;
+    async fn move_category(&self, user_id: i64, cmd: MoveCategoryCmd) -> Result<Category>;
 }
 
 #[async_trait]
 impl<T: BudgetRepository> BudgetCategoriesService for BudgetServiceImpl<T> {
-    async fn list_categories(&self) -> Result<Vec<Category>> {
-        Ok(self.repo.list_categories().await?)
+    async fn list_categories(&self, user_id: i64, page: PageCmd) -> Result<Page<Category>> {
+        Ok(self.repo.list_categories_page(user_id, &page).await?)
+    }
+
+    async fn get_category(&self, user_id: i64, id: i64) -> Result<Category> {
+        Ok(self.repo.get_category_by_id(user_id, id).await?)
+    }
+
+    async fn create_category(&self, user_id: i64, req: CreateCategoryCmd) -> Result<Category> {
+        let tx = self.repo.begin().await?;
+        let budget = resolve_budget(&tx, user_id, req.budget).await?;
+        let category = Category::new(req.name, budget, req.parent_id)?;
+        if let Some(parent_id) = category.parent_id {
+            let parents = parents(&tx.list_categories(user_id).await?);
+            if !parents.contains_key(&parent_id) {
+                return Err(CategoryError::ParentNotFound(parent_id).into());
+            }
+        }
+        let id = tx.create_category(user_id, category).await?;
+        let category = tx.get_category_by_id(user_id, id).await?;
+        audit(&tx, user_id, AuditAction::Create, None, Some(&category)).await?;
+        tx.commit().await?;
+
+        Ok(category)
+    }
+
+    async fn update_category(&self, user_id: i64, cmd: UpdateCategoryCmd) -> Result<Category> {
+        let tx = self.repo.begin().await?;
+        let before = tx.get_category_by_id(user_id, cmd.id).await?;
+        let mut category = before.clone();
+
+        let budget = resolve_budget(&tx, user_id, cmd.budget).await?;
+        category.update(cmd.name, budget)?;
+
+        tx.update_category(user_id, category).await?;
+        let category = tx.get_category_by_id(user_id, cmd.id).await?;
+        audit(
+            &tx,
+            user_id,
+            AuditAction::Update,
+            Some(&before),
+            Some(&category),
+        )
+        .await?;
+        tx.commit().await?;
+
+        Ok(category)
+    }
+
+    async fn delete_category(&self, user_id: i64, cmd: DeleteCategoryCmd) -> Result<()> {
+        let tx = self.repo.begin().await?;
+        let category = tx.get_category_by_id(user_id, cmd.id).await?;
+
+        let children: Vec<Category> = tx
+            .list_categories(user_id)
+            .await?
+            .into_iter()
+            .filter(|c| c.parent_id == Some(cmd.id))
+            .collect();
+        if !children.is_empty() && !cmd.promote_children {
+            return Err(BudgetServiceError::ConflictError(format!(
+                "category {} has {} subcategories",
+                cmd.id,
+                children.len()
+            )));
+        }
+
+        let records = tx
+            .list_records(
+                user_id,
+                ListRecordsCmd {
+                    category_ids: vec![cmd.id],
+                    ..Default::default()
+                },
+            )
+            .await?;
+        let recurring: Vec<_> = tx
+            .list_recurring_records(user_id)
+            .await?
+            .into_iter()
+            .filter(|r| r.category_id == Some(cmd.id))
+            .collect();
+
+        match cmd.policy {
+            DeletePolicy::Refuse => {
+                if !records.is_empty() || !recurring.is_empty() {
+                    return Err(BudgetServiceError::ConflictError(format!(
+                        "category {} is used by {} records and {} recurring records",
+                        cmd.id,
+                        records.len(),
+                        recurring.len()
+                    )));
+                }
+            }
+            DeletePolicy::Reassign(target_id) => {
+                if target_id == cmd.id {
+                    return Err(DeletePolicyError::ReassignToItself.into());
+                }
+                let target = tx.get_category_by_id(user_id, target_id).await?;
+
+                for before in records {
+                    let mut record = before.clone();
+                    record.replace_category(cmd.id, &target);
+                    tx.update_record(user_id, record.clone()).await?;
+                    audit(
+                        &tx,
+                        user_id,
+                        AuditAction::Update,
+                        Some(&before),
+                        Some(&record),
+                    )
+                    .await?;
+                }
+                for before in recurring {
+                    let mut recurring = before.clone();
+                    recurring.category_id = Some(target_id);
+                    tx.update_recurring_record(recurring.clone()).await?;
+                    audit(
+                        &tx,
+                        user_id,
+                        AuditAction::Update,
+                        Some(&before),
+                        Some(&recurring),
+                    )
+                    .await?;
+                }
+            }
+            DeletePolicy::Cascade => {
+                for record in records {
+                    apply_balance_changes(&tx, user_id, record.balance_changes(), -1).await?;
+                    tx.delete_record(user_id, record.id).await?;
+                    audit(&tx, user_id, AuditAction::Delete, Some(&record), None).await?;
+                }
+                for recurring in recurring {
+                    tx.delete_recurring_record(user_id, recurring.id).await?;
+                    audit(&tx, user_id, AuditAction::Delete, Some(&recurring), None).await?;
+                }
+            }
+        }
+
+        for before in children {
+            let mut child = before.clone();
+            child.parent_id = category.parent_id;
+            tx.update_category(user_id, child.clone()).await?;
+            audit(
+                &tx,
+                user_id,
+                AuditAction::Update,
+                Some(&before),
+                Some(&child),
+            )
+            .await?;
+        }
+
+        tx.delete_category(user_id, cmd.id).await?;
+        audit(&tx, user_id, AuditAction::Delete, Some(&category), None).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn category_tree(&self, user_id: i64) -> Result<Vec<CategoryTree>> {
+        let categories = self.repo.list_categories(user_id).await?;
+
+        Ok(CategoryTree::build(categories))
+    }
+
+    async fn category_subtree(&self, user_id: i64, id: i64) -> Result<CategoryTree> {
+        let tx = self.repo.begin().await?;
+        tx.get_category_by_id(user_id, id).await?;
+        let categories = tx.list_categories(user_id).await?;
+        tx.commit().await?;
+
+        Ok(CategoryTree::subtree(categories, id).expect("category is in the list"))
+    }
+
+    async fn move_category(&self, user_id: i64, cmd: MoveCategoryCmd) -> Result<Category> {
+        let tx = self.repo.begin().await?;
+        let before = tx.get_category_by_id(user_id, cmd.id).await?;
+        let mut category = before.clone();
+
+        if let Some(parent_id) = cmd.parent_id {
+            let parents = parents(&tx.list_categories(user_id).await?);
+            if !parents.contains_key(&parent_id) {
+                return Err(CategoryError::ParentNotFound(parent_id).into());
+            }
+            if is_descendant(&parents, parent_id, cmd.id) {
+                return Err(CategoryError::CategoryCycle.into());
+            }
+        }
+
+        category.parent_id = cmd.parent_id;
+        tx.update_category(user_id, category).await?;
+        let category = tx.get_category_by_id(user_id, cmd.id).await?;
+        audit(
+            &tx,
+            user_id,
+            AuditAction::Update,
+            Some(&before),
+            Some(&category),
+        )
+        .await?;
+        tx.commit().await?;
+
+        Ok(category)
+    }
+}
+
+fn parents(categories: &[Category]) -> HashMap<i64, Option<i64>> {
+    categories.iter().map(|c| (c.id, c.parent_id)).collect()
+}
+
+/// Checks whether `id` is `ancestor_id` or lies below it.
+fn is_descendant(parents: &HashMap<i64, Option<i64>>, id: i64, ancestor_id: i64) -> bool {
+    let mut current = Some(id);
+    let mut depth = 0;
+    while let Some(id) = current {
+        if id == ancestor_id {
+            return true;
+        }
+        current = parents.get(&id).copied().flatten();
+
+        // Guards against cycles already stored in the database.
+        depth += 1;
+        if depth > parents.len() {
+            return true;
+        }
     }
 
-    async fn create_category(&self, req: CreateCategoryCmd) -> Result<Category> {
-        let category = Category::new(req.name, req.budget, req.parent_id)?;
-        let id = self.repo.create_category(category).await?;
+    false
+}
+
+/// Converts a budget to minor units of the user's reporting currency, the
+/// currency budget reports are shown in by default.
+async fn resolve_budget<T: BudgetRepository>(
+    repo: &T,
+    user_id: i64,
+    budget: Option<Amount>,
+) -> Result<Option<i64>> {
+    let Some(budget) = budget else {
+        return Ok(None);
+    };
+    let user = repo.get_user_by_id(user_id).await?;
+
+    let budget = budget
+        .to_money(&user.reporting_currency)
+        .map_err(CategoryError::InvalidBudget)?;
+
+    Ok(Some(budget.minor()))
+}
 
-        Ok(self.repo.get_category_by_id(id).await?)
+#[cfg(test)]
+mod test {
+    use crate::{
+        repository::test::test_db,
+        service::{
+            budget::{AccountRepository, CategoryRepository, RecordRepository},
+            records::{BudgetRecordService, CreateRecordCmd, RecordSplitCmd},
+        },
+    };
+
+    use super::*;
+
+    fn move_cmd(id: i64, parent_id: Option<i64>) -> MoveCategoryCmd {
+        MoveCategoryCmd { id, parent_id }
     }
 
-    async fn update_category(&self, cmd: UpdateCategoryCmd) -> Result<Category> {
-        let mut category = self.repo.get_category_by_id(cmd.id).await?;
+    #[tokio::test]
+    async fn test_move_category() {
+        let fixture = include_str!("../repository/fixtures/records.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
 
-        category.name = cmd.name;
-        category.budget = cmd.budget;
+        let result = svc.move_category(1, move_cmd(4, Some(3))).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+        assert_eq!(result.unwrap().parent_id, Some(3));
 
-        self.repo.update_category(category).await?;
+        let subtree = svc.category_subtree(1, 3).await.unwrap();
+        assert_eq!(subtree.children[0].category.id, 4);
 
-        Ok(self.repo.get_category_by_id(cmd.id).await?)
+        let result = svc.move_category(1, move_cmd(2, None)).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+        let tree = svc.category_tree(1).await.unwrap();
+        let roots: Vec<i64> = tree.iter().map(|t| t.category.id).collect();
+        assert_eq!(roots, vec![1, 2, 5]);
     }
 
-    async fn delete_category(&self, id: i64) -> Result<()> {
-        Ok(self.repo.delete_category(id).await?)
+    #[tokio::test]
+    async fn test_move_category_rejects_cycles() {
+        let fixture = include_str!("../repository/fixtures/records.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        for (id, parent_id) in [(1, 4), (2, 2)] {
+            let result = svc.move_category(1, move_cmd(id, Some(parent_id))).await;
+            assert!(matches!(
+                result,
+                Err(BudgetServiceError::CategoryValidationError(
+                    CategoryError::CategoryCycle
+                ))
+            ));
+        }
+
+        let result = svc.move_category(1, move_cmd(2, Some(6))).await;
+        assert!(matches!(
+            result,
+            Err(BudgetServiceError::CategoryValidationError(
+                CategoryError::ParentNotFound(6)
+            ))
+        ));
+
+        let result = svc
+            .create_category(
+                1,
+                CreateCategoryCmd {
+                    name: "orphan".into(),
+                    budget: None,
+                    parent_id: Some(42),
+                },
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(BudgetServiceError::CategoryValidationError(
+                CategoryError::ParentNotFound(42)
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_delete_category_with_children() {
+        let fixture = include_str!("../repository/fixtures/records.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        let cmd = |policy, promote_children| DeleteCategoryCmd {
+            id: 2,
+            policy,
+            promote_children,
+        };
+
+        let result = svc
+            .delete_category(1, cmd(DeletePolicy::Cascade, false))
+            .await;
+        assert!(matches!(result, Err(BudgetServiceError::ConflictError(_))));
+
+        let result = svc
+            .delete_category(1, cmd(DeletePolicy::Refuse, true))
+            .await;
+        assert!(matches!(result, Err(BudgetServiceError::ConflictError(_))));
+
+        let result = svc
+            .delete_category(1, cmd(DeletePolicy::Reassign(3), true))
+            .await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        let bakery = svc.repo.get_category_by_id(1, 4).await.unwrap();
+        assert_eq!(bakery.parent_id, Some(1));
+        let ids: Vec<i64> = svc
+            .list_records(
+                1,
+                ListRecordsCmd {
+                    category_ids: vec![3],
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap()
+            .items
+            .iter()
+            .map(|r| r.id)
+            .collect();
+        assert_eq!(ids, vec![6, 3, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_delete_category_moves_split_lines() {
+        let fixture = include_str!("../repository/fixtures/records.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        let record = svc
+            .create_record(
+                1,
+                CreateRecordCmd {
+                    account_id: 1,
+                    transaction_type: "Outcome".into(),
+                    amount: 1000.into(),
+                    category: None,
+                    description: None,
+                    destination_account_id: None,
+                    received_amount: None,
+                    splits: vec![
+                        RecordSplitCmd {
+                            category_id: 2,
+                            amount: 600.into(),
+                            note: None,
+                        },
+                        RecordSplitCmd {
+                            category_id: 3,
+                            amount: 400.into(),
+                            note: None,
+                        },
+                    ],
+                    tag_ids: Vec::new(),
+                    occurred_at: None,
+                    posted_at: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        let result = svc
+            .delete_category(
+                1,
+                DeleteCategoryCmd {
+                    id: 3,
+                    policy: DeletePolicy::Reassign(2),
+                    promote_children: false,
+                },
+            )
+            .await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        let record = svc.repo.get_record_by_id(1, record.id).await.unwrap();
+        let categories: Vec<i64> = record.splits.iter().map(|s| s.category.id).collect();
+        assert_eq!(categories, vec![2, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_delete_category_cascades() {
+        let fixture = include_str!("../repository/fixtures/records.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        let result = svc
+            .delete_category(
+                1,
+                DeleteCategoryCmd {
+                    id: 3,
+                    policy: DeletePolicy::Cascade,
+                    promote_children: false,
+                },
+            )
+            .await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        assert!(svc.repo.get_record_by_id(1, 3).await.is_err());
+        let card = svc.repo.get_account_by_id(1, 2).await.unwrap();
+        assert_eq!(card.balance, 2500);
     }
 }
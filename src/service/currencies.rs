@@ -0,0 +1,249 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use sqlx::types::chrono::NaiveDate;
+
+use crate::{
+    domain::{
+        Result,
+        models::{AuditAction, Currency, CurrencyError, ExchangeRate, Page},
+    },
+    service::{
+        audit::audit,
+        budget::{BudgetRepository, BudgetServiceImpl, PageCmd},
+    },
+};
+
+/// Columns of an exchange rate file, matched against the header row.
+const RATE_COLUMNS: [&str; 4] = ["date", "base", "quote", "rate"];
+
+#[derive(Default)]
+pub struct ListExchangeRatesCmd {
+    pub base: Option<Currency>,
+    pub quote: Option<Currency>,
+}
+
+pub struct SetExchangeRateCmd {
+    pub base: Currency,
+    pub quote: Currency,
+    pub rate: f64,
+    pub valid_on: NaiveDate,
+}
+
+#[async_trait]
+pub trait BudgetCurrenciesService: Send + Sync + 'static {
+    /// Rates matching `cmd`, latest day first.
+    async fn list_exchange_rates(
+        &self,
+        user_id: i64,
+        cmd: ListExchangeRatesCmd,
+        page: PageCmd,
+    ) -> Result<Page<ExchangeRate>>;
+    /// Stores a rate, replacing the rate of the same pair and day.
+    async fn set_exchange_rate(
+        &self,
+        user_id: i64,
+        cmd: SetExchangeRateCmd,
+    ) -> Result<ExchangeRate>;
+    async fn delete_exchange_rate(&self, user_id: i64, id: i64) -> Result<()>;
+    /// Stores all rates of a CSV file with a `date,base,quote,rate` header,
+    /// nothing is stored when any row is invalid.
+    async fn import_exchange_rates(&self, user_id: i64, content: String) -> Result<usize>;
+}
+
+#[async_trait]
+impl<T: BudgetRepository> BudgetCurrenciesService for BudgetServiceImpl<T> {
+    async fn list_exchange_rates(
+        &self,
+        user_id: i64,
+        cmd: ListExchangeRatesCmd,
+        page: PageCmd,
+    ) -> Result<Page<ExchangeRate>> {
+        Ok(self.repo.list_exchange_rates(user_id, &cmd, &page).await?)
+    }
+
+    async fn set_exchange_rate(
+        &self,
+        user_id: i64,
+        cmd: SetExchangeRateCmd,
+    ) -> Result<ExchangeRate> {
+        let rate = ExchangeRate::new(cmd.base, cmd.quote, cmd.rate, cmd.valid_on)?;
+
+        let tx = self.repo.begin().await?;
+        let rate = store_rate(&tx, user_id, rate).await?;
+        tx.commit().await?;
+
+        Ok(rate)
+    }
+
+    async fn delete_exchange_rate(&self, user_id: i64, id: i64) -> Result<()> {
+        let tx = self.repo.begin().await?;
+        let rate = tx.get_exchange_rate_by_id(user_id, id).await?;
+        tx.delete_exchange_rate(user_id, id).await?;
+        audit(&tx, user_id, AuditAction::Delete, Some(&rate), None).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn import_exchange_rates(&self, user_id: i64, content: String) -> Result<usize> {
+        let rates = parse_rates(&content)?;
+
+        let tx = self.repo.begin().await?;
+        for rate in &rates {
+            store_rate(&tx, user_id, rate.clone()).await?;
+        }
+        tx.commit().await?;
+
+        Ok(rates.len())
+    }
+}
+
+/// Stores a rate and audits it as created, or as updated when it replaces
+/// the rate of the same pair and day.
+async fn store_rate<T: BudgetRepository>(
+    repo: &T,
+    user_id: i64,
+    rate: ExchangeRate,
+) -> Result<ExchangeRate> {
+    let before = repo
+        .get_exchange_rate_on(user_id, &rate.base, &rate.quote, rate.valid_on)
+        .await?;
+    let id = repo.upsert_exchange_rate(user_id, rate).await?;
+    let rate = repo.get_exchange_rate_by_id(user_id, id).await?;
+    let action = match before {
+        Some(_) => AuditAction::Update,
+        None => AuditAction::Create,
+    };
+    audit(repo, user_id, action, before.as_ref(), Some(&rate)).await?;
+
+    Ok(rate)
+}
+
+fn parse_rates(content: &str) -> std::result::Result<Vec<ExchangeRate>, CurrencyError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(content.as_bytes());
+
+    let invalid = |line: u64, message: String| CurrencyError::InvalidRateRow { line, message };
+    let header = reader
+        .headers()
+        .map_err(|e| invalid(1, e.to_string()))?
+        .clone();
+    let mut columns = [0; RATE_COLUMNS.len()];
+    for (column, name) in columns.iter_mut().zip(RATE_COLUMNS) {
+        *column = header
+            .iter()
+            .position(|h| h.eq_ignore_ascii_case(name))
+            .ok_or_else(|| invalid(1, format!("column \"{name}\" not found in header")))?;
+    }
+    let [date, base, quote, rate] = columns;
+
+    let mut rates = Vec::new();
+    for row in reader.records() {
+        let row = row.map_err(|e| invalid(e.position().map_or(0, |p| p.line()), e.to_string()))?;
+        let line = row.position().map_or(0, |p| p.line());
+        if row.iter().all(str::is_empty) {
+            continue;
+        }
+
+        let field = |index: usize| row.get(index).unwrap_or_default();
+        let valid_on = NaiveDate::parse_from_str(field(date), "%Y-%m-%d")
+            .map_err(|_| invalid(line, format!("invalid date \"{}\"", field(date))))?;
+        let value = f64::from_str(field(rate))
+            .map_err(|_| invalid(line, format!("invalid rate \"{}\"", field(rate))))?;
+        let parsed = Currency::from_str(field(base))
+            .and_then(|base| Ok((base, Currency::from_str(field(quote))?)))
+            .and_then(|(base, quote)| ExchangeRate::new(base, quote, value, valid_on))
+            .map_err(|e| invalid(line, e.to_string()))?;
+
+        rates.push(parsed);
+    }
+
+    Ok(rates)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        domain::models::AuditEntity, repository::test::test_db, service::audit::BudgetAuditService,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_import_exchange_rates() {
+        let fixture = include_str!("../repository/fixtures/records.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        let content = "date,base,quote,rate\n2026-03-01,eur,USD,1.08\n\n2026-03-02,EUR,USD,1.1\n";
+        let result = svc.import_exchange_rates(1, content.into()).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+        assert_eq!(result.unwrap(), 2);
+
+        // Importing the same day again replaces the rate.
+        let content = "rate,date,base,quote\n1.09,2026-03-01,EUR,USD\n";
+        svc.import_exchange_rates(1, content.into()).await.unwrap();
+
+        let rates = svc
+            .list_exchange_rates(1, ListExchangeRatesCmd::default(), PageCmd::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            rates.items.iter().map(|r| r.rate).collect::<Vec<_>>(),
+            vec![1.1, 1.09]
+        );
+        let history = svc
+            .entity_history(
+                1,
+                AuditEntity::ExchangeRate,
+                rates.items[1].id,
+                PageCmd::default(),
+            )
+            .await
+            .unwrap();
+        let actions: Vec<_> = history.items.iter().map(|e| e.action).collect();
+        assert_eq!(actions, vec![AuditAction::Create, AuditAction::Update]);
+        let rates = svc
+            .list_exchange_rates(2, ListExchangeRatesCmd::default(), PageCmd::default())
+            .await
+            .unwrap();
+        assert!(rates.items.is_empty());
+
+        let page = PageCmd {
+            limit: Some(1),
+            ..Default::default()
+        };
+        let first = svc
+            .list_exchange_rates(1, ListExchangeRatesCmd::default(), page.clone())
+            .await
+            .unwrap();
+        assert_eq!(first.items[0].rate, 1.1);
+        let page = PageCmd {
+            after: first.next_cursor,
+            ..page
+        };
+        let second = svc
+            .list_exchange_rates(1, ListExchangeRatesCmd::default(), page)
+            .await
+            .unwrap();
+        assert_eq!(second.items[0].rate, 1.09);
+        assert_eq!(second.next_cursor, None);
+
+        let content = "date,base,quote,rate\n2026-03-03,EUR,USD,1.1\n2026-03-04,EUR,EUR,1\n";
+        let result = svc.import_exchange_rates(1, content.into()).await;
+        assert!(matches!(
+            result,
+            Err(
+                crate::domain::errors::BudgetServiceError::CurrencyValidationError(
+                    CurrencyError::InvalidRateRow { line: 3, .. }
+                )
+            )
+        ));
+        let rates = svc
+            .list_exchange_rates(1, ListExchangeRatesCmd::default(), PageCmd::default())
+            .await
+            .unwrap();
+        assert_eq!(rates.items.len(), 2);
+    }
+}
@@ -0,0 +1,487 @@
+use async_trait::async_trait;
+use sqlx::types::chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime};
+
+use crate::{
+    domain::{
+        Result,
+        models::{AuditAction, Currency, ImportError, Money, MoneyError, Record, RecordType},
+    },
+    service::{
+        audit::audit,
+        budget::{BudgetRepository, BudgetServiceImpl},
+        records::apply_balance_changes,
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CsvColumn {
+    /// Zero-based column position.
+    Index(usize),
+    /// Column name from the header row.
+    Name(String),
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SignConvention {
+    /// Negative amounts are outcomes and positive amounts are incomes.
+    #[default]
+    NegativeIsOutcome,
+    /// Positive amounts are outcomes and negative amounts are incomes.
+    PositiveIsOutcome,
+}
+
+/// Describes how the columns of a bank statement map to records.
+#[derive(Debug, Clone)]
+pub struct CsvMapping {
+    pub has_header: bool,
+    pub date_column: CsvColumn,
+    pub amount_column: CsvColumn,
+    pub description_column: Option<CsvColumn>,
+    pub sign_convention: SignConvention,
+    /// `strftime` format of the date column, a time part is optional.
+    pub date_format: String,
+    pub delimiter: char,
+    pub decimal_separator: char,
+}
+
+pub struct ImportCsvCmd {
+    pub account_id: i64,
+    pub content: String,
+    pub mapping: CsvMapping,
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportRowError {
+    /// Line of the file the row starts on, starting from 1.
+    pub line: u64,
+    pub message: String,
+}
+
+/// Result of an import. Nothing is written on a dry run or when any row has
+/// an error, `records` then holds the parsed rows without ids.
+#[derive(Debug)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub imported: usize,
+    pub records: Vec<Record>,
+    pub errors: Vec<ImportRowError>,
+}
+
+#[async_trait]
+pub trait BudgetImportsService: Send + Sync + 'static {
+    async fn import_csv(&self, user_id: i64, cmd: ImportCsvCmd) -> Result<ImportReport>;
+}
+
+#[async_trait]
+impl<T: BudgetRepository> BudgetImportsService for BudgetServiceImpl<T> {
+    async fn import_csv(&self, user_id: i64, cmd: ImportCsvCmd) -> Result<ImportReport> {
+        let account = self.repo.get_account_by_id(user_id, cmd.account_id).await?;
+        let (records, errors) =
+            parse_csv(account.id, &account.currency, &cmd.content, &cmd.mapping)?;
+
+        if cmd.dry_run || !errors.is_empty() {
+            return Ok(ImportReport {
+                dry_run: cmd.dry_run,
+                imported: 0,
+                records,
+                errors,
+            });
+        }
+
+        let tx = self.repo.begin().await?;
+        let mut created = Vec::with_capacity(records.len());
+        for record in records {
+            apply_balance_changes(&tx, user_id, record.balance_changes(), 1).await?;
+            let id = tx.create_record(user_id, record).await?;
+            let record = tx.get_record_by_id(user_id, id).await?;
+            audit(&tx, user_id, AuditAction::Create, None, Some(&record)).await?;
+            created.push(record);
+        }
+        tx.commit().await?;
+
+        Ok(ImportReport {
+            dry_run: false,
+            imported: created.len(),
+            records: created,
+            errors,
+        })
+    }
+}
+
+struct ColumnIndexes {
+    date: usize,
+    amount: usize,
+    description: Option<usize>,
+}
+
+fn parse_csv(
+    account_id: i64,
+    currency: &Currency,
+    content: &str,
+    mapping: &CsvMapping,
+) -> Result<(Vec<Record>, Vec<ImportRowError>)> {
+    if !mapping.delimiter.is_ascii() {
+        return Err(ImportError::InvalidDelimiter.into());
+    }
+    if mapping.decimal_separator != '.' && mapping.decimal_separator != ',' {
+        return Err(ImportError::InvalidDecimalSeparator.into());
+    }
+
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(mapping.has_header)
+        .delimiter(mapping.delimiter as u8)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(content.as_bytes());
+
+    let header = if mapping.has_header {
+        Some(
+            reader
+                .headers()
+                .map_err(|e| ImportError::InvalidHeader(e.to_string()))?
+                .clone(),
+        )
+    } else {
+        None
+    };
+    let resolve = |column: &CsvColumn| -> Result<usize, ImportError> {
+        match (column, &header) {
+            (CsvColumn::Index(index), _) => Ok(*index),
+            (CsvColumn::Name(_), None) => Err(ImportError::NamedColumnWithoutHeader),
+            (CsvColumn::Name(name), Some(header)) => header
+                .iter()
+                .position(|h| h.eq_ignore_ascii_case(name))
+                .ok_or_else(|| ImportError::UnknownColumn(name.clone())),
+        }
+    };
+    let columns = ColumnIndexes {
+        date: resolve(&mapping.date_column)?,
+        amount: resolve(&mapping.amount_column)?,
+        description: mapping
+            .description_column
+            .as_ref()
+            .map(resolve)
+            .transpose()?,
+    };
+
+    let mut records = Vec::new();
+    let mut errors = Vec::new();
+    for row in reader.records() {
+        let (line, result) = match row {
+            Ok(row) => {
+                let line = row.position().map_or(0, |p| p.line());
+                (
+                    line,
+                    parse_row(account_id, currency, &row, &columns, mapping),
+                )
+            }
+            Err(e) => {
+                let line = e.position().map_or(0, |p| p.line());
+                (line, Err(e.to_string()))
+            }
+        };
+
+        match result {
+            Ok(Some(record)) => records.push(record),
+            Ok(None) => {}
+            Err(message) => errors.push(ImportRowError { line, message }),
+        }
+    }
+
+    Ok((records, errors))
+}
+
+/// Parses a single row, blank rows are skipped.
+fn parse_row(
+    account_id: i64,
+    currency: &Currency,
+    row: &csv::StringRecord,
+    columns: &ColumnIndexes,
+    mapping: &CsvMapping,
+) -> Result<Option<Record>, String> {
+    if row.iter().all(str::is_empty) {
+        return Ok(None);
+    }
+
+    let field = |index: usize, name: &str| {
+        row.get(index)
+            .ok_or_else(|| format!("{name} column {index} is missing"))
+    };
+
+    let date = parse_date(field(columns.date, "date")?, &mapping.date_format)?;
+    let amount = parse_amount(
+        field(columns.amount, "amount")?,
+        mapping.decimal_separator,
+        currency,
+    )?;
+    let description = columns
+        .description
+        .map(|index| field(index, "description"))
+        .transpose()?
+        .filter(|description| !description.is_empty())
+        .map(str::to_owned);
+
+    let is_outcome = match mapping.sign_convention {
+        SignConvention::NegativeIsOutcome => amount.minor() < 0,
+        SignConvention::PositiveIsOutcome => amount.minor() > 0,
+    };
+    let record_type = if is_outcome {
+        RecordType::Outcome
+    } else {
+        RecordType::Income
+    };
+
+    let mut record = Record::new(
+        account_id,
+        record_type.to_string(),
+        if amount.minor() < 0 {
+            amount.checked_neg().map_err(|e| e.to_string())?
+        } else {
+            amount
+        },
+        None,
+        description,
+    )
+    .map_err(|e| e.to_string())?;
+    record.occurred_at = date;
+
+    Ok(Some(record))
+}
+
+fn parse_date(value: &str, format: &str) -> Result<sqlx::types::chrono::DateTime<Local>, String> {
+    let datetime = NaiveDateTime::parse_from_str(value, format)
+        .or_else(|_| {
+            NaiveDate::parse_from_str(value, format).map(|date| date.and_time(NaiveTime::MIN))
+        })
+        .map_err(|e| format!("invalid date \"{value}\": {e}"))?;
+
+    datetime
+        .and_local_timezone(Local)
+        .earliest()
+        .ok_or_else(|| format!("date \"{value}\" does not exist in the local time zone"))
+}
+
+/// Parses a decimal amount in the currency of the account. Spaces,
+/// apostrophes and the separator not used for decimals are treated as
+/// thousands separators.
+fn parse_amount(
+    value: &str,
+    decimal_separator: char,
+    currency: &Currency,
+) -> Result<Money, String> {
+    let thousands_separator = if decimal_separator == '.' { ',' } else { '.' };
+
+    let mut cleaned: String = value
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '\'' && *c != thousands_separator)
+        .map(|c| if c == decimal_separator { '.' } else { c })
+        .collect();
+    // Statements may leave out the whole part, as in ".5".
+    let whole_start = usize::from(cleaned.starts_with(['-', '+']));
+    if cleaned[whole_start..].starts_with('.') {
+        cleaned.insert(whole_start, '0');
+    }
+
+    Money::parse(&cleaned, currency.clone()).map_err(|e| match e {
+        MoneyError::InvalidAmount(_) => format!("invalid amount \"{value}\""),
+        e => e.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        domain::errors::BudgetServiceError,
+        repository::test::test_db,
+        service::{budget::AccountRepository, records::BudgetRecordService},
+    };
+
+    use super::*;
+
+    fn mapping() -> CsvMapping {
+        CsvMapping {
+            has_header: true,
+            date_column: CsvColumn::Name("Date".into()),
+            amount_column: CsvColumn::Name("Amount".into()),
+            description_column: Some(CsvColumn::Name("Description".into())),
+            sign_convention: SignConvention::NegativeIsOutcome,
+            date_format: "%d.%m.%Y".into(),
+            delimiter: ';',
+            decimal_separator: ',',
+        }
+    }
+
+    const STATEMENT: &str = "Date;Description;Amount\n\
+        01.03.2026;Coffee;-3,50\n\
+        02.03.2026;Salary;1 200,00\n";
+
+    #[test]
+    fn test_parse_amount() {
+        let usd = Currency::default();
+        let parse = |value: &str, separator: char| {
+            parse_amount(value, separator, &usd).map(|amount| amount.minor())
+        };
+        assert_eq!(parse("-3,50", ','), Ok(-350));
+        assert_eq!(parse("1.234,5", ','), Ok(123450));
+        assert_eq!(parse("1,234.56", '.'), Ok(123456));
+        assert_eq!(parse("+7", '.'), Ok(700));
+        assert_eq!(parse(".5", '.'), Ok(50));
+        assert_eq!(parse("-.5", '.'), Ok(-50));
+        assert!(parse("1.234", '.').is_err());
+        assert!(parse("abc", '.').is_err());
+        assert!(parse("-", '.').is_err());
+
+        let jpy: Currency = "JPY".parse().unwrap();
+        assert_eq!(
+            parse_amount("1,200", '.', &jpy).map(|a| a.minor()),
+            Ok(1200)
+        );
+        assert!(parse_amount("12.5", '.', &jpy).is_err());
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let (records, errors) =
+            parse_csv(1, &Currency::default(), STATEMENT, &mapping()).expect("must parse");
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(records.len(), 2);
+
+        assert_eq!(records[0].record_type, RecordType::Outcome);
+        assert_eq!(i64::from(records[0].amount), 350);
+        assert_eq!(records[0].description, Some("Coffee".into()));
+        assert_eq!(
+            records[0].occurred_at.date_naive(),
+            NaiveDate::from_ymd_opt(2026, 3, 1).unwrap()
+        );
+
+        assert_eq!(records[1].record_type, RecordType::Income);
+        assert_eq!(i64::from(records[1].amount), 120000);
+    }
+
+    #[test]
+    fn test_parse_csv_without_header() {
+        let mapping = CsvMapping {
+            has_header: false,
+            date_column: CsvColumn::Index(0),
+            amount_column: CsvColumn::Index(1),
+            description_column: None,
+            sign_convention: SignConvention::PositiveIsOutcome,
+            date_format: "%Y-%m-%d %H:%M".into(),
+            delimiter: ',',
+            decimal_separator: '.',
+        };
+
+        let (records, errors) = parse_csv(
+            1,
+            &Currency::default(),
+            "2026-03-01 10:30,12.00\n",
+            &mapping,
+        )
+        .expect("must parse");
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(records[0].record_type, RecordType::Outcome);
+        assert_eq!(i64::from(records[0].amount), 1200);
+
+        let mapping = CsvMapping {
+            date_column: CsvColumn::Name("Date".into()),
+            ..mapping
+        };
+        let result = parse_csv(
+            1,
+            &Currency::default(),
+            "2026-03-01 10:30,12.00\n",
+            &mapping,
+        );
+        assert!(matches!(
+            result,
+            Err(BudgetServiceError::ImportValidationError(
+                ImportError::NamedColumnWithoutHeader
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_parse_csv_row_errors() {
+        let content = "Date;Description;Amount\n\
+            01.03.2026;Coffee;-3,50\n\
+            2026-03-02;Bad date;1,00\n\
+            03.03.2026;Zero;0\n\
+            04.03.2026;Short\n";
+
+        let (records, errors) =
+            parse_csv(1, &Currency::default(), content, &mapping()).expect("must parse");
+        assert_eq!(records.len(), 1);
+        assert_eq!(
+            errors.iter().map(|e| e.line).collect::<Vec<_>>(),
+            vec![3, 4, 5]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_import_csv() {
+        let fixture = include_str!("../repository/fixtures/fixture.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        let report = svc
+            .import_csv(
+                1,
+                ImportCsvCmd {
+                    account_id: 1,
+                    content: STATEMENT.into(),
+                    mapping: mapping(),
+                    dry_run: true,
+                },
+            )
+            .await
+            .expect("must preview import");
+        assert_eq!(report.imported, 0);
+        assert_eq!(report.records.len(), 2);
+        let records = svc.list_records(1, Default::default()).await.unwrap();
+        assert_eq!(records.items.len(), 1);
+
+        let report = svc
+            .import_csv(
+                1,
+                ImportCsvCmd {
+                    account_id: 1,
+                    content: STATEMENT.into(),
+                    mapping: mapping(),
+                    dry_run: false,
+                },
+            )
+            .await
+            .expect("must import");
+        assert_eq!(report.imported, 2);
+        assert!(report.records.iter().all(|r| r.id > 0));
+
+        let records = svc.list_records(1, Default::default()).await.unwrap();
+        assert_eq!(records.items.len(), 3);
+        let account = svc.repo.get_account_by_id(1, 1).await.unwrap();
+        assert_eq!(account.balance, 120000 - 350);
+    }
+
+    #[tokio::test]
+    async fn test_import_csv_with_errors_writes_nothing() {
+        let fixture = include_str!("../repository/fixtures/fixture.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        let report = svc
+            .import_csv(
+                1,
+                ImportCsvCmd {
+                    account_id: 1,
+                    content: format!("{STATEMENT}03.03.2026;Broken;x\n"),
+                    mapping: mapping(),
+                    dry_run: false,
+                },
+            )
+            .await
+            .expect("must return report");
+        assert_eq!(report.imported, 0);
+        assert_eq!(report.errors.len(), 1);
+
+        let records = svc.list_records(1, Default::default()).await.unwrap();
+        assert_eq!(records.items.len(), 1);
+    }
+}
@@ -1,4 +1,13 @@
 pub mod accounts;
+pub mod audit;
+pub mod auth;
 pub mod budget;
 pub mod categories;
+pub mod currencies;
+pub mod imports;
 pub mod records;
+pub mod recurring;
+pub mod reports;
+pub mod tags;
+pub mod trash;
+pub mod users;
@@ -1,83 +1,1096 @@
 use async_trait::async_trait;
-use sqlx::types::chrono::Local;
+use sqlx::types::chrono::{DateTime, Local};
 
 use crate::{
     domain::{
         Result,
-        models::{Category, Record},
+        models::{
+            Account, Amount, AuditAction, Category, Currency, Cursor, CursorKey, Money, Page,
+            PageError, Record, RecordError, RecordSplit, RecordType, Tag,
+        },
+    },
+    service::{
+        audit::audit,
+        budget::{BudgetRepository, BudgetServiceImpl},
     },
-    service::budget::{BudgetRepository, BudgetServiceImpl},
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CreateRecordCmd {
     pub account_id: i64,
     pub transaction_type: String,
-    pub amount: i64,
+    /// In the currency of the account.
+    pub amount: Amount,
     pub category: Option<i64>,
     pub description: Option<String>,
+    pub destination_account_id: Option<i64>,
+    /// In the currency of the destination account.
+    pub received_amount: Option<Amount>,
+    /// Divides the amount between categories, the record has no category
+    /// of its own when split.
+    pub splits: Vec<RecordSplitCmd>,
+    pub tag_ids: Vec<i64>,
+    /// When the transaction took place, defaults to now.
+    pub occurred_at: Option<DateTime<Local>>,
+    pub posted_at: Option<DateTime<Local>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordSplitCmd {
+    pub category_id: i64,
+    pub amount: Amount,
+    pub note: Option<String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct ListRecordsCmd {
     pub limit: Option<u64>,
-    pub offset: Option<u64>,
-    pub category_id: Option<i64>,
+    /// Position to resume after, its key must be of the `sort_by` field.
+    pub after: Option<Cursor>,
+    /// Also count the records matching the filters on all pages.
+    pub include_total: bool,
+    /// Records in any of these categories, all records when empty.
+    pub category_ids: Vec<i64>,
+    /// Also match records in descendants of `category_ids`.
+    pub include_subcategories: bool,
+    /// Records with these tags, all records when empty.
+    pub tag_ids: Vec<i64>,
+    pub tag_match: TagMatch,
+    /// Records of this account, including transfers into it.
+    pub account_id: Option<i64>,
+    pub record_type: Option<RecordType>,
+    /// Inclusive lower bound of the transaction date.
+    pub from: Option<DateTime<Local>>,
+    /// Exclusive upper bound of the transaction date.
+    pub to: Option<DateTime<Local>>,
+    /// Inclusive bounds of the amount, amounts of accounts in other
+    /// currencies are converted to the currency of the bound.
+    pub min_amount: Option<Money>,
+    pub max_amount: Option<Money>,
+    /// Case-insensitive substring of the description.
+    pub description: Option<String>,
+    pub sort_by: RecordSortField,
+    pub sort_order: SortOrder,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TagMatch {
+    /// Records with at least one of the tags.
+    #[default]
+    Any,
+    /// Records with every one of the tags.
+    All,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RecordSortField {
+    #[default]
+    Date,
+    Amount,
+    Category,
+}
+
+impl RecordSortField {
+    /// Position of `record` in a list sorted by this field.
+    fn cursor(self, record: &Record) -> Cursor {
+        let key = match self {
+            Self::Date => CursorKey::Date(record.occurred_at),
+            Self::Amount => CursorKey::Amount(record.amount.get()),
+            Self::Category => CursorKey::Name(record.category.as_ref().map(|c| c.name.clone())),
+        };
+        Cursor::new(key, record.id)
+    }
+
+    /// Key of the cursors of lists sorted by this field, the value is
+    /// irrelevant.
+    fn key_kind(self) -> CursorKey {
+        match self {
+            Self::Date => CursorKey::Date(DateTime::<Local>::MIN_UTC.into()),
+            Self::Amount => CursorKey::Amount(0),
+            Self::Category => CursorKey::Name(None),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
 }
 
 pub struct UpdateRecordCmd {
     pub id: i64,
-    pub amount: i64,
+    pub amount: Amount,
     pub description: Option<String>,
     pub category_id: Option<i64>,
+    pub destination_account_id: Option<i64>,
+    /// Keeps the received amount of a transfer when `None`, unless the
+    /// destination changes to another currency.
+    pub received_amount: Option<Amount>,
+    /// Replaces the split lines of the record, keeps them when `None`.
+    pub splits: Option<Vec<RecordSplitCmd>>,
+    /// Replaces the tags of the record, keeps them when `None`.
+    pub tag_ids: Option<Vec<i64>>,
+    /// Keeps the transaction date when `None`.
+    pub occurred_at: Option<DateTime<Local>>,
+    /// Keeps the posting date when `None`.
+    pub posted_at: Option<DateTime<Local>>,
 }
 #[async_trait]
 pub trait BudgetRecordService: Send + Sync + 'static {
-    async fn list_records(&self, cmd: ListRecordsCmd) -> Result<Vec<Record>>;
-    async fn create_record(&self, cmd: CreateRecordCmd) -> Result<Record>;
-    async fn update_record(&self, cmd: UpdateRecordCmd) -> Result<Record>;
-    async fn delete_record(&self, id: i64) -> Result<()>;
+    /// Lists records sorted by `sort_by` then by id, in pages resuming after
+    /// the cursor of the previous one.
+    async fn list_records(&self, user_id: i64, cmd: ListRecordsCmd) -> Result<Page<Record>>;
+    async fn get_record(&self, user_id: i64, id: i64) -> Result<Record>;
+    async fn create_record(&self, user_id: i64, cmd: CreateRecordCmd) -> Result<Record>;
+    async fn update_record(&self, user_id: i64, cmd: UpdateRecordCmd) -> Result<Record>;
+    async fn delete_record(&self, user_id: i64, id: i64) -> Result<()>;
 }
 
 #[async_trait]
 impl<T: BudgetRepository> BudgetRecordService for BudgetServiceImpl<T> {
-    async fn list_records(&self, cmd: ListRecordsCmd) -> Result<Vec<Record>> {
-        Ok(self.repo.list_records(cmd).await?)
+    async fn list_records(&self, user_id: i64, mut cmd: ListRecordsCmd) -> Result<Page<Record>> {
+        let sort_by = cmd.sort_by;
+        if let Some(after) = &cmd.after
+            && !after.same_kind(&sort_by.key_kind())
+        {
+            return Err(PageError::InvalidCursor.into());
+        }
+
+        let total_count = match cmd.include_total {
+            true => Some(self.repo.count_records(user_id, &cmd).await?),
+            false => None,
+        };
+
+        let limit = cmd.limit;
+        cmd.limit = limit.map(|limit| limit.saturating_add(1));
+        let records = self.repo.list_records(user_id, cmd).await?;
+
+        Ok(Page::new(
+            records,
+            limit,
+            |record| sort_by.cursor(record),
+            total_count,
+        ))
     }
 
-    async fn create_record(&self, cmd: CreateRecordCmd) -> Result<Record> {
-        let mut category: Option<Category> = None;
-        if let Some(category_id) = cmd.category {
-            category = Some(self.repo.get_category_by_id(category_id).await?);
-        }
-        let transaction = Record::new(
-            cmd.account_id,
-            cmd.transaction_type,
-            cmd.amount,
-            category,
-            cmd.description,
-        )?;
-        let id = self.repo.create_record(transaction).await?;
-        Ok(self.repo.get_record_by_id(id).await?)
+    async fn get_record(&self, user_id: i64, id: i64) -> Result<Record> {
+        Ok(self.repo.get_record_by_id(user_id, id).await?)
+    }
+
+    async fn create_record(&self, user_id: i64, cmd: CreateRecordCmd) -> Result<Record> {
+        let tx = self.repo.begin().await?;
+
+        let transaction = record_from_cmd(&tx, user_id, cmd).await?;
+        apply_balance_changes(&tx, user_id, transaction.balance_changes(), 1).await?;
+        let id = tx.create_record(user_id, transaction).await?;
+        let record = tx.get_record_by_id(user_id, id).await?;
+        audit(&tx, user_id, AuditAction::Create, None, Some(&record)).await?;
+        tx.commit().await?;
+
+        Ok(record)
     }
 
-    async fn update_record(&self, cmd: UpdateRecordCmd) -> Result<Record> {
-        let mut record = self.repo.get_record_by_id(cmd.id).await?;
+    async fn update_record(&self, user_id: i64, cmd: UpdateRecordCmd) -> Result<Record> {
+        let tx = self.repo.begin().await?;
+
+        let mut record = tx.get_record_by_id(user_id, cmd.id).await?;
+        let before = record.clone();
+        apply_balance_changes(&tx, user_id, record.balance_changes(), -1).await?;
+
         let mut category: Option<Category> = None;
         if let Some(category_id) = cmd.category_id {
-            category = Some(self.repo.get_category_by_id(category_id).await?);
+            category = Some(tx.get_category_by_id(user_id, category_id).await?);
         }
         record.description = cmd.description;
-        record.set_amount(cmd.amount)?;
+        let amount = cmd.amount.to_money(&record.currency)?;
+        record.set_amount(amount.clone())?;
         record.category = category;
+        let splits = match cmd.splits {
+            Some(splits) => resolve_splits(&tx, user_id, splits, &record.currency).await?,
+            None => std::mem::take(&mut record.splits),
+        };
+        record.set_splits(splits)?;
+        if let Some(tag_ids) = cmd.tag_ids {
+            record.tags = resolve_tags(&tx, user_id, tag_ids).await?;
+        }
+        record.set_dates(
+            cmd.occurred_at.unwrap_or(record.occurred_at),
+            cmd.posted_at.or(record.posted_at),
+        )?;
         record.updated_at = Local::now();
 
-        self.repo.update_record(record).await?;
-        Ok(self.repo.get_record_by_id(cmd.id).await?)
+        match record.transfer.as_ref() {
+            Some(transfer) => {
+                let destination_account_id =
+                    cmd.destination_account_id.unwrap_or(transfer.account_id);
+                let destination = tx
+                    .get_account_by_id(user_id, destination_account_id)
+                    .await?;
+                let received_amount = match cmd.received_amount.as_ref() {
+                    // The destination keeps what it received unless it now
+                    // holds another currency.
+                    None if destination.currency == transfer.currency => {
+                        Some(Money::new(transfer.amount.get(), transfer.currency.clone()))
+                    }
+                    received => received_amount(&amount, received, &destination)?,
+                };
+                record.set_transfer(destination_account_id, received_amount)?;
+            }
+            None if cmd.destination_account_id.is_some() || cmd.received_amount.is_some() => {
+                return Err(RecordError::UnexpectedTransferDestination.into());
+            }
+            None => {}
+        }
+
+        apply_balance_changes(&tx, user_id, record.balance_changes(), 1).await?;
+        tx.update_record(user_id, record).await?;
+        let record = tx.get_record_by_id(user_id, cmd.id).await?;
+        audit(
+            &tx,
+            user_id,
+            AuditAction::Update,
+            Some(&before),
+            Some(&record),
+        )
+        .await?;
+        tx.commit().await?;
+
+        Ok(record)
     }
 
-    async fn delete_record(&self, id: i64) -> Result<()> {
-        self.repo.delete_record(id).await?;
+    async fn delete_record(&self, user_id: i64, id: i64) -> Result<()> {
+        let tx = self.repo.begin().await?;
+
+        let record = tx.get_record_by_id(user_id, id).await?;
+        apply_balance_changes(&tx, user_id, record.balance_changes(), -1).await?;
+        tx.delete_record(user_id, id).await?;
+        audit(&tx, user_id, AuditAction::Delete, Some(&record), None).await?;
+        tx.commit().await?;
+
         Ok(())
     }
 }
+
+/// Builds a new record from `cmd` after checking that the referenced accounts
+/// and category belong to the user.
+pub(crate) async fn record_from_cmd<T: BudgetRepository>(
+    repo: &T,
+    user_id: i64,
+    cmd: CreateRecordCmd,
+) -> Result<Record> {
+    let mut category: Option<Category> = None;
+    if let Some(category_id) = cmd.category {
+        category = Some(repo.get_category_by_id(user_id, category_id).await?);
+    }
+    let account = repo.get_account_by_id(user_id, cmd.account_id).await?;
+    let amount = cmd.amount.to_money(&account.currency)?;
+    let splits = resolve_splits(repo, user_id, cmd.splits, &account.currency).await?;
+    let tags = resolve_tags(repo, user_id, cmd.tag_ids).await?;
+
+    let mut record = if cmd.transaction_type == RecordType::Transfer.to_string() {
+        let destination_account_id = cmd
+            .destination_account_id
+            .ok_or(RecordError::TransferDestinationRequired)?;
+        let destination = repo
+            .get_account_by_id(user_id, destination_account_id)
+            .await?;
+        let received_amount = received_amount(&amount, cmd.received_amount.as_ref(), &destination)?;
+
+        Record::new_transfer(
+            cmd.account_id,
+            destination_account_id,
+            amount,
+            received_amount,
+            category,
+            cmd.description,
+        )?
+    } else {
+        if cmd.destination_account_id.is_some() || cmd.received_amount.is_some() {
+            return Err(RecordError::UnexpectedTransferDestination.into());
+        }
+
+        Record::new(
+            cmd.account_id,
+            cmd.transaction_type,
+            amount,
+            category,
+            cmd.description,
+        )?
+    };
+    record.set_splits(splits)?;
+    record.tags = tags;
+    record.set_dates(cmd.occurred_at.unwrap_or(record.occurred_at), cmd.posted_at)?;
+
+    Ok(record)
+}
+
+/// Resolves the received amount of a transfer in the currency of the
+/// destination account, it can only be left out when both accounts share a
+/// currency.
+fn received_amount(
+    amount: &Money,
+    received_amount: Option<&Amount>,
+    destination: &Account,
+) -> Result<Option<Money>> {
+    match received_amount {
+        Some(received_amount) => Ok(Some(received_amount.to_money(&destination.currency)?)),
+        None if *amount.currency() != destination.currency => {
+            Err(RecordError::ReceivedAmountRequired {
+                from: amount.currency().clone(),
+                to: destination.currency.clone(),
+            }
+            .into())
+        }
+        None => Ok(None),
+    }
+}
+
+/// Builds split lines in the currency of the record, failing when any
+/// category does not belong to the user.
+async fn resolve_splits<T: BudgetRepository>(
+    repo: &T,
+    user_id: i64,
+    splits: Vec<RecordSplitCmd>,
+    currency: &Currency,
+) -> Result<Vec<RecordSplit>> {
+    let mut result = Vec::with_capacity(splits.len());
+    for split in splits {
+        let category = repo.get_category_by_id(user_id, split.category_id).await?;
+        let amount = split.amount.to_money(currency)?;
+        result.push(RecordSplit::new(category, amount.minor(), split.note)?);
+    }
+
+    Ok(result)
+}
+
+/// Loads the tags with the given ids, failing when any of them does not
+/// belong to the user.
+async fn resolve_tags<T: BudgetRepository>(
+    repo: &T,
+    user_id: i64,
+    mut tag_ids: Vec<i64>,
+) -> Result<Vec<Tag>> {
+    tag_ids.sort_unstable();
+    tag_ids.dedup();
+
+    let mut tags = Vec::with_capacity(tag_ids.len());
+    for id in tag_ids {
+        tags.push(repo.get_tag_by_id(user_id, id).await?);
+    }
+
+    Ok(tags)
+}
+
+/// Applies balance changes of a record to its accounts, `sign` is `-1` to
+/// revert them.
+pub(crate) async fn apply_balance_changes<T: BudgetRepository>(
+    repo: &T,
+    user_id: i64,
+    changes: Vec<(i64, i64)>,
+    sign: i64,
+) -> Result<()> {
+    for (account_id, delta) in changes {
+        repo.update_account_balance(user_id, account_id, delta * sign)
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use sqlx::types::chrono::NaiveDate;
+
+    use crate::{
+        domain::{
+            errors::BudgetServiceError,
+            models::{Account, Currency, MoneyError, User},
+        },
+        repository::test::test_db,
+        service::{
+            accounts::{BudgetAccountsService, CreateAccountCmd},
+            budget::{AccountRepository, UserRepository},
+            currencies::{BudgetCurrenciesService, SetExchangeRateCmd},
+        },
+    };
+
+    use super::*;
+
+    fn income(amount: i64) -> CreateRecordCmd {
+        CreateRecordCmd {
+            account_id: 1,
+            transaction_type: "Income".into(),
+            amount: amount.into(),
+            category: None,
+            description: None,
+            destination_account_id: None,
+            received_amount: None,
+            splits: Vec::new(),
+            tag_ids: Vec::new(),
+            occurred_at: None,
+            posted_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_record_updates_balance() {
+        let fixture = include_str!("../repository/fixtures/fixture.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        let result = svc.create_record(1, income(1500)).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        let account = svc.repo.get_account_by_id(1, 1).await.unwrap();
+        assert_eq!(account.balance, 1500);
+    }
+
+    #[tokio::test]
+    async fn test_update_record_updates_balance() {
+        let fixture = include_str!("../repository/fixtures/fixture.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        let record = svc.create_record(1, income(300)).await.unwrap();
+        let result = svc
+            .update_record(
+                1,
+                UpdateRecordCmd {
+                    id: record.id,
+                    amount: 500.into(),
+                    description: None,
+                    category_id: None,
+                    destination_account_id: None,
+                    received_amount: None,
+                    splits: None,
+                    tag_ids: None,
+                    occurred_at: None,
+                    posted_at: None,
+                },
+            )
+            .await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        let account = svc.repo.get_account_by_id(1, 1).await.unwrap();
+        assert_eq!(account.balance, 500);
+    }
+
+    #[tokio::test]
+    async fn test_update_rejects_negative_amount() {
+        let fixture = include_str!("../repository/fixtures/fixture.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        let record = svc.create_record(1, income(300)).await.unwrap();
+        let result = svc
+            .update_record(
+                1,
+                UpdateRecordCmd {
+                    id: record.id,
+                    amount: Amount::Decimal("-5.00".into()),
+                    description: None,
+                    category_id: None,
+                    destination_account_id: None,
+                    received_amount: None,
+                    splits: None,
+                    tag_ids: None,
+                    occurred_at: None,
+                    posted_at: None,
+                },
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(BudgetServiceError::RecordValidationError(
+                RecordError::AmountCannotBeLessOrEqualToZero
+            ))
+        ));
+
+        let account = svc.repo.get_account_by_id(1, 1).await.unwrap();
+        assert_eq!(account.balance, 300);
+    }
+
+    #[tokio::test]
+    async fn test_delete_record_updates_balance() {
+        let fixture = include_str!("../repository/fixtures/fixture.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        let record = svc.create_record(1, income(700)).await.unwrap();
+        let result = svc.delete_record(1, record.id).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        let account = svc.repo.get_account_by_id(1, 1).await.unwrap();
+        assert_eq!(account.balance, 0);
+    }
+
+    #[tokio::test]
+    async fn test_transfer_updates_both_balances() {
+        let fixture = include_str!("../repository/fixtures/fixture.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        let account =
+            Account::new("savings".into(), 0, "DebitCard".into(), Currency::default()).unwrap();
+        let destination_id = svc.repo.create_account(1, account).await.unwrap();
+
+        let result = svc
+            .create_record(
+                1,
+                CreateRecordCmd {
+                    transaction_type: "Transfer".into(),
+                    destination_account_id: Some(destination_id),
+                    received_amount: Some(900.into()),
+                    tag_ids: Vec::new(),
+                    ..income(1000)
+                },
+            )
+            .await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        let source = svc.repo.get_account_by_id(1, 1).await.unwrap();
+        let destination = svc.repo.get_account_by_id(1, destination_id).await.unwrap();
+        assert_eq!(source.balance, -1000);
+        assert_eq!(destination.balance, 900);
+
+        svc.delete_record(1, result.unwrap().id).await.unwrap();
+
+        let source = svc.repo.get_account_by_id(1, 1).await.unwrap();
+        let destination = svc.repo.get_account_by_id(1, destination_id).await.unwrap();
+        assert_eq!(source.balance, 0);
+        assert_eq!(destination.balance, 0);
+    }
+
+    #[tokio::test]
+    async fn test_backdated_record() {
+        let fixture = include_str!("../repository/fixtures/records.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        let date = |value: &str| {
+            DateTime::parse_from_rfc3339(value)
+                .unwrap()
+                .with_timezone(&Local)
+        };
+        let record = svc
+            .create_record(
+                1,
+                CreateRecordCmd {
+                    occurred_at: Some(date("2026-03-10T12:00:00Z")),
+                    posted_at: Some(date("2026-03-12T00:00:00Z")),
+                    ..income(100)
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(record.occurred_at, date("2026-03-10T12:00:00Z"));
+        assert!(record.created_at > record.occurred_at);
+
+        let between = |from, to| ListRecordsCmd {
+            from: Some(date(from)),
+            to: Some(date(to)),
+            ..Default::default()
+        };
+        let result = svc
+            .list_records(1, between("2026-03-01T00:00:00Z", "2026-03-16T00:00:00Z"))
+            .await
+            .unwrap();
+        assert_eq!(
+            result.items.iter().map(|r| r.id).collect::<Vec<_>>(),
+            vec![3, record.id, 2]
+        );
+
+        let update = |occurred_at, posted_at| UpdateRecordCmd {
+            id: record.id,
+            amount: 100.into(),
+            description: None,
+            category_id: None,
+            destination_account_id: None,
+            received_amount: None,
+            splits: None,
+            tag_ids: None,
+            occurred_at,
+            posted_at,
+        };
+        let result = svc
+            .update_record(1, update(Some(date("2026-03-13T00:00:00Z")), None))
+            .await;
+        assert!(matches!(
+            result,
+            Err(BudgetServiceError::RecordValidationError(
+                RecordError::PostedBeforeOccurred
+            ))
+        ));
+
+        let result = svc.update_record(1, update(None, None)).await.unwrap();
+        assert_eq!(result.occurred_at, date("2026-03-10T12:00:00Z"));
+        assert_eq!(result.posted_at, Some(date("2026-03-12T00:00:00Z")));
+    }
+
+    #[tokio::test]
+    async fn test_list_records_in_pages() {
+        let fixture = include_str!("../repository/fixtures/fixture.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        let occurred_at = DateTime::parse_from_rfc3339("2026-03-10T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Local);
+        for (amount, category) in [(300, None), (100, Some(1)), (300, Some(1)), (200, None)] {
+            let cmd = CreateRecordCmd {
+                category,
+                occurred_at: Some(occurred_at),
+                ..income(amount)
+            };
+            svc.create_record(1, cmd).await.unwrap();
+        }
+
+        let ids = |records: &[Record]| records.iter().map(|r| r.id).collect::<Vec<_>>();
+        for sort_by in [
+            RecordSortField::Date,
+            RecordSortField::Amount,
+            RecordSortField::Category,
+        ] {
+            for sort_order in [SortOrder::Asc, SortOrder::Desc] {
+                let cmd = || ListRecordsCmd {
+                    sort_by,
+                    sort_order,
+                    ..Default::default()
+                };
+                let all = svc.list_records(1, cmd()).await.unwrap();
+                assert!(!all.has_more());
+
+                let mut paged = Vec::new();
+                let mut after = None;
+                loop {
+                    let page = svc
+                        .list_records(
+                            1,
+                            ListRecordsCmd {
+                                limit: Some(2),
+                                after,
+                                include_total: true,
+                                ..cmd()
+                            },
+                        )
+                        .await
+                        .unwrap();
+                    assert_eq!(page.total_count, Some(5));
+                    paged.extend(ids(&page.items));
+                    after = page.next_cursor;
+                    if after.is_none() {
+                        break;
+                    }
+                }
+                assert_eq!(paged, ids(&all.items), "{sort_by:?} {sort_order:?}");
+            }
+        }
+
+        // A record added while paging does not shift the following pages.
+        let first = svc
+            .list_records(
+                1,
+                ListRecordsCmd {
+                    limit: Some(2),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let all = svc
+            .list_records(1, ListRecordsCmd::default())
+            .await
+            .unwrap();
+        svc.create_record(1, income(50)).await.unwrap();
+        let rest = svc
+            .list_records(
+                1,
+                ListRecordsCmd {
+                    after: first.next_cursor.clone(),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(ids(&rest.items), ids(&all.items[2..]));
+
+        let result = svc
+            .list_records(
+                1,
+                ListRecordsCmd {
+                    after: first.next_cursor,
+                    sort_by: RecordSortField::Amount,
+                    ..Default::default()
+                },
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(BudgetServiceError::PageValidationError(
+                PageError::InvalidCursor
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_amount_bounds_convert_other_currencies() {
+        let fixture = include_str!("../repository/fixtures/fixture.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+        let usd = Currency::default();
+        let eur = Currency::from_str("EUR").unwrap();
+
+        let account = svc
+            .create_account(
+                1,
+                CreateAccountCmd {
+                    name: "euro".into(),
+                    account_type: "Cash".into(),
+                    initial_balance: 0.into(),
+                    currency: Some(eur.clone()),
+                },
+            )
+            .await
+            .unwrap();
+        let record = svc
+            .create_record(
+                1,
+                CreateRecordCmd {
+                    account_id: account.id,
+                    ..income(1000)
+                },
+            )
+            .await
+            .unwrap();
+
+        let above = |minor| ListRecordsCmd {
+            min_amount: Some(Money::new(minor, usd.clone())),
+            ..Default::default()
+        };
+        let ids = |page: Page<Record>| page.items.iter().map(|r| r.id).collect::<Vec<_>>();
+
+        // Without a rate the amount cannot be compared.
+        let result = svc.list_records(1, above(500)).await.unwrap();
+        assert_eq!(ids(result), vec![1]);
+
+        svc.set_exchange_rate(
+            1,
+            SetExchangeRateCmd {
+                base: eur,
+                quote: usd.clone(),
+                rate: 1.1,
+                valid_on: NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let result = svc.list_records(1, above(1050)).await.unwrap();
+        assert_eq!(ids(result), vec![record.id]);
+        let result = svc.list_records(1, above(1150)).await.unwrap();
+        assert!(result.items.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_amount_bounds_convert_between_decimal_places() {
+        let fixture = include_str!("../repository/fixtures/fixture.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+        let usd = Currency::default();
+        let jpy = Currency::from_str("JPY").unwrap();
+
+        let account = svc
+            .create_account(
+                1,
+                CreateAccountCmd {
+                    name: "yen".into(),
+                    account_type: "Cash".into(),
+                    initial_balance: 0.into(),
+                    currency: Some(jpy.clone()),
+                },
+            )
+            .await
+            .unwrap();
+        let record = svc
+            .create_record(
+                1,
+                CreateRecordCmd {
+                    account_id: account.id,
+                    ..income(1500)
+                },
+            )
+            .await
+            .unwrap();
+        svc.set_exchange_rate(
+            1,
+            SetExchangeRateCmd {
+                base: usd.clone(),
+                quote: jpy.clone(),
+                rate: 150.0,
+                valid_on: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let between = |min: Option<Money>, max: Option<Money>| ListRecordsCmd {
+            min_amount: min,
+            max_amount: max,
+            ..Default::default()
+        };
+        let ids = |page: Page<Record>| page.items.iter().map(|r| r.id).collect::<Vec<_>>();
+
+        // 1500 yen and 10 dollars are the same amount.
+        let cmd = between(Some(Money::new(990, usd.clone())), None);
+        let result = svc.list_records(1, cmd).await.unwrap();
+        assert_eq!(ids(result), vec![record.id, 1]);
+        let cmd = between(Some(Money::new(1010, usd.clone())), None);
+        assert!(svc.list_records(1, cmd).await.unwrap().items.is_empty());
+
+        let cmd = between(None, Some(Money::new(1510, jpy.clone())));
+        let result = svc.list_records(1, cmd).await.unwrap();
+        assert_eq!(ids(result), vec![record.id, 1]);
+        let cmd = between(None, Some(Money::new(1490, jpy)));
+        assert!(svc.list_records(1, cmd).await.unwrap().items.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_decimal_amounts_use_account_currency() {
+        let fixture = include_str!("../repository/fixtures/fixture.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        let decimal = |amount: &str| Amount::Decimal(amount.into());
+        let record = svc
+            .create_record(
+                1,
+                CreateRecordCmd {
+                    amount: decimal("12.5"),
+                    ..income(0)
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(i64::from(record.amount), 1250);
+
+        let result = svc
+            .create_record(
+                1,
+                CreateRecordCmd {
+                    amount: decimal("12.505"),
+                    ..income(0)
+                },
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(BudgetServiceError::MoneyValidationError(
+                MoneyError::TooManyDecimalPlaces { .. }
+            ))
+        ));
+
+        let jpy: Currency = "JPY".parse().unwrap();
+        let account = Account::new("yen".into(), 0, "Cash".into(), jpy).unwrap();
+        let destination_id = svc.repo.create_account(1, account).await.unwrap();
+        let transfer = |received_amount| CreateRecordCmd {
+            transaction_type: "Transfer".into(),
+            destination_account_id: Some(destination_id),
+            received_amount,
+            ..income(0)
+        };
+
+        let result = svc.create_record(1, transfer(None)).await;
+        assert!(matches!(
+            result,
+            Err(BudgetServiceError::RecordValidationError(
+                RecordError::ReceivedAmountRequired { .. }
+            ))
+        ));
+
+        let result = svc
+            .create_record(
+                1,
+                CreateRecordCmd {
+                    amount: decimal("10.00"),
+                    ..transfer(Some(decimal("1500")))
+                },
+            )
+            .await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        let destination = svc.repo.get_account_by_id(1, destination_id).await.unwrap();
+        assert_eq!(destination.balance, 1500);
+    }
+
+    #[tokio::test]
+    async fn test_update_keeps_received_amount() {
+        let fixture = include_str!("../repository/fixtures/fixture.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        let jpy: Currency = "JPY".parse().unwrap();
+        let account = Account::new("yen".into(), 0, "Cash".into(), jpy).unwrap();
+        let destination_id = svc.repo.create_account(1, account).await.unwrap();
+        let record = svc
+            .create_record(
+                1,
+                CreateRecordCmd {
+                    transaction_type: "Transfer".into(),
+                    amount: 1000.into(),
+                    destination_account_id: Some(destination_id),
+                    received_amount: Some(1500.into()),
+                    ..income(0)
+                },
+            )
+            .await
+            .unwrap();
+
+        let update = |description: &str| UpdateRecordCmd {
+            id: record.id,
+            amount: 1000.into(),
+            description: Some(description.into()),
+            category_id: None,
+            destination_account_id: None,
+            received_amount: None,
+            splits: None,
+            tag_ids: None,
+            occurred_at: None,
+            posted_at: None,
+        };
+        let result = svc.update_record(1, update("exchange")).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+        let transfer = result.unwrap().transfer.unwrap();
+        assert_eq!(transfer.amount.get(), 1500);
+        let destination = svc.repo.get_account_by_id(1, destination_id).await.unwrap();
+        assert_eq!(destination.balance, 1500);
+
+        let result = svc
+            .update_record(
+                1,
+                UpdateRecordCmd {
+                    received_amount: Some(1600.into()),
+                    ..update("exchange")
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.transfer.unwrap().amount.get(), 1600);
+        let destination = svc.repo.get_account_by_id(1, destination_id).await.unwrap();
+        assert_eq!(destination.balance, 1600);
+    }
+
+    #[tokio::test]
+    async fn test_records_are_isolated_by_user() {
+        let fixture = include_str!("../repository/fixtures/fixture.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        let other_user_id = svc
+            .repo
+            .create_user(User::new("other".into()).unwrap())
+            .await
+            .unwrap();
+
+        let result = svc.create_record(other_user_id, income(100)).await;
+        assert!(result.is_err(), "must not use another user's account");
+
+        let result = svc.delete_record(other_user_id, 1).await;
+        assert!(result.is_err(), "must not delete another user's record");
+
+        let result = svc.get_record(other_user_id, 1).await;
+        assert!(matches!(
+            result,
+            Err(BudgetServiceError::EntityNotFoundError {
+                entity: "record",
+                id: 1
+            })
+        ));
+
+        let records = svc
+            .list_records(other_user_id, ListRecordsCmd::default())
+            .await
+            .unwrap();
+        assert!(records.items.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_split_record() {
+        let fixture = include_str!("../repository/fixtures/records.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        let split = |category_id, amount: i64| RecordSplitCmd {
+            category_id,
+            amount: amount.into(),
+            note: None,
+        };
+        let cmd = |splits| CreateRecordCmd {
+            transaction_type: "Outcome".into(),
+            splits,
+            ..income(1000)
+        };
+
+        let result = svc
+            .create_record(1, cmd(vec![split(2, 600), split(3, 300)]))
+            .await;
+        assert!(matches!(
+            result,
+            Err(BudgetServiceError::RecordValidationError(
+                RecordError::SplitAmountMismatch {
+                    expected: 1000,
+                    actual: 900
+                }
+            ))
+        ));
+
+        let result = svc
+            .create_record(1, cmd(vec![split(2, 600), split(6, 400)]))
+            .await;
+        assert!(result.is_err(), "must not use another user's category");
+
+        let result = svc
+            .create_record(1, cmd(vec![split(2, 600), split(3, 400)]))
+            .await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+        let record = result.unwrap();
+        assert_eq!(record.category, None);
+        assert_eq!(record.splits.len(), 2);
+
+        let ids: Vec<i64> = svc
+            .list_records(
+                1,
+                ListRecordsCmd {
+                    category_ids: vec![1],
+                    include_subcategories: true,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap()
+            .items
+            .iter()
+            .map(|r| r.id)
+            .collect();
+        assert_eq!(ids, vec![record.id, 6, 3, 2, 1]);
+
+        let update = |splits| UpdateRecordCmd {
+            id: record.id,
+            amount: 1200.into(),
+            description: None,
+            category_id: None,
+            destination_account_id: None,
+            received_amount: None,
+            splits,
+            tag_ids: None,
+            occurred_at: None,
+            posted_at: None,
+        };
+
+        let result = svc.update_record(1, update(None)).await;
+        assert!(matches!(
+            result,
+            Err(BudgetServiceError::RecordValidationError(
+                RecordError::SplitAmountMismatch { .. }
+            ))
+        ));
+
+        let result = svc
+            .update_record(1, update(Some(vec![split(2, 1000), split(4, 200)])))
+            .await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        let account = svc.repo.get_account_by_id(1, 1).await.unwrap();
+        assert_eq!(account.balance, -1200);
+    }
+}
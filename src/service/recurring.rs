@@ -0,0 +1,386 @@
+use async_trait::async_trait;
+use sqlx::types::chrono::{DateTime, Local};
+
+use crate::{
+    domain::{
+        Result,
+        models::{
+            Amount, AuditAction, AuditEntry, Money, Page, RecordError, RecordType, Recurrence,
+            RecurrenceUnit, RecurringRecord,
+        },
+    },
+    service::{
+        audit::audit,
+        budget::{BudgetRepository, BudgetServiceImpl, PageCmd},
+        records::{CreateRecordCmd, apply_balance_changes, record_from_cmd},
+    },
+};
+
+/// Most occurrences returned by a single preview.
+pub const MAX_PREVIEW_OCCURRENCES: usize = 100;
+
+pub struct CreateRecurringRecordCmd {
+    pub record: CreateRecordCmd,
+    pub unit: RecurrenceUnit,
+    pub interval: u32,
+    pub day_of_month: Option<u32>,
+    pub starts_at: DateTime<Local>,
+    pub ends_at: Option<DateTime<Local>>,
+}
+
+/// Changes the record template and the end date, the schedule itself cannot
+/// be changed once occurrences may have been created.
+pub struct UpdateRecurringRecordCmd {
+    pub id: i64,
+    pub amount: Amount,
+    pub description: Option<String>,
+    pub category_id: Option<i64>,
+    pub destination_account_id: Option<i64>,
+    pub received_amount: Option<Amount>,
+    pub ends_at: Option<DateTime<Local>>,
+}
+
+#[async_trait]
+pub trait BudgetRecurringService: Send + Sync + 'static {
+    /// Recurring records ordered by id.
+    async fn list_recurring_records(
+        &self,
+        user_id: i64,
+        page: PageCmd,
+    ) -> Result<Page<RecurringRecord>>;
+    async fn create_recurring_record(
+        &self,
+        user_id: i64,
+        cmd: CreateRecurringRecordCmd,
+    ) -> Result<RecurringRecord>;
+    async fn update_recurring_record(
+        &self,
+        user_id: i64,
+        cmd: UpdateRecurringRecordCmd,
+    ) -> Result<RecurringRecord>;
+    async fn delete_recurring_record(&self, user_id: i64, id: i64) -> Result<()>;
+    /// Returns dates of the next `count` occurrences not yet turned into records.
+    async fn preview_recurring_record(
+        &self,
+        user_id: i64,
+        id: i64,
+        count: usize,
+    ) -> Result<Vec<DateTime<Local>>>;
+    /// Creates records for all occurrences due at `now` and returns how many
+    /// were created, used by the scheduler for all users.
+    async fn materialize_recurring_records(&self, now: DateTime<Local>) -> Result<usize>;
+}
+
+#[async_trait]
+impl<T: BudgetRepository> BudgetRecurringService for BudgetServiceImpl<T> {
+    async fn list_recurring_records(
+        &self,
+        user_id: i64,
+        page: PageCmd,
+    ) -> Result<Page<RecurringRecord>> {
+        Ok(self
+            .repo
+            .list_recurring_records_page(user_id, &page)
+            .await?)
+    }
+
+    async fn create_recurring_record(
+        &self,
+        user_id: i64,
+        cmd: CreateRecurringRecordCmd,
+    ) -> Result<RecurringRecord> {
+        let recurrence = Recurrence::new(cmd.unit, cmd.interval, cmd.day_of_month)?;
+
+        let tx = self.repo.begin().await?;
+        let template = record_from_cmd(&tx, user_id, cmd.record).await?;
+        let recurring = RecurringRecord::new(
+            user_id,
+            template.account_id,
+            template.record_type,
+            Money::new(template.amount.get(), template.currency),
+            template.description,
+            template.category.map(|c| c.id),
+            template.transfer.as_ref().map(|t| t.account_id),
+            template
+                .transfer
+                .map(|t| Money::new(t.amount.get(), t.currency)),
+            recurrence,
+            cmd.starts_at,
+            cmd.ends_at,
+        )?;
+        let id = tx.create_recurring_record(recurring).await?;
+        let recurring = tx.get_recurring_record_by_id(user_id, id).await?;
+        audit(&tx, user_id, AuditAction::Create, None, Some(&recurring)).await?;
+        tx.commit().await?;
+
+        Ok(recurring)
+    }
+
+    async fn update_recurring_record(
+        &self,
+        user_id: i64,
+        cmd: UpdateRecurringRecordCmd,
+    ) -> Result<RecurringRecord> {
+        let tx = self.repo.begin().await?;
+        let before = tx.get_recurring_record_by_id(user_id, cmd.id).await?;
+        let mut recurring = before.clone();
+
+        let is_transfer = recurring.record_type == RecordType::Transfer;
+        if !is_transfer && (cmd.destination_account_id.is_some() || cmd.received_amount.is_some()) {
+            return Err(RecordError::UnexpectedTransferDestination.into());
+        }
+
+        recurring.amount = cmd.amount.to_money(&recurring.currency)?.minor();
+        recurring.description = cmd.description;
+        recurring.category_id = cmd.category_id;
+        if let Some(destination_account_id) = cmd
+            .destination_account_id
+            .or(recurring.destination_account_id)
+            .filter(|_| is_transfer)
+        {
+            let destination = tx
+                .get_account_by_id(user_id, destination_account_id)
+                .await?;
+            recurring.destination_account_id = Some(destination_account_id);
+            recurring.received_amount = match cmd.received_amount {
+                Some(amount) => Some(amount.to_money(&destination.currency)?.minor()),
+                None => None,
+            };
+            recurring.destination_currency = Some(destination.currency);
+        }
+        recurring.set_ends_at(cmd.ends_at)?;
+        recurring.updated_at = Local::now();
+
+        // Validates the changed template the same way occurrences are built.
+        record_from_cmd(&tx, user_id, record_cmd(&recurring, recurring.starts_at)).await?;
+
+        tx.update_recurring_record(recurring).await?;
+        let recurring = tx.get_recurring_record_by_id(user_id, cmd.id).await?;
+        audit(
+            &tx,
+            user_id,
+            AuditAction::Update,
+            Some(&before),
+            Some(&recurring),
+        )
+        .await?;
+        tx.commit().await?;
+
+        Ok(recurring)
+    }
+
+    async fn delete_recurring_record(&self, user_id: i64, id: i64) -> Result<()> {
+        let tx = self.repo.begin().await?;
+        let recurring = tx.get_recurring_record_by_id(user_id, id).await?;
+        tx.delete_recurring_record(user_id, id).await?;
+        audit(&tx, user_id, AuditAction::Delete, Some(&recurring), None).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn preview_recurring_record(
+        &self,
+        user_id: i64,
+        id: i64,
+        count: usize,
+    ) -> Result<Vec<DateTime<Local>>> {
+        let recurring = self.repo.get_recurring_record_by_id(user_id, id).await?;
+
+        Ok(recurring.upcoming(count.min(MAX_PREVIEW_OCCURRENCES)))
+    }
+
+    async fn materialize_recurring_records(&self, now: DateTime<Local>) -> Result<usize> {
+        let due = self.repo.list_due_recurring_records(now).await?;
+
+        let mut created = 0;
+        for recurring in due {
+            match materialize(&self.repo, recurring.user_id, recurring.id, now).await {
+                Ok(count) => created += count,
+                Err(e) => {
+                    tracing::warn!(
+                        "cannot create records of recurring record {}: {e}",
+                        recurring.id
+                    )
+                }
+            }
+        }
+
+        Ok(created)
+    }
+}
+
+/// Creates records for every due occurrence of a single recurring record in
+/// one transaction.
+async fn materialize<T: BudgetRepository>(
+    repo: &T,
+    user_id: i64,
+    id: i64,
+    now: DateTime<Local>,
+) -> Result<usize> {
+    let tx = repo.begin().await?;
+    let mut recurring = tx.get_recurring_record_by_id(user_id, id).await?;
+
+    let mut created = 0;
+    while let Some(occurrence_at) = recurring.next_occurrence_at.filter(|at| *at <= now) {
+        let cmd = record_cmd(&recurring, occurrence_at);
+        let record = record_from_cmd(&tx, user_id, cmd).await?;
+
+        apply_balance_changes(&tx, user_id, record.balance_changes(), 1).await?;
+        let record_id = tx.create_record(user_id, record).await?;
+        let record = tx.get_record_by_id(user_id, record_id).await?;
+        let entry = AuditEntry::new(user_id, None, AuditAction::Create, None, Some(&record));
+        tx.append_audit_entry(entry).await?;
+
+        recurring.advance();
+        created += 1;
+    }
+
+    tx.update_recurring_record(recurring).await?;
+    tx.commit().await?;
+
+    Ok(created)
+}
+
+fn record_cmd(recurring: &RecurringRecord, occurred_at: DateTime<Local>) -> CreateRecordCmd {
+    CreateRecordCmd {
+        account_id: recurring.account_id,
+        transaction_type: recurring.record_type.to_string(),
+        amount: Amount::Minor(recurring.amount),
+        category: recurring.category_id,
+        description: recurring.description.clone(),
+        destination_account_id: recurring.destination_account_id,
+        received_amount: recurring.received_amount.map(Amount::Minor),
+        splits: Vec::new(),
+        tag_ids: Vec::new(),
+        occurred_at: Some(occurred_at),
+        posted_at: None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::{
+        domain::errors::BudgetServiceError,
+        repository::test::test_db,
+        service::{
+            budget::{AccountRepository, RecurringRecordRepository},
+            records::{BudgetRecordService, ListRecordsCmd},
+        },
+    };
+
+    use super::*;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    fn cmd(
+        starts_at: DateTime<Local>,
+        ends_at: Option<DateTime<Local>>,
+    ) -> CreateRecurringRecordCmd {
+        CreateRecurringRecordCmd {
+            record: CreateRecordCmd {
+                account_id: 1,
+                transaction_type: "Outcome".into(),
+                amount: 500.into(),
+                category: Some(1),
+                description: Some("subscription".into()),
+                destination_account_id: None,
+                received_amount: None,
+                splits: Vec::new(),
+                tag_ids: Vec::new(),
+                occurred_at: None,
+                posted_at: None,
+            },
+            unit: RecurrenceUnit::Day,
+            interval: 2,
+            day_of_month: None,
+            starts_at,
+            ends_at,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_materialize_recurring_records() {
+        let fixture = include_str!("../repository/fixtures/fixture.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        let now = Local::now();
+        let recurring = svc
+            .create_recurring_record(1, cmd(now - DAY * 5, None))
+            .await
+            .expect("must create recurring record");
+
+        let created = svc
+            .materialize_recurring_records(now)
+            .await
+            .expect("must materialize");
+        assert_eq!(created, 3);
+
+        let records = svc
+            .list_records(1, ListRecordsCmd::default())
+            .await
+            .unwrap();
+        assert_eq!(records.items.len(), 4);
+        let account = svc.repo.get_account_by_id(1, 1).await.unwrap();
+        assert_eq!(account.balance, -1500);
+
+        let created = svc.materialize_recurring_records(now).await.unwrap();
+        assert_eq!(created, 0);
+
+        let preview = svc
+            .preview_recurring_record(1, recurring.id, 2)
+            .await
+            .unwrap();
+        assert_eq!(preview.len(), 2);
+        assert!(preview[0] > now && preview[0] < preview[1]);
+    }
+
+    #[tokio::test]
+    async fn test_recurring_record_end_date() {
+        let fixture = include_str!("../repository/fixtures/fixture.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        let now = Local::now();
+        let recurring = svc
+            .create_recurring_record(1, cmd(now - DAY * 5, Some(now - DAY * 2)))
+            .await
+            .expect("must create recurring record");
+
+        let created = svc.materialize_recurring_records(now).await.unwrap();
+        assert_eq!(created, 2);
+
+        let recurring = svc
+            .repo
+            .get_recurring_record_by_id(1, recurring.id)
+            .await
+            .unwrap();
+        assert_eq!(recurring.next_occurrence_at, None);
+
+        let result = svc
+            .create_recurring_record(1, cmd(now, Some(now - DAY)))
+            .await;
+        assert!(matches!(
+            result,
+            Err(BudgetServiceError::RecurrenceValidationError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_recurring_record_checks_references() {
+        let fixture = include_str!("../repository/fixtures/fixture.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        let result = svc
+            .create_recurring_record(2, cmd(Local::now(), None))
+            .await;
+        assert!(result.is_err());
+
+        let result = svc
+            .list_recurring_records(1, PageCmd::default())
+            .await
+            .unwrap();
+        assert!(result.items.is_empty());
+    }
+}
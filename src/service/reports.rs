@@ -0,0 +1,702 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use chrono::TimeDelta;
+use sqlx::types::chrono::{DateTime, Local};
+
+use crate::{
+    domain::{
+        Result,
+        models::{
+            CashFlowBucket, Category, CategoryBudgetReport, Currency, Money, ReportError,
+            TagSpending,
+        },
+    },
+    service::budget::{BudgetRepository, BudgetServiceImpl},
+};
+
+pub struct BudgetReportCmd {
+    /// Inclusive start of the period.
+    pub from: DateTime<Local>,
+    /// Exclusive end of the period.
+    pub to: DateTime<Local>,
+    /// Amounts of accounts in other currencies are converted to it, and so
+    /// are budgets when it is not the reporting currency of the user.
+    pub currency: Currency,
+}
+
+pub struct CashFlowCmd {
+    /// Inclusive start of the period.
+    pub from: DateTime<Local>,
+    /// Exclusive end of the period.
+    pub to: DateTime<Local>,
+    /// Amounts of accounts in other currencies are converted to it.
+    pub currency: Currency,
+    pub interval: CashFlowInterval,
+    /// Only records of this account, transfers into and out of it included.
+    pub account_id: Option<i64>,
+    /// Only records in this category or its subcategories.
+    pub category_id: Option<i64>,
+}
+
+/// Length of the intervals of a cash flow report, weeks start on Monday.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CashFlowInterval {
+    Day,
+    Week,
+    #[default]
+    Month,
+    Year,
+}
+
+#[async_trait]
+pub trait BudgetReportsService: Send + Sync + 'static {
+    async fn budget_report(
+        &self,
+        user_id: i64,
+        cmd: BudgetReportCmd,
+    ) -> Result<Vec<CategoryBudgetReport>>;
+    /// Returns the outcome total of every tag in the period, tags without
+    /// records included.
+    async fn tag_report(&self, user_id: i64, cmd: BudgetReportCmd) -> Result<Vec<TagSpending>>;
+    /// Returns income, outcome and net flow per interval of the period.
+    /// Transfers between the user's own accounts only count when the report
+    /// is limited to one account.
+    async fn cash_flow(&self, user_id: i64, cmd: CashFlowCmd) -> Result<Vec<CashFlowBucket>>;
+}
+
+#[async_trait]
+impl<T: BudgetRepository> BudgetReportsService for BudgetServiceImpl<T> {
+    async fn budget_report(
+        &self,
+        user_id: i64,
+        cmd: BudgetReportCmd,
+    ) -> Result<Vec<CategoryBudgetReport>> {
+        if cmd.from >= cmd.to {
+            return Err(ReportError::InvalidPeriod.into());
+        }
+
+        let tx = self.repo.begin().await?;
+        ensure_exchange_rates(&tx, user_id, &cmd).await?;
+        let mut categories = tx.list_categories(user_id).await?;
+        convert_budgets(&tx, user_id, &cmd, &mut categories).await?;
+        let spending = tx
+            .spending_by_category(user_id, &cmd.currency, cmd.from, cmd.to)
+            .await?;
+        tx.commit().await?;
+
+        // Every category adds its own spending to all of its ancestors.
+        let parents: HashMap<i64, Option<i64>> =
+            categories.iter().map(|c| (c.id, c.parent_id)).collect();
+        let mut totals: HashMap<i64, Money> = HashMap::new();
+        for (category_id, amount) in spending {
+            let amount = Money::new(amount, cmd.currency.clone());
+            let mut current = Some(category_id);
+            let mut depth = 0;
+            while let Some(id) = current {
+                let total = totals
+                    .entry(id)
+                    .or_insert_with(|| Money::zero(cmd.currency.clone()));
+                *total = total.checked_add(&amount)?;
+                current = parents.get(&id).copied().flatten();
+
+                depth += 1;
+                if depth > parents.len() {
+                    break;
+                }
+            }
+        }
+
+        categories.sort_by_key(|c| c.id);
+
+        Ok(categories
+            .into_iter()
+            .map(|category| {
+                let spent = totals.get(&category.id).map_or(0, Money::minor);
+                CategoryBudgetReport::new(category, spent)
+            })
+            .collect())
+    }
+
+    async fn tag_report(&self, user_id: i64, cmd: BudgetReportCmd) -> Result<Vec<TagSpending>> {
+        if cmd.from >= cmd.to {
+            return Err(ReportError::InvalidPeriod.into());
+        }
+
+        let tx = self.repo.begin().await?;
+        ensure_exchange_rates(&tx, user_id, &cmd).await?;
+        let tags = tx.list_tags(user_id).await?;
+        let totals: HashMap<i64, i64> = tx
+            .spending_by_tag(user_id, &cmd.currency, cmd.from, cmd.to)
+            .await?
+            .into_iter()
+            .collect();
+        tx.commit().await?;
+
+        Ok(tags
+            .into_iter()
+            .map(|tag| {
+                let spent = totals.get(&tag.id).copied().unwrap_or_default();
+                TagSpending { tag, spent }
+            })
+            .collect())
+    }
+
+    async fn cash_flow(&self, user_id: i64, cmd: CashFlowCmd) -> Result<Vec<CashFlowBucket>> {
+        if cmd.from >= cmd.to {
+            return Err(ReportError::InvalidPeriod.into());
+        }
+
+        let tx = self.repo.begin().await?;
+        if let Some(account_id) = cmd.account_id {
+            tx.get_account_by_id(user_id, account_id).await?;
+        }
+        if let Some(category_id) = cmd.category_id {
+            tx.get_category_by_id(user_id, category_id).await?;
+        }
+        if let Some((from, date)) = tx.find_missing_cash_flow_rate(user_id, &cmd).await? {
+            return Err(ReportError::MissingExchangeRate {
+                from,
+                to: cmd.currency,
+                date,
+            }
+            .into());
+        }
+        let buckets = tx.cash_flow(user_id, &cmd).await?;
+        tx.commit().await?;
+
+        Ok(buckets)
+    }
+}
+
+/// Fails with the first record of the period whose amount cannot be
+/// converted to the report currency.
+async fn ensure_exchange_rates<T: BudgetRepository>(
+    repo: &T,
+    user_id: i64,
+    cmd: &BudgetReportCmd,
+) -> Result<()> {
+    let missing = repo
+        .find_missing_exchange_rate(user_id, &cmd.currency, cmd.from, cmd.to)
+        .await?;
+    if let Some((from, date)) = missing {
+        return Err(ReportError::MissingExchangeRate {
+            from,
+            to: cmd.currency.clone(),
+            date,
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Converts the budgets, kept in the reporting currency of the user, to the
+/// report currency with the latest rate on or before the last day of the
+/// period.
+async fn convert_budgets<T: BudgetRepository>(
+    repo: &T,
+    user_id: i64,
+    cmd: &BudgetReportCmd,
+    categories: &mut [Category],
+) -> Result<()> {
+    let reporting_currency = repo.get_user_by_id(user_id).await?.reporting_currency;
+    if reporting_currency == cmd.currency || categories.iter().all(|c| c.budget.is_none()) {
+        return Ok(());
+    }
+
+    let date = (cmd.to - TimeDelta::nanoseconds(1)).date_naive();
+    let rate = repo
+        .find_exchange_rate(user_id, &reporting_currency, &cmd.currency, date)
+        .await?
+        .ok_or_else(|| ReportError::MissingExchangeRate {
+            from: reporting_currency.clone(),
+            to: cmd.currency.clone(),
+            date,
+        })?;
+    for category in categories {
+        if let Some(budget) = category.budget {
+            let budget = Money::new(budget, reporting_currency.clone())
+                .convert(rate, cmd.currency.clone())?;
+            category.budget = Some(budget.minor());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use ::chrono::Days;
+    use sqlx::types::chrono::NaiveDate;
+
+    use crate::{
+        domain::errors::BudgetServiceError,
+        repository::test::test_db,
+        service::{
+            accounts::{BudgetAccountsService, CreateAccountCmd},
+            categories::{BudgetCategoriesService, UpdateCategoryCmd},
+            currencies::{BudgetCurrenciesService, SetExchangeRateCmd},
+            records::{BudgetRecordService, CreateRecordCmd},
+        },
+    };
+
+    use super::*;
+
+    fn cmd(from: &str, to: &str) -> BudgetReportCmd {
+        BudgetReportCmd {
+            from: DateTime::parse_from_rfc3339(from).unwrap().into(),
+            to: DateTime::parse_from_rfc3339(to).unwrap().into(),
+            currency: Currency::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_budget_report_rolls_up_subcategories() {
+        let fixture = include_str!("../repository/fixtures/records.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+        svc.update_category(
+            1,
+            UpdateCategoryCmd {
+                id: 1,
+                name: "food".into(),
+                budget: Some(3000.into()),
+            },
+        )
+        .await
+        .expect("must update category");
+
+        let result = svc
+            .budget_report(1, cmd("2026-02-01T00:00:00Z", "2026-04-01T00:00:00Z"))
+            .await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        let report: HashMap<i64, CategoryBudgetReport> = result
+            .unwrap()
+            .into_iter()
+            .map(|r| (r.category.id, r))
+            .collect();
+        assert_eq!(report.len(), 5);
+
+        let food = &report[&1];
+        assert_eq!(food.spent, 300 + 120 + 2500);
+        assert_eq!(food.remaining, Some(80));
+        assert_eq!(food.percent_used, Some(97.33));
+
+        let groceries = &report[&2];
+        assert_eq!(groceries.spent, 420);
+        assert_eq!(groceries.remaining, None);
+        assert_eq!(groceries.percent_used, None);
+
+        assert_eq!(report[&4].spent, 120);
+        assert_eq!(report[&5].spent, 0);
+    }
+
+    #[tokio::test]
+    async fn test_budget_report_invalid_period() {
+        let svc = BudgetServiceImpl::new(test_db(None).await);
+
+        let result = svc
+            .budget_report(1, cmd("2026-04-01T00:00:00Z", "2026-03-01T00:00:00Z"))
+            .await;
+        assert!(matches!(
+            result,
+            Err(BudgetServiceError::ReportValidationError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_tag_report() {
+        let fixture = include_str!("../repository/fixtures/records.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        let result = svc
+            .tag_report(1, cmd("2026-03-01T00:00:00Z", "2026-04-01T00:00:00Z"))
+            .await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        let report: Vec<(String, i64)> = result
+            .unwrap()
+            .into_iter()
+            .map(|r| (r.tag.name, r.spent))
+            .collect();
+        assert_eq!(
+            report,
+            vec![("business".into(), 2620), ("vacation".into(), 2500)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_budget_report_converts_currencies() {
+        let fixture = include_str!("../repository/fixtures/records.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+        let eur = Currency::from_str("EUR").unwrap();
+
+        let account = svc
+            .create_account(
+                1,
+                CreateAccountCmd {
+                    name: "euro".into(),
+                    account_type: "Cash".into(),
+                    initial_balance: 0.into(),
+                    currency: Some(eur.clone()),
+                },
+            )
+            .await
+            .expect("must create account");
+        for (account_id, amount, category) in [(account.id, 1000, 3), (1, 1100, 4)] {
+            svc.create_record(
+                1,
+                CreateRecordCmd {
+                    account_id,
+                    transaction_type: "Outcome".into(),
+                    amount: amount.into(),
+                    category: Some(category),
+                    description: None,
+                    destination_account_id: None,
+                    received_amount: None,
+                    splits: vec![],
+                    tag_ids: vec![],
+                    occurred_at: None,
+                    posted_at: None,
+                },
+            )
+            .await
+            .expect("must create record");
+        }
+
+        let now = Local::now();
+        let period = |currency: &Currency| BudgetReportCmd {
+            from: now - Days::new(1),
+            to: now + Days::new(1),
+            currency: currency.clone(),
+        };
+
+        let result = svc.budget_report(1, period(&Currency::default())).await;
+        assert!(matches!(
+            result,
+            Err(BudgetServiceError::ReportValidationError(
+                ReportError::MissingExchangeRate { .. }
+            ))
+        ));
+
+        svc.set_exchange_rate(
+            1,
+            SetExchangeRateCmd {
+                base: eur.clone(),
+                quote: Currency::default(),
+                rate: 1.1,
+                valid_on: NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+            },
+        )
+        .await
+        .expect("must set exchange rate");
+
+        let spent = |report: Vec<CategoryBudgetReport>| -> Vec<(i64, i64)> {
+            report
+                .into_iter()
+                .filter(|r| r.spent > 0)
+                .map(|r| (r.category.id, r.spent))
+                .collect()
+        };
+
+        let result = svc.budget_report(1, period(&Currency::default())).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+        assert_eq!(
+            spent(result.unwrap()),
+            vec![(1, 1100 + 1100), (2, 1100), (3, 1100), (4, 1100)]
+        );
+
+        // The inverse rate converts to the other currency of the pair.
+        let result = svc.budget_report(1, period(&eur)).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+        assert_eq!(
+            spent(result.unwrap()),
+            vec![(1, 1000 + 1000), (2, 1000), (3, 1000), (4, 1000)]
+        );
+
+        // Budgets are kept in the reporting currency and converted as well.
+        let category = svc.get_category(1, 3).await.unwrap();
+        svc.update_category(
+            1,
+            UpdateCategoryCmd {
+                id: 3,
+                name: category.name,
+                budget: Some(2200.into()),
+            },
+        )
+        .await
+        .expect("must update category");
+        let result = svc.budget_report(1, period(&eur)).await.unwrap();
+        let report = result.iter().find(|r| r.category.id == 3).unwrap();
+        assert_eq!(report.category.budget, Some(2000));
+        assert_eq!(report.remaining, Some(1000));
+        assert_eq!(report.percent_used, Some(50.0));
+    }
+
+    #[tokio::test]
+    async fn test_cash_flow_of_account_in_other_currency() {
+        let fixture = include_str!("../repository/fixtures/records.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+        let eur = Currency::from_str("EUR").unwrap();
+
+        let account = svc
+            .create_account(
+                1,
+                CreateAccountCmd {
+                    name: "euro".into(),
+                    account_type: "Cash".into(),
+                    initial_balance: 0.into(),
+                    currency: Some(eur.clone()),
+                },
+            )
+            .await
+            .expect("must create account");
+        svc.create_record(
+            1,
+            CreateRecordCmd {
+                account_id: 1,
+                transaction_type: "Transfer".into(),
+                amount: 1000.into(),
+                category: None,
+                description: None,
+                destination_account_id: Some(account.id),
+                received_amount: Some(900.into()),
+                splits: vec![],
+                tag_ids: vec![],
+                occurred_at: Some(
+                    DateTime::parse_from_rfc3339("2026-03-10T12:00:00Z")
+                        .unwrap()
+                        .into(),
+                ),
+                posted_at: None,
+            },
+        )
+        .await
+        .expect("must create transfer");
+
+        let cmd = |account_id, category_id| CashFlowCmd {
+            from: DateTime::parse_from_rfc3339("2026-03-01T00:00:00Z")
+                .unwrap()
+                .into(),
+            to: DateTime::parse_from_rfc3339("2026-04-01T00:00:00Z")
+                .unwrap()
+                .into(),
+            currency: Currency::default(),
+            interval: CashFlowInterval::Month,
+            account_id,
+            category_id,
+        };
+
+        let result = svc.cash_flow(1, cmd(Some(account.id), None)).await;
+        assert!(matches!(
+            result,
+            Err(BudgetServiceError::ReportValidationError(
+                ReportError::MissingExchangeRate { .. }
+            ))
+        ));
+
+        svc.set_exchange_rate(
+            1,
+            SetExchangeRateCmd {
+                base: eur.clone(),
+                quote: Currency::default(),
+                rate: 1.1,
+                valid_on: NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+            },
+        )
+        .await
+        .expect("must set exchange rate");
+
+        let result = svc.cash_flow(1, cmd(Some(account.id), None)).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+        let buckets = result.unwrap();
+        assert_eq!(buckets.len(), 1);
+        assert_eq!(buckets[0].income, 990);
+        assert_eq!(buckets[0].running_balance, 990);
+
+        let result = svc.cash_flow(1, cmd(None, Some(6))).await;
+        assert!(result.is_err(), "must not use another user's category");
+    }
+
+    #[tokio::test]
+    async fn test_cash_flow_running_balance_starts_at_opening_balance() {
+        let fixture = include_str!("../repository/fixtures/fixture.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        let account = svc
+            .create_account(
+                1,
+                CreateAccountCmd {
+                    name: "savings".into(),
+                    account_type: "Cash".into(),
+                    initial_balance: 10000.into(),
+                    currency: None,
+                },
+            )
+            .await
+            .expect("must create account");
+        // Before, during and after the period.
+        let records = [
+            ("Income", 5000, "2026-02-15T12:00:00Z"),
+            ("Outcome", 3000, "2026-03-10T12:00:00Z"),
+            ("Income", 100000, "2026-05-10T12:00:00Z"),
+        ];
+        for (transaction_type, amount, occurred_at) in records {
+            svc.create_record(
+                1,
+                CreateRecordCmd {
+                    account_id: account.id,
+                    transaction_type: transaction_type.into(),
+                    amount: amount.into(),
+                    category: None,
+                    description: None,
+                    destination_account_id: None,
+                    received_amount: None,
+                    splits: vec![],
+                    tag_ids: vec![],
+                    occurred_at: Some(DateTime::parse_from_rfc3339(occurred_at).unwrap().into()),
+                    posted_at: None,
+                },
+            )
+            .await
+            .expect("must create record");
+        }
+
+        let cmd = |account_id| CashFlowCmd {
+            from: DateTime::parse_from_rfc3339("2026-03-01T00:00:00Z")
+                .unwrap()
+                .into(),
+            to: DateTime::parse_from_rfc3339("2026-04-01T00:00:00Z")
+                .unwrap()
+                .into(),
+            currency: Currency::default(),
+            interval: CashFlowInterval::Month,
+            account_id,
+            category_id: None,
+        };
+
+        let buckets = svc.cash_flow(1, cmd(Some(account.id))).await.unwrap();
+        assert_eq!(buckets.len(), 1);
+        assert_eq!(buckets[0].net, -3000);
+        assert_eq!(buckets[0].running_balance, 10000 + 5000 - 3000);
+
+        // The other account of the user is empty.
+        let buckets = svc.cash_flow(1, cmd(None)).await.unwrap();
+        assert_eq!(buckets[0].running_balance, 12000);
+    }
+
+    #[tokio::test]
+    async fn test_reports_convert_between_decimal_places() {
+        let fixture = include_str!("../repository/fixtures/records.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+        let jpy = Currency::from_str("JPY").unwrap();
+
+        let account = svc
+            .create_account(
+                1,
+                CreateAccountCmd {
+                    name: "yen".into(),
+                    account_type: "Cash".into(),
+                    initial_balance: 0.into(),
+                    currency: Some(jpy.clone()),
+                },
+            )
+            .await
+            .expect("must create account");
+        for (account_id, amount, category) in [(account.id, 1500, 3), (1, 1100, 4)] {
+            svc.create_record(
+                1,
+                CreateRecordCmd {
+                    account_id,
+                    transaction_type: "Outcome".into(),
+                    amount: amount.into(),
+                    category: Some(category),
+                    description: None,
+                    destination_account_id: None,
+                    received_amount: None,
+                    splits: vec![],
+                    tag_ids: vec![],
+                    occurred_at: None,
+                    posted_at: None,
+                },
+            )
+            .await
+            .expect("must create record");
+        }
+        svc.set_exchange_rate(
+            1,
+            SetExchangeRateCmd {
+                base: Currency::default(),
+                quote: jpy.clone(),
+                rate: 150.0,
+                valid_on: NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+            },
+        )
+        .await
+        .expect("must set exchange rate");
+
+        let now = Local::now();
+        let spent = async |currency: &Currency| -> Vec<(i64, i64)> {
+            let cmd = BudgetReportCmd {
+                from: now - Days::new(1),
+                to: now + Days::new(1),
+                currency: currency.clone(),
+            };
+            svc.budget_report(1, cmd)
+                .await
+                .unwrap()
+                .into_iter()
+                .filter(|r| r.category.id == 3 || r.category.id == 4)
+                .map(|r| (r.category.id, r.spent))
+                .collect()
+        };
+
+        // 1500 yen are 10 dollars, 11 dollars are 1650 yen.
+        assert_eq!(
+            spent(&Currency::default()).await,
+            vec![(3, 1000), (4, 1100)]
+        );
+        assert_eq!(spent(&jpy).await, vec![(3, 1500), (4, 1650)]);
+
+        // The budget of 20 dollars is 3000 yen.
+        svc.update_category(
+            1,
+            UpdateCategoryCmd {
+                id: 3,
+                name: "restaurants".into(),
+                budget: Some(2000.into()),
+            },
+        )
+        .await
+        .expect("must update category");
+        let cmd = BudgetReportCmd {
+            from: now - Days::new(1),
+            to: now + Days::new(1),
+            currency: jpy.clone(),
+        };
+        let result = svc.budget_report(1, cmd).await.unwrap();
+        let report = result.iter().find(|r| r.category.id == 3).unwrap();
+        assert_eq!(report.category.budget, Some(3000));
+        assert_eq!(report.remaining, Some(1500));
+
+        let result = svc
+            .cash_flow(
+                1,
+                CashFlowCmd {
+                    from: now - Days::new(1),
+                    to: now + Days::new(1),
+                    currency: Currency::default(),
+                    interval: CashFlowInterval::Year,
+                    account_id: Some(account.id),
+                    category_id: None,
+                },
+            )
+            .await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+        let outcome: i64 = result.unwrap().iter().map(|b| b.outcome).sum();
+        assert_eq!(outcome, 1000);
+    }
+}
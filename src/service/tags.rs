@@ -0,0 +1,276 @@
+use async_trait::async_trait;
+
+use crate::{
+    domain::{
+        Result,
+        models::{AuditAction, Page, Tag, TagError},
+    },
+    service::{
+        audit::audit,
+        budget::{BudgetRepository, BudgetServiceImpl, PageCmd},
+    },
+};
+
+pub struct CreateTagCmd {
+    pub name: String,
+}
+
+pub struct RenameTagCmd {
+    pub id: i64,
+    pub name: String,
+}
+
+pub struct MergeTagsCmd {
+    /// Tag removed by the merge.
+    pub source_id: i64,
+    /// Tag the records of the source tag are moved to.
+    pub target_id: i64,
+}
+
+#[async_trait]
+pub trait BudgetTagsService: Send + Sync + 'static {
+    /// Tags ordered by name.
+    async fn list_tags(&self, user_id: i64, page: PageCmd) -> Result<Page<Tag>>;
+    async fn create_tag(&self, user_id: i64, cmd: CreateTagCmd) -> Result<Tag>;
+    async fn rename_tag(&self, user_id: i64, cmd: RenameTagCmd) -> Result<Tag>;
+    async fn delete_tag(&self, user_id: i64, id: i64) -> Result<()>;
+    /// Moves all records of the source tag to the target tag and deletes the
+    /// source tag.
+    async fn merge_tags(&self, user_id: i64, cmd: MergeTagsCmd) -> Result<Tag>;
+}
+
+#[async_trait]
+impl<T: BudgetRepository> BudgetTagsService for BudgetServiceImpl<T> {
+    async fn list_tags(&self, user_id: i64, page: PageCmd) -> Result<Page<Tag>> {
+        Ok(self.repo.list_tags_page(user_id, &page).await?)
+    }
+
+    async fn create_tag(&self, user_id: i64, cmd: CreateTagCmd) -> Result<Tag> {
+        let tag = Tag::new(cmd.name)?;
+
+        let tx = self.repo.begin().await?;
+        ensure_unique_name(&tx, user_id, &tag).await?;
+        let id = tx.create_tag(user_id, tag).await?;
+        let tag = tx.get_tag_by_id(user_id, id).await?;
+        audit(&tx, user_id, AuditAction::Create, None, Some(&tag)).await?;
+        tx.commit().await?;
+
+        Ok(tag)
+    }
+
+    async fn rename_tag(&self, user_id: i64, cmd: RenameTagCmd) -> Result<Tag> {
+        let tx = self.repo.begin().await?;
+        let before = tx.get_tag_by_id(user_id, cmd.id).await?;
+        let mut tag = before.clone();
+        tag.set_name(cmd.name)?;
+
+        ensure_unique_name(&tx, user_id, &tag).await?;
+        tx.update_tag(user_id, tag).await?;
+        let tag = tx.get_tag_by_id(user_id, cmd.id).await?;
+        audit(&tx, user_id, AuditAction::Update, Some(&before), Some(&tag)).await?;
+        tx.commit().await?;
+
+        Ok(tag)
+    }
+
+    async fn delete_tag(&self, user_id: i64, id: i64) -> Result<()> {
+        let tx = self.repo.begin().await?;
+        let tag = tx.get_tag_by_id(user_id, id).await?;
+        tx.delete_tag(user_id, id).await?;
+        audit(&tx, user_id, AuditAction::Delete, Some(&tag), None).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn merge_tags(&self, user_id: i64, cmd: MergeTagsCmd) -> Result<Tag> {
+        if cmd.source_id == cmd.target_id {
+            return Err(TagError::MergeIntoItself.into());
+        }
+
+        let tx = self.repo.begin().await?;
+        let source = tx.get_tag_by_id(user_id, cmd.source_id).await?;
+        let target = tx.get_tag_by_id(user_id, cmd.target_id).await?;
+
+        tx.retag_records(user_id, cmd.source_id, cmd.target_id)
+            .await?;
+        tx.delete_tag(user_id, cmd.source_id).await?;
+        audit(&tx, user_id, AuditAction::Delete, Some(&source), None).await?;
+        tx.commit().await?;
+
+        Ok(target)
+    }
+}
+
+async fn ensure_unique_name<T: BudgetRepository>(repo: &T, user_id: i64, tag: &Tag) -> Result<()> {
+    match repo.find_tag_by_name(user_id, &tag.name).await? {
+        Some(existing) if existing.id != tag.id => {
+            Err(TagError::DuplicateTagName(tag.name.clone()).into())
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        domain::{
+            errors::BudgetServiceError,
+            models::{Cursor, CursorKey, PageError},
+        },
+        repository::test::test_db,
+        service::{
+            budget::RecordRepository,
+            records::{BudgetRecordService, ListRecordsCmd},
+        },
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_rename_tag() {
+        let fixture = include_str!("../repository/fixtures/records.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        let result = svc
+            .rename_tag(
+                1,
+                RenameTagCmd {
+                    id: 1,
+                    name: " summer vacation ".into(),
+                },
+            )
+            .await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+        assert_eq!(result.unwrap().name, "summer vacation");
+
+        let result = svc
+            .rename_tag(
+                1,
+                RenameTagCmd {
+                    id: 1,
+                    name: "business".into(),
+                },
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(BudgetServiceError::TagValidationError(
+                TagError::DuplicateTagName(_)
+            ))
+        ));
+
+        let result = svc
+            .rename_tag(
+                1,
+                RenameTagCmd {
+                    id: 3,
+                    name: "stolen".into(),
+                },
+            )
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_list_tags_in_pages() {
+        let fixture = include_str!("../repository/fixtures/records.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        let all = svc.list_tags(1, PageCmd::default()).await.unwrap();
+        let mut paged = Vec::new();
+        let mut after = None;
+        loop {
+            let page = PageCmd {
+                limit: Some(1),
+                after,
+                include_total: true,
+            };
+            let page = svc.list_tags(1, page).await.unwrap();
+            // One more tag was created after each page.
+            let total = all.items.len() + paged.len();
+            assert_eq!(page.total_count, Some(total as u64));
+            paged.extend(page.items.iter().map(|t| t.id));
+            after = page.next_cursor;
+
+            // Tags created while paging before the cursor are not returned.
+            svc.create_tag(
+                1,
+                CreateTagCmd {
+                    name: format!("!{}", paged.len()),
+                },
+            )
+            .await
+            .unwrap();
+            if after.is_none() {
+                break;
+            }
+        }
+        assert_eq!(paged, all.items.iter().map(|t| t.id).collect::<Vec<_>>());
+
+        let page = PageCmd {
+            after: Some(Cursor::new(CursorKey::Id, 1)),
+            ..Default::default()
+        };
+        assert!(matches!(
+            svc.list_tags(1, page).await,
+            Err(BudgetServiceError::PageValidationError(
+                PageError::InvalidCursor
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_merge_tags() {
+        let fixture = include_str!("../repository/fixtures/records.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        let result = svc
+            .merge_tags(
+                1,
+                MergeTagsCmd {
+                    source_id: 1,
+                    target_id: 2,
+                },
+            )
+            .await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        let tags = svc.list_tags(1, PageCmd::default()).await.unwrap();
+        assert_eq!(tags.items.iter().map(|t| t.id).collect::<Vec<_>>(), vec![2]);
+
+        let records = svc
+            .list_records(
+                1,
+                ListRecordsCmd {
+                    tag_ids: vec![2],
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            records.items.iter().map(|r| r.id).collect::<Vec<_>>(),
+            vec![3, 2, 1]
+        );
+
+        let record = svc.repo.get_record_by_id(1, 3).await.unwrap();
+        assert_eq!(record.tags.len(), 1);
+
+        let result = svc
+            .merge_tags(
+                1,
+                MergeTagsCmd {
+                    source_id: 2,
+                    target_id: 2,
+                },
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(BudgetServiceError::TagValidationError(
+                TagError::MergeIntoItself
+            ))
+        ));
+    }
+}
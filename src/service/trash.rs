@@ -0,0 +1,293 @@
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use sqlx::types::chrono::{DateTime, Local};
+
+use crate::{
+    domain::{
+        Result,
+        errors::BudgetServiceError,
+        models::{Account, AuditAction, Category, Deleted, Page, PurgeReport, Record},
+    },
+    service::{
+        audit::audit,
+        budget::{BudgetRepository, BudgetServiceImpl, PageCmd},
+        records::apply_balance_changes,
+    },
+};
+
+/// Deleted items to remove permanently, every deleted item when both are
+/// absent.
+#[derive(Default)]
+pub struct PurgeCmd {
+    /// Only items of this user.
+    pub user_id: Option<i64>,
+    /// Only items deleted before this instant.
+    pub before: Option<DateTime<Local>>,
+}
+
+#[async_trait]
+pub trait BudgetTrashService: Send + Sync + 'static {
+    /// Deleted records of a user, most recently deleted first, like the
+    /// other lists of the trash.
+    async fn list_deleted_records(
+        &self,
+        user_id: i64,
+        page: PageCmd,
+    ) -> Result<Page<Deleted<Record>>>;
+    async fn list_deleted_accounts(
+        &self,
+        user_id: i64,
+        page: PageCmd,
+    ) -> Result<Page<Deleted<Account>>>;
+    async fn list_deleted_categories(
+        &self,
+        user_id: i64,
+        page: PageCmd,
+    ) -> Result<Page<Deleted<Category>>>;
+    /// Restores a record and applies it to the balances of its accounts
+    /// again, the accounts and categories it uses must not be deleted.
+    async fn restore_record(&self, user_id: i64, id: i64) -> Result<Record>;
+    async fn restore_account(&self, user_id: i64, id: i64) -> Result<Account>;
+    /// Restores a category, its parent must not be deleted.
+    async fn restore_category(&self, user_id: i64, id: i64) -> Result<Category>;
+    /// Permanently removes items of all users deleted before `before`, run
+    /// by the scheduler once the retention period is over.
+    async fn purge_trash(&self, before: DateTime<Local>) -> Result<PurgeReport>;
+    /// Permanently removes every item in the trash of a user.
+    async fn empty_trash(&self, user_id: i64) -> Result<PurgeReport>;
+}
+
+#[async_trait]
+impl<T: BudgetRepository> BudgetTrashService for BudgetServiceImpl<T> {
+    async fn list_deleted_records(
+        &self,
+        user_id: i64,
+        page: PageCmd,
+    ) -> Result<Page<Deleted<Record>>> {
+        Ok(self.repo.list_deleted_records(user_id, &page).await?)
+    }
+
+    async fn list_deleted_accounts(
+        &self,
+        user_id: i64,
+        page: PageCmd,
+    ) -> Result<Page<Deleted<Account>>> {
+        Ok(self.repo.list_deleted_accounts(user_id, &page).await?)
+    }
+
+    async fn list_deleted_categories(
+        &self,
+        user_id: i64,
+        page: PageCmd,
+    ) -> Result<Page<Deleted<Category>>> {
+        Ok(self.repo.list_deleted_categories(user_id, &page).await?)
+    }
+
+    async fn restore_record(&self, user_id: i64, id: i64) -> Result<Record> {
+        let tx = self.repo.begin().await?;
+        let record = tx.get_deleted_record_by_id(user_id, id).await?;
+
+        let accounts: HashSet<i64> = tx
+            .list_accounts(user_id)
+            .await?
+            .into_iter()
+            .map(|a| a.id)
+            .collect();
+        let account_ids = std::iter::once(record.account_id)
+            .chain(record.transfer.as_ref().map(|t| t.account_id));
+        for account_id in account_ids {
+            if !accounts.contains(&account_id) {
+                return Err(BudgetServiceError::ConflictError(format!(
+                    "record {id} uses deleted account {account_id}"
+                )));
+            }
+        }
+
+        let categories: HashSet<i64> = tx
+            .list_categories(user_id)
+            .await?
+            .into_iter()
+            .map(|c| c.id)
+            .collect();
+        let category_ids = record
+            .category
+            .iter()
+            .chain(record.splits.iter().map(|s| &s.category))
+            .map(|c| c.id);
+        for category_id in category_ids {
+            if !categories.contains(&category_id) {
+                return Err(BudgetServiceError::ConflictError(format!(
+                    "record {id} uses deleted category {category_id}"
+                )));
+            }
+        }
+
+        tx.restore_record(user_id, id).await?;
+        apply_balance_changes(&tx, user_id, record.balance_changes(), 1).await?;
+        let record = tx.get_record_by_id(user_id, id).await?;
+        audit(&tx, user_id, AuditAction::Restore, None, Some(&record)).await?;
+        tx.commit().await?;
+
+        Ok(record)
+    }
+
+    async fn restore_account(&self, user_id: i64, id: i64) -> Result<Account> {
+        let tx = self.repo.begin().await?;
+        tx.get_deleted_account_by_id(user_id, id).await?;
+        tx.restore_account(user_id, id).await?;
+        let account = tx.get_account_by_id(user_id, id).await?;
+        audit(&tx, user_id, AuditAction::Restore, None, Some(&account)).await?;
+        tx.commit().await?;
+
+        Ok(account)
+    }
+
+    async fn restore_category(&self, user_id: i64, id: i64) -> Result<Category> {
+        let tx = self.repo.begin().await?;
+        let category = tx.get_deleted_category_by_id(user_id, id).await?;
+
+        if let Some(parent_id) = category.parent_id {
+            let active = tx
+                .list_categories(user_id)
+                .await?
+                .iter()
+                .any(|c| c.id == parent_id);
+            if !active {
+                return Err(BudgetServiceError::ConflictError(format!(
+                    "category {id} has deleted parent {parent_id}"
+                )));
+            }
+        }
+
+        tx.restore_category(user_id, id).await?;
+        let category = tx.get_category_by_id(user_id, id).await?;
+        audit(&tx, user_id, AuditAction::Restore, None, Some(&category)).await?;
+        tx.commit().await?;
+
+        Ok(category)
+    }
+
+    async fn purge_trash(&self, before: DateTime<Local>) -> Result<PurgeReport> {
+        let tx = self.repo.begin().await?;
+        let cmd = PurgeCmd {
+            before: Some(before),
+            ..Default::default()
+        };
+        let report = tx.purge_deleted(&cmd).await?;
+        tx.commit().await?;
+
+        Ok(report)
+    }
+
+    async fn empty_trash(&self, user_id: i64) -> Result<PurgeReport> {
+        let tx = self.repo.begin().await?;
+        let cmd = PurgeCmd {
+            user_id: Some(user_id),
+            ..Default::default()
+        };
+        let report = tx.purge_deleted(&cmd).await?;
+        tx.commit().await?;
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        domain::models::DeletePolicy,
+        repository::test::test_db,
+        service::{
+            accounts::{BudgetAccountsService, DeleteAccountCmd},
+            budget::AccountRepository,
+            records::BudgetRecordService,
+        },
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_restore_record_balances() {
+        let fixture = include_str!("../repository/fixtures/records.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        // Transfer of 1000 from account 2 to account 1.
+        svc.delete_record(1, 5).await.expect("must delete record");
+        let wallet = svc.repo.get_account_by_id(1, 1).await.unwrap();
+        let card = svc.repo.get_account_by_id(1, 2).await.unwrap();
+        assert_eq!(wallet.balance, -1000);
+        assert_eq!(card.balance, 1000);
+
+        let deleted = svc.list_deleted_records(1, PageCmd::default()).await;
+        assert_eq!(deleted.unwrap().items.len(), 1);
+        let deleted = svc.list_deleted_records(2, PageCmd::default()).await;
+        assert!(deleted.unwrap().items.is_empty());
+
+        let result = svc.restore_record(1, 5).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+        let wallet = svc.repo.get_account_by_id(1, 1).await.unwrap();
+        let card = svc.repo.get_account_by_id(1, 2).await.unwrap();
+        assert_eq!(wallet.balance, 0);
+        assert_eq!(card.balance, 0);
+
+        assert!(svc.restore_record(1, 5).await.is_err());
+        let deleted = svc.list_deleted_records(1, PageCmd::default()).await;
+        assert!(deleted.unwrap().items.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_restore_record_of_deleted_account() {
+        let fixture = include_str!("../repository/fixtures/records.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+
+        svc.delete_account(
+            1,
+            DeleteAccountCmd {
+                id: 2,
+                policy: DeletePolicy::Cascade,
+            },
+        )
+        .await
+        .expect("must delete account");
+        let wallet = svc.repo.get_account_by_id(1, 1).await.unwrap();
+        assert_eq!(wallet.balance, -1000);
+
+        let result = svc.restore_record(1, 5).await;
+        assert!(matches!(result, Err(BudgetServiceError::ConflictError(_))));
+
+        let result = svc.restore_account(1, 2).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+        assert_eq!(result.unwrap().balance, 3500);
+
+        svc.restore_record(1, 5).await.expect("must restore record");
+        svc.restore_record(1, 3).await.expect("must restore record");
+        let wallet = svc.repo.get_account_by_id(1, 1).await.unwrap();
+        let card = svc.repo.get_account_by_id(1, 2).await.unwrap();
+        assert_eq!(wallet.balance, 0);
+        assert_eq!(card.balance, 0);
+        assert_eq!(
+            svc.list_records(1, Default::default())
+                .await
+                .unwrap()
+                .items
+                .len(),
+            6
+        );
+    }
+
+    #[tokio::test]
+    async fn test_empty_trash_of_user() {
+        let fixture = include_str!("../repository/fixtures/records.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+        svc.delete_record(1, 3).await.unwrap();
+        svc.delete_record(2, 7).await.unwrap();
+
+        let report = svc.empty_trash(1).await.unwrap();
+        assert_eq!(report.records, 1);
+        let deleted = svc.list_deleted_records(1, PageCmd::default()).await;
+        assert!(deleted.unwrap().items.is_empty());
+        let deleted = svc.list_deleted_records(2, PageCmd::default()).await;
+        assert_eq!(deleted.unwrap().items.len(), 1);
+    }
+}
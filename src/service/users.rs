@@ -0,0 +1,151 @@
+use async_trait::async_trait;
+
+use crate::{
+    domain::{
+        Result,
+        models::{AuditAction, Currency, User, UserError},
+    },
+    service::{
+        audit::audit,
+        auth::hash_password,
+        budget::{BudgetRepository, BudgetServiceImpl},
+    },
+};
+
+pub struct CreateUserCmd {
+    pub username: String,
+    pub password: String,
+}
+
+pub struct UpdateUserCmd {
+    pub id: i64,
+    pub username: String,
+    pub reporting_currency: Option<Currency>,
+}
+
+#[async_trait]
+pub trait BudgetUsersService: Send + Sync + 'static {
+    async fn create_user(&self, cmd: CreateUserCmd) -> Result<User>;
+    async fn get_user(&self, id: i64) -> Result<User>;
+    async fn update_user(&self, cmd: UpdateUserCmd) -> Result<User>;
+}
+
+#[async_trait]
+impl<T: BudgetRepository> BudgetUsersService for BudgetServiceImpl<T> {
+    async fn create_user(&self, cmd: CreateUserCmd) -> Result<User> {
+        let user = User::new(cmd.username)?;
+        let password_hash = hash_password(cmd.password).await?;
+
+        let tx = self.repo.begin().await?;
+        let id = tx.create_user(user).await?;
+        tx.set_password_hash(id, password_hash).await?;
+        let user = tx.get_user_by_id(id).await?;
+        audit(&tx, id, AuditAction::Create, None, Some(&user)).await?;
+        tx.commit().await?;
+
+        Ok(user)
+    }
+
+    async fn get_user(&self, id: i64) -> Result<User> {
+        Ok(self.repo.get_user_by_id(id).await?)
+    }
+
+    async fn update_user(&self, cmd: UpdateUserCmd) -> Result<User> {
+        let tx = self.repo.begin().await?;
+        let before = tx.get_user_by_id(cmd.id).await?;
+        let mut user = before.clone();
+        user.set_username(cmd.username)?;
+        if let Some(currency) = cmd.reporting_currency {
+            // Budgets are stored in the reporting currency without one.
+            if currency != user.reporting_currency {
+                let categories = tx.list_categories(user.id).await?;
+                if categories.iter().any(|c| c.budget.is_some()) {
+                    return Err(UserError::BudgetsInReportingCurrency.into());
+                }
+            }
+            user.reporting_currency = currency;
+        }
+        tx.update_user(user).await?;
+        let user = tx.get_user_by_id(cmd.id).await?;
+        audit(
+            &tx,
+            user.id,
+            AuditAction::Update,
+            Some(&before),
+            Some(&user),
+        )
+        .await?;
+        tx.commit().await?;
+
+        Ok(user)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use crate::{
+        domain::errors::BudgetServiceError,
+        domain::models::AuditEntity,
+        repository::test::test_db,
+        service::{
+            audit::BudgetAuditService,
+            budget::PageCmd,
+            categories::{BudgetCategoriesService, UpdateCategoryCmd},
+        },
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reporting_currency_is_kept_with_budgets() {
+        let fixture = include_str!("../repository/fixtures/records.sql");
+        let svc = BudgetServiceImpl::new(test_db(Some(fixture)).await);
+        let user = svc.get_user(1).await.unwrap();
+        let update = |currency: &str| UpdateUserCmd {
+            id: 1,
+            username: user.username.clone(),
+            reporting_currency: Some(Currency::from_str(currency).unwrap()),
+        };
+
+        let result = svc.update_user(update("USD")).await;
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+
+        let category = svc.get_category(1, 1).await.unwrap();
+        svc.update_category(
+            1,
+            UpdateCategoryCmd {
+                id: 1,
+                name: category.name,
+                budget: Some(3000.into()),
+            },
+        )
+        .await
+        .expect("must update category");
+
+        let result = svc.update_user(update("EUR")).await;
+        assert!(matches!(
+            result,
+            Err(BudgetServiceError::UserValidationError(
+                UserError::BudgetsInReportingCurrency
+            ))
+        ));
+        assert_eq!(
+            svc.get_user(1).await.unwrap().reporting_currency,
+            user.reporting_currency
+        );
+
+        // Only the accepted change is audited.
+        let history = svc
+            .entity_history(1, AuditEntity::User, 1, PageCmd::default())
+            .await
+            .unwrap();
+        assert_eq!(history.items.len(), 1);
+        assert_eq!(history.items[0].action, AuditAction::Update);
+        assert_eq!(
+            history.items[0].after.as_ref().unwrap()["reporting_currency"],
+            "USD"
+        );
+    }
+}
@@ -1,28 +1,39 @@
 use std::sync::Arc;
 
 use axum::{
-    Extension, Json,
-    extract::Path,
+    Extension,
     http::StatusCode,
     response::{IntoResponse, Result},
 };
 
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
 use crate::{
-    domain::models,
+    domain::{errors::BudgetServiceError, models},
     service::{
-        accounts::{CreateAccountCmd, UpdateAccountCmd},
+        accounts::{CreateAccountCmd, DeleteAccountCmd, UpdateAccountCmd},
         budget::BudgetService,
     },
+    transport::{
+        amounts::{decimal, format_amount},
+        auth::CurrentUser,
+        errors::ProblemResponses,
+        extract::{Json, Path, Query},
+        params::{DeletePolicyParam, PageInfo, PageParam, parse_currency},
+    },
 };
 
 type State = Extension<Arc<dyn BudgetService>>;
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone, ToSchema)]
 pub struct Account {
     id: i64,
     name: String,
+    account_type: String,
+    currency: String,
+    /// Current balance in the currency of the account.
+    balance: String,
 }
 
 impl From<&models::Account> for Account {
@@ -30,13 +41,18 @@ impl From<&models::Account> for Account {
         Self {
             id: dto.id,
             name: dto.name.clone(),
+            account_type: dto.account_type.to_string(),
+            currency: dto.currency.to_string(),
+            balance: format_amount(dto.balance, &dto.currency),
         }
     }
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ListAccountsResponse {
     data: Vec<Account>,
+    #[serde(flatten)]
+    page: PageInfo,
 }
 
 impl IntoResponse for ListAccountsResponse {
@@ -45,26 +61,72 @@ impl IntoResponse for ListAccountsResponse {
     }
 }
 
-impl From<Vec<models::Account>> for ListAccountsResponse {
-    fn from(value: Vec<models::Account>) -> Self {
-        let data = value.iter().map(Account::from).collect();
-        Self { data }
+impl From<models::Page<models::Account>> for ListAccountsResponse {
+    fn from(value: models::Page<models::Account>) -> Self {
+        Self {
+            data: value.items.iter().map(Account::from).collect(),
+            page: PageInfo::from(&value),
+        }
     }
 }
 
-pub async fn list_accounts(Extension(svc): State) -> Result<ListAccountsResponse> {
-    let result = svc.list_accounts().await?;
+#[utoipa::path(
+    get,
+    path = "/accounts",
+    tag = "accounts",
+    params(PageParam),
+    responses((status = 200, body = ListAccountsResponse), ProblemResponses)
+)]
+pub async fn list_accounts(
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+    Query(page): Query<PageParam>,
+) -> Result<ListAccountsResponse> {
+    let page = page.into_cmd().map_err(BudgetServiceError::from)?;
+    let result = svc.list_accounts(user.id, page).await?;
     Ok(result.into())
 }
 
-#[derive(Deserialize)]
+#[derive(Serialize, ToSchema)]
+pub struct GetAccountResponse {
+    data: Account,
+}
+
+impl IntoResponse for GetAccountResponse {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::OK, Json(self)).into_response()
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/accounts/{id}",
+    tag = "accounts",
+    params(("id" = i64, Path)),
+    responses((status = 200, body = GetAccountResponse), ProblemResponses)
+)]
+pub async fn get_account(
+    Path(id): Path<i64>,
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+) -> Result<GetAccountResponse> {
+    let data = svc.get_account(user.id, id).await?;
+
+    Ok(GetAccountResponse {
+        data: Account::from(&data),
+    })
+}
+
+#[derive(Deserialize, ToSchema)]
 pub struct CreateAccountRequest {
     name: String,
     account_type: String,
-    initial_balance: i64,
+    initial_balance: String,
+    /// ISO 4217 code, defaults to the reporting currency of the user.
+    currency: Option<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct CreateAccountResponse {
     data: Account,
 }
@@ -75,16 +137,29 @@ impl IntoResponse for CreateAccountResponse {
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/accounts",
+    tag = "accounts",
+    request_body = CreateAccountRequest,
+    responses((status = 201, body = CreateAccountResponse), ProblemResponses)
+)]
 pub async fn create_account(
     Extension(svc): State,
+    CurrentUser(user): CurrentUser,
     Json(req): Json<CreateAccountRequest>,
 ) -> Result<CreateAccountResponse> {
+    let currency = parse_currency(req.currency.as_deref()).map_err(BudgetServiceError::from)?;
     let data = svc
-        .create_account(CreateAccountCmd {
-            name: req.name,
-            account_type: req.account_type,
-            initial_balance: req.initial_balance,
-        })
+        .create_account(
+            user.id,
+            CreateAccountCmd {
+                name: req.name,
+                account_type: req.account_type,
+                initial_balance: decimal(req.initial_balance),
+                currency,
+            },
+        )
         .await?;
 
     Ok(CreateAccountResponse {
@@ -92,12 +167,12 @@ pub async fn create_account(
     })
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct UpdateAccountRequest {
     name: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct UpdateAccountResponse {
     data: Account,
 }
@@ -108,13 +183,22 @@ impl IntoResponse for UpdateAccountResponse {
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/accounts/{id}",
+    tag = "accounts",
+    params(("id" = i64, Path)),
+    request_body = UpdateAccountRequest,
+    responses((status = 200, body = UpdateAccountResponse), ProblemResponses)
+)]
 pub async fn update_account(
     Path(id): Path<i64>,
     Extension(svc): State,
+    CurrentUser(user): CurrentUser,
     Json(req): Json<UpdateAccountRequest>,
 ) -> Result<UpdateAccountResponse> {
     let data = svc
-        .update_account(UpdateAccountCmd { id, name: req.name })
+        .update_account(user.id, UpdateAccountCmd { id, name: req.name })
         .await?;
 
     Ok(UpdateAccountResponse {
@@ -122,8 +206,37 @@ pub async fn update_account(
     })
 }
 
-pub async fn delete_account(Path(id): Path<i64>, Extension(svc): State) -> impl IntoResponse {
-    let result = svc.delete_account(id).await;
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DeleteAccountReq {
+    #[serde(default)]
+    #[param(inline)]
+    policy: DeletePolicyParam,
+    /// Account receiving the records with the `reassign` policy.
+    target_id: Option<i64>,
+}
+
+#[utoipa::path(
+    delete,
+    path = "/accounts/{id}",
+    tag = "accounts",
+    params(("id" = i64, Path), DeleteAccountReq),
+    responses((status = 200), ProblemResponses)
+)]
+pub async fn delete_account(
+    Path(id): Path<i64>,
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+    Query(req): Query<DeleteAccountReq>,
+) -> impl IntoResponse {
+    let policy = match req.policy.with_target(req.target_id) {
+        Ok(policy) => policy,
+        Err(e) => return BudgetServiceError::from(e).into_response(),
+    };
+
+    let result = svc
+        .delete_account(user.id, DeleteAccountCmd { id, policy })
+        .await;
     match result {
         Ok(()) => (StatusCode::OK).into_response(),
         Err(e) => e.into_response(),
@@ -0,0 +1,13 @@
+use crate::domain::models::{Amount, Currency, Money};
+
+/// Formats minor units the way every amount is returned, as a decimal string
+/// in major units such as `"12.50"`. Requests send amounts in the same form
+/// and plain JSON numbers are rejected, so `12` cannot be mistaken for cents.
+pub fn format_amount(minor: i64, currency: &Currency) -> String {
+    Money::new(minor, currency.clone()).to_string()
+}
+
+/// Amount of a request, parsed by the service once its currency is known.
+pub fn decimal(amount: String) -> Amount {
+    Amount::Decimal(amount)
+}
@@ -0,0 +1,173 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    http::StatusCode,
+    response::{IntoResponse, Result},
+};
+use serde::{Deserialize, Serialize};
+use sqlx::types::chrono::{DateTime, Local};
+
+use crate::{
+    domain::{
+        errors::BudgetServiceError,
+        models::{self, AuditAction, AuditEntity},
+    },
+    service::{audit::ListAuditEntriesCmd, budget::BudgetService},
+    transport::{
+        auth::CurrentUser,
+        extract::{Json, Path, Query},
+        params::{DateParam, PageInfo, PageParam},
+    },
+};
+
+type State = Extension<Arc<dyn BudgetService>>;
+
+#[derive(Serialize)]
+struct AuditEntry {
+    id: i64,
+    entity_type: String,
+    entity_id: i64,
+    action: String,
+    /// `null` for changes made by the server, such as records created from
+    /// recurring records.
+    actor_id: Option<i64>,
+    before: Option<serde_json::Value>,
+    after: Option<serde_json::Value>,
+    created_at: DateTime<Local>,
+}
+
+impl From<models::AuditEntry> for AuditEntry {
+    fn from(entry: models::AuditEntry) -> Self {
+        Self {
+            id: entry.id,
+            entity_type: entry.entity_type.to_string(),
+            entity_id: entry.entity_id,
+            action: entry.action.to_string(),
+            actor_id: entry.actor_id,
+            before: entry.before,
+            after: entry.after,
+            created_at: entry.created_at,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityParam {
+    Account,
+    Category,
+    Record,
+    RecurringRecord,
+    Tag,
+    User,
+    ExchangeRate,
+    Token,
+}
+
+impl From<EntityParam> for AuditEntity {
+    fn from(entity: EntityParam) -> Self {
+        match entity {
+            EntityParam::Account => Self::Account,
+            EntityParam::Category => Self::Category,
+            EntityParam::Record => Self::Record,
+            EntityParam::RecurringRecord => Self::RecurringRecord,
+            EntityParam::Tag => Self::Tag,
+            EntityParam::User => Self::User,
+            EntityParam::ExchangeRate => Self::ExchangeRate,
+            EntityParam::Token => Self::Token,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum ActionParam {
+    Create,
+    Update,
+    Delete,
+    Restore,
+}
+
+impl From<ActionParam> for AuditAction {
+    fn from(action: ActionParam) -> Self {
+        match action {
+            ActionParam::Create => Self::Create,
+            ActionParam::Update => Self::Update,
+            ActionParam::Delete => Self::Delete,
+            ActionParam::Restore => Self::Restore,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ListAuditEntriesReq {
+    entity_type: Option<EntityParam>,
+    entity_id: Option<i64>,
+    action: Option<ActionParam>,
+    from: Option<DateParam>,
+    to: Option<DateParam>,
+}
+
+impl ListAuditEntriesReq {
+    fn into_cmd(self) -> ListAuditEntriesCmd {
+        ListAuditEntriesCmd {
+            entity_type: self.entity_type.map(Into::into),
+            entity_id: self.entity_id,
+            action: self.action.map(Into::into),
+            from: self.from.map(Into::into),
+            to: self.to.map(Into::into),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ListAuditEntriesResponse {
+    data: Vec<AuditEntry>,
+    #[serde(flatten)]
+    page: PageInfo,
+}
+
+impl IntoResponse for ListAuditEntriesResponse {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::OK, Json(self)).into_response()
+    }
+}
+
+impl From<models::Page<models::AuditEntry>> for ListAuditEntriesResponse {
+    fn from(value: models::Page<models::AuditEntry>) -> Self {
+        Self {
+            page: PageInfo::from(&value),
+            data: value.items.into_iter().map(AuditEntry::from).collect(),
+        }
+    }
+}
+
+/// Activity feed of the current user, newest first.
+pub async fn activity_feed(
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+    Query(req): Query<ListAuditEntriesReq>,
+    Query(page): Query<PageParam>,
+) -> Result<ListAuditEntriesResponse> {
+    let page = page.into_cmd().map_err(BudgetServiceError::from)?;
+    let result = svc.activity_feed(user.id, req.into_cmd(), page).await?;
+
+    Ok(result.into())
+}
+
+/// History of a single entity, oldest first.
+pub async fn entity_history(
+    Path((entity_type, id)): Path<(EntityParam, i64)>,
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+    Query(page): Query<PageParam>,
+) -> Result<ListAuditEntriesResponse> {
+    let page = page.into_cmd().map_err(BudgetServiceError::from)?;
+    let result = svc
+        .entity_history(user.id, entity_type.into(), id, page)
+        .await?;
+
+    Ok(result.into())
+}
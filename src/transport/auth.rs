@@ -0,0 +1,255 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    extract::{FromRequestParts, Request},
+    http::{StatusCode, header::AUTHORIZATION, request::Parts},
+    middleware::Next,
+    response::{IntoResponse, Response, Result},
+};
+use serde::{Deserialize, Serialize};
+use sqlx::types::chrono::{DateTime, Local};
+
+use crate::{
+    domain::{errors::BudgetServiceError, models},
+    service::{
+        auth::{CreateApiTokenCmd, IssuedToken, LoginCmd},
+        budget::BudgetService,
+        users::CreateUserCmd,
+    },
+    transport::{
+        errors::Problem,
+        extract::{Json, Path, Query},
+        params::{PageInfo, PageParam},
+        users::User,
+    },
+};
+
+type State = Extension<Arc<dyn BudgetService>>;
+
+/// Authenticated user, set by the [`require_auth`] middleware.
+#[derive(Clone)]
+pub struct CurrentUser(pub models::User);
+
+impl<S: Send + Sync> FromRequestParts<S> for CurrentUser {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<CurrentUser>()
+            .cloned()
+            .ok_or_else(|| unauthorized("authentication required"))
+    }
+}
+
+fn unauthorized(message: &str) -> Response {
+    Problem::new(StatusCode::UNAUTHORIZED, "Unauthorized", message).into_response()
+}
+
+/// Rejects requests without a valid `Authorization: Bearer <token>` header and
+/// passes the authenticated user and token to handlers.
+pub async fn require_auth(Extension(svc): State, mut req: Request, next: Next) -> Response {
+    let secret = req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|value| value.trim().to_owned());
+
+    let Some(secret) = secret else {
+        return unauthorized("missing bearer token");
+    };
+
+    match svc.authenticate(secret).await {
+        Ok((user, token)) => {
+            req.extensions_mut().insert(CurrentUser(user));
+            req.extensions_mut().insert(token);
+            next.run(req).await
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+#[derive(Serialize)]
+pub struct Token {
+    id: i64,
+    kind: String,
+    name: Option<String>,
+    created_at: DateTime<Local>,
+    expires_at: Option<DateTime<Local>>,
+}
+
+impl From<&models::Token> for Token {
+    fn from(token: &models::Token) -> Self {
+        Self {
+            id: token.id,
+            kind: token.kind.to_string(),
+            name: token.name.clone(),
+            created_at: token.created_at,
+            expires_at: token.expires_at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct IssuedTokenData {
+    token: String,
+    #[serde(flatten)]
+    details: Token,
+}
+
+impl From<IssuedToken> for IssuedTokenData {
+    fn from(issued: IssuedToken) -> Self {
+        Self {
+            token: issued.secret,
+            details: Token::from(&issued.token),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct RegisterRequest {
+    username: String,
+    password: String,
+}
+
+#[derive(Serialize)]
+pub struct RegisterResponse {
+    data: User,
+}
+
+impl IntoResponse for RegisterResponse {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::CREATED, Json(self)).into_response()
+    }
+}
+
+pub async fn register(
+    Extension(svc): State,
+    Json(req): Json<RegisterRequest>,
+) -> Result<RegisterResponse> {
+    let result = svc
+        .create_user(CreateUserCmd {
+            username: req.username,
+            password: req.password,
+        })
+        .await?;
+
+    Ok(RegisterResponse {
+        data: User::from(&result),
+    })
+}
+
+#[derive(Deserialize)]
+pub struct LoginRequest {
+    username: String,
+    password: String,
+}
+
+#[derive(Serialize)]
+pub struct LoginResponse {
+    data: IssuedTokenData,
+}
+
+impl IntoResponse for LoginResponse {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::OK, Json(self)).into_response()
+    }
+}
+
+pub async fn login(Extension(svc): State, Json(req): Json<LoginRequest>) -> Result<LoginResponse> {
+    let result = svc
+        .login(LoginCmd {
+            username: req.username,
+            password: req.password,
+        })
+        .await?;
+
+    Ok(LoginResponse {
+        data: result.into(),
+    })
+}
+
+pub async fn logout(
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+    Extension(token): Extension<models::Token>,
+) -> impl IntoResponse {
+    let result = svc.revoke_token(user.id, token.id).await;
+
+    match result {
+        Ok(()) => (StatusCode::OK).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+#[derive(Serialize)]
+pub struct ListTokensResponse {
+    data: Vec<Token>,
+    #[serde(flatten)]
+    page: PageInfo,
+}
+
+impl IntoResponse for ListTokensResponse {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::OK, Json(self)).into_response()
+    }
+}
+
+pub async fn list_tokens(
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+    Query(page): Query<PageParam>,
+) -> Result<ListTokensResponse> {
+    let page = page.into_cmd().map_err(BudgetServiceError::from)?;
+    let result = svc.list_api_tokens(user.id, page).await?;
+
+    Ok(ListTokensResponse {
+        data: result.items.iter().map(Token::from).collect(),
+        page: PageInfo::from(&result),
+    })
+}
+
+#[derive(Deserialize)]
+pub struct CreateTokenRequest {
+    name: String,
+}
+
+#[derive(Serialize)]
+pub struct CreateTokenResponse {
+    data: IssuedTokenData,
+}
+
+impl IntoResponse for CreateTokenResponse {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::CREATED, Json(self)).into_response()
+    }
+}
+
+pub async fn create_token(
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+    Json(req): Json<CreateTokenRequest>,
+) -> Result<CreateTokenResponse> {
+    let result = svc
+        .create_api_token(user.id, CreateApiTokenCmd { name: req.name })
+        .await?;
+
+    Ok(CreateTokenResponse {
+        data: result.into(),
+    })
+}
+
+pub async fn delete_token(
+    Path(id): Path<i64>,
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+) -> impl IntoResponse {
+    let result = svc.revoke_token(user.id, id).await;
+
+    match result {
+        Ok(()) => (StatusCode::OK).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
@@ -1,45 +1,79 @@
 use std::sync::Arc;
 
 use axum::{
-    Extension, Json,
-    extract::Path,
+    Extension,
     http::StatusCode,
     response::{IntoResponse, Result},
 };
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
 use crate::{
-    domain::models,
+    domain::{
+        errors::BudgetServiceError,
+        models::{self, Currency},
+    },
     service::{
         budget::BudgetService,
-        categories::{CreateCategoryCmd, UpdateCategoryCmd},
+        categories::{CreateCategoryCmd, DeleteCategoryCmd, MoveCategoryCmd, UpdateCategoryCmd},
+    },
+    transport::{
+        amounts::{decimal, format_amount},
+        auth::CurrentUser,
+        errors::ProblemResponses,
+        extract::{Json, Path, Query},
+        params::{DeletePolicyParam, PageInfo, PageParam},
     },
 };
 
 type State = Extension<Arc<dyn BudgetService>>;
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone, ToSchema)]
 pub struct Category {
     id: i64,
     name: String,
-    budget: Option<i64>,
+    /// In the reporting currency of the user.
+    budget: Option<String>,
     parent_id: Option<i64>,
 }
 
-impl From<&models::Category> for Category {
-    fn from(dto: &models::Category) -> Self {
+impl Category {
+    pub fn new(dto: &models::Category, currency: &Currency) -> Self {
         Self {
             id: dto.id,
             name: dto.name.clone(),
-            budget: dto.budget,
+            budget: dto.budget.map(|budget| format_amount(budget, currency)),
             parent_id: dto.parent_id,
         }
     }
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
+pub struct CategoryNode {
+    #[serde(flatten)]
+    category: Category,
+    #[schema(no_recursion)]
+    children: Vec<CategoryNode>,
+}
+
+impl CategoryNode {
+    fn new(tree: models::CategoryTree, currency: &Currency) -> Self {
+        Self {
+            category: Category::new(&tree.category, currency),
+            children: tree
+                .children
+                .into_iter()
+                .map(|child| CategoryNode::new(child, currency))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
 pub struct ListCategoryResponse {
     data: Vec<Category>,
+    #[serde(flatten)]
+    page: PageInfo,
 }
 
 impl IntoResponse for ListCategoryResponse {
@@ -48,21 +82,70 @@ impl IntoResponse for ListCategoryResponse {
     }
 }
 
-pub async fn list_categories(Extension(svc): State) -> Result<ListCategoryResponse> {
-    let result = svc.list_categories().await?;
-    let result = result.iter().map(Category::from).collect();
+#[utoipa::path(
+    get,
+    path = "/categories",
+    tag = "categories",
+    params(PageParam),
+    responses((status = 200, body = ListCategoryResponse), ProblemResponses)
+)]
+pub async fn list_categories(
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+    Query(page): Query<PageParam>,
+) -> Result<ListCategoryResponse> {
+    let page = page.into_cmd().map_err(BudgetServiceError::from)?;
+    let result = svc.list_categories(user.id, page).await?;
+    let data = result
+        .items
+        .iter()
+        .map(|category| Category::new(category, &user.reporting_currency))
+        .collect();
+
+    Ok(ListCategoryResponse {
+        data,
+        page: PageInfo::from(&result),
+    })
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct GetCategoryResponse {
+    data: Category,
+}
+
+impl IntoResponse for GetCategoryResponse {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::OK, Json(self)).into_response()
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/categories/{id}",
+    tag = "categories",
+    params(("id" = i64, Path)),
+    responses((status = 200, body = GetCategoryResponse), ProblemResponses)
+)]
+pub async fn get_category(
+    Path(id): Path<i64>,
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+) -> Result<GetCategoryResponse> {
+    let result = svc.get_category(user.id, id).await?;
 
-    Ok(ListCategoryResponse { data: result })
+    Ok(GetCategoryResponse {
+        data: Category::new(&result, &user.reporting_currency),
+    })
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct CreateCategoryRequest {
     name: String,
-    budget: Option<i64>,
+    budget: Option<String>,
     parent_id: Option<i64>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct CreateCategoryResponse {
     data: Category,
 }
@@ -73,30 +156,41 @@ impl IntoResponse for CreateCategoryResponse {
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/categories",
+    tag = "categories",
+    request_body = CreateCategoryRequest,
+    responses((status = 201, body = CreateCategoryResponse), ProblemResponses)
+)]
 pub async fn create_category(
     Extension(svc): State,
+    CurrentUser(user): CurrentUser,
     Json(req): Json<CreateCategoryRequest>,
 ) -> Result<CreateCategoryResponse> {
     let result = svc
-        .create_category(CreateCategoryCmd {
-            name: req.name,
-            budget: req.budget,
-            parent_id: req.parent_id,
-        })
+        .create_category(
+            user.id,
+            CreateCategoryCmd {
+                name: req.name,
+                budget: req.budget.map(decimal),
+                parent_id: req.parent_id,
+            },
+        )
         .await?;
 
     Ok(CreateCategoryResponse {
-        data: Category::from(&result),
+        data: Category::new(&result, &user.reporting_currency),
     })
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct UpdateCategoryRequest {
     name: String,
-    budget: Option<i64>,
+    budget: Option<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct UpdateCategoryResponse {
     data: Category,
 }
@@ -107,28 +201,186 @@ impl IntoResponse for UpdateCategoryResponse {
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/categories/{id}",
+    tag = "categories",
+    params(("id" = i64, Path)),
+    request_body = UpdateCategoryRequest,
+    responses((status = 200, body = UpdateCategoryResponse), ProblemResponses)
+)]
 pub async fn update_category(
     Path(id): Path<i64>,
     Extension(svc): State,
+    CurrentUser(user): CurrentUser,
     Json(req): Json<UpdateCategoryRequest>,
 ) -> Result<UpdateCategoryResponse> {
     let result = svc
-        .update_category(UpdateCategoryCmd {
-            id,
-            name: req.name,
-            budget: req.budget,
-        })
+        .update_category(
+            user.id,
+            UpdateCategoryCmd {
+                id,
+                name: req.name,
+                budget: req.budget.map(decimal),
+            },
+        )
         .await?;
 
     Ok(UpdateCategoryResponse {
-        data: Category::from(&result),
+        data: Category::new(&result, &user.reporting_currency),
     })
 }
 
-pub async fn delete_category(Path(id): Path<i64>, Extension(svc): State) -> impl IntoResponse {
-    let result = svc.delete_category(id).await;
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DeleteCategoryReq {
+    #[serde(default)]
+    #[param(inline)]
+    policy: DeletePolicyParam,
+    /// Category receiving the records with the `reassign` policy.
+    target_id: Option<i64>,
+    #[serde(default)]
+    promote_children: bool,
+}
+
+#[utoipa::path(
+    delete,
+    path = "/categories/{id}",
+    tag = "categories",
+    params(("id" = i64, Path), DeleteCategoryReq),
+    responses((status = 200), ProblemResponses)
+)]
+pub async fn delete_category(
+    Path(id): Path<i64>,
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+    Query(req): Query<DeleteCategoryReq>,
+) -> impl IntoResponse {
+    let policy = match req.policy.with_target(req.target_id) {
+        Ok(policy) => policy,
+        Err(e) => return BudgetServiceError::from(e).into_response(),
+    };
+
+    let result = svc
+        .delete_category(
+            user.id,
+            DeleteCategoryCmd {
+                id,
+                policy,
+                promote_children: req.promote_children,
+            },
+        )
+        .await;
     match result {
         Ok(()) => (StatusCode::OK).into_response(),
         Err(e) => e.into_response(),
     }
 }
+
+#[derive(Serialize, ToSchema)]
+pub struct CategoryTreeResponse {
+    data: Vec<CategoryNode>,
+}
+
+impl IntoResponse for CategoryTreeResponse {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::OK, Json(self)).into_response()
+    }
+}
+
+/// Returns all categories nested under their parents.
+#[utoipa::path(
+    get,
+    path = "/categories/tree",
+    tag = "categories",
+    responses((status = 200, body = CategoryTreeResponse), ProblemResponses)
+)]
+pub async fn category_tree(
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+) -> Result<CategoryTreeResponse> {
+    let result = svc.category_tree(user.id).await?;
+
+    Ok(CategoryTreeResponse {
+        data: result
+            .into_iter()
+            .map(|tree| CategoryNode::new(tree, &user.reporting_currency))
+            .collect(),
+    })
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CategorySubtreeResponse {
+    data: CategoryNode,
+}
+
+impl IntoResponse for CategorySubtreeResponse {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::OK, Json(self)).into_response()
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/categories/{id}/tree",
+    tag = "categories",
+    params(("id" = i64, Path)),
+    responses((status = 200, body = CategorySubtreeResponse), ProblemResponses)
+)]
+pub async fn category_subtree(
+    Path(id): Path<i64>,
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+) -> Result<CategorySubtreeResponse> {
+    let result = svc.category_subtree(user.id, id).await?;
+
+    Ok(CategorySubtreeResponse {
+        data: CategoryNode::new(result, &user.reporting_currency),
+    })
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct MoveCategoryRequest {
+    /// New parent, `null` makes the category a root.
+    parent_id: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct MoveCategoryResponse {
+    data: Category,
+}
+
+impl IntoResponse for MoveCategoryResponse {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::OK, Json(self)).into_response()
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/categories/{id}/parent",
+    tag = "categories",
+    params(("id" = i64, Path)),
+    request_body = MoveCategoryRequest,
+    responses((status = 200, body = MoveCategoryResponse), ProblemResponses)
+)]
+pub async fn move_category(
+    Path(id): Path<i64>,
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+    Json(req): Json<MoveCategoryRequest>,
+) -> Result<MoveCategoryResponse> {
+    let result = svc
+        .move_category(
+            user.id,
+            MoveCategoryCmd {
+                id,
+                parent_id: req.parent_id,
+            },
+        )
+        .await?;
+
+    Ok(MoveCategoryResponse {
+        data: Category::new(&result, &user.reporting_currency),
+    })
+}
@@ -0,0 +1,175 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    http::StatusCode,
+    response::{IntoResponse, Result},
+};
+use serde::{Deserialize, Serialize};
+use sqlx::types::chrono::NaiveDate;
+
+use crate::{
+    domain::{
+        errors::BudgetServiceError,
+        models::{self, Currency},
+    },
+    service::{
+        budget::BudgetService,
+        currencies::{ListExchangeRatesCmd, SetExchangeRateCmd},
+    },
+    transport::{
+        auth::CurrentUser,
+        extract::{Json, Path, Query},
+        params::{PageInfo, PageParam, parse_currency},
+    },
+};
+
+type State = Extension<Arc<dyn BudgetService>>;
+
+#[derive(Serialize)]
+struct ExchangeRate {
+    id: i64,
+    base: String,
+    quote: String,
+    /// Amount of the quote currency one unit of the base currency buys.
+    rate: f64,
+    valid_on: NaiveDate,
+}
+
+impl From<models::ExchangeRate> for ExchangeRate {
+    fn from(rate: models::ExchangeRate) -> Self {
+        Self {
+            id: rate.id,
+            base: rate.base.to_string(),
+            quote: rate.quote.to_string(),
+            rate: rate.rate,
+            valid_on: rate.valid_on,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ListExchangeRatesReq {
+    base: Option<String>,
+    quote: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ListExchangeRatesResponse {
+    data: Vec<ExchangeRate>,
+    #[serde(flatten)]
+    page: PageInfo,
+}
+
+impl IntoResponse for ListExchangeRatesResponse {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::OK, Json(self)).into_response()
+    }
+}
+
+pub async fn list_exchange_rates(
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+    Query(req): Query<ListExchangeRatesReq>,
+    Query(page): Query<PageParam>,
+) -> Result<ListExchangeRatesResponse> {
+    let cmd = ListExchangeRatesCmd {
+        base: parse_currency(req.base.as_deref()).map_err(BudgetServiceError::from)?,
+        quote: parse_currency(req.quote.as_deref()).map_err(BudgetServiceError::from)?,
+    };
+    let page = page.into_cmd().map_err(BudgetServiceError::from)?;
+    let result = svc.list_exchange_rates(user.id, cmd, page).await?;
+
+    Ok(ListExchangeRatesResponse {
+        page: PageInfo::from(&result),
+        data: result.items.into_iter().map(ExchangeRate::from).collect(),
+    })
+}
+
+#[derive(Deserialize)]
+pub struct SetExchangeRateRequest {
+    base: String,
+    quote: String,
+    rate: f64,
+    valid_on: NaiveDate,
+}
+
+#[derive(Serialize)]
+pub struct SetExchangeRateResponse {
+    data: ExchangeRate,
+}
+
+impl IntoResponse for SetExchangeRateResponse {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::OK, Json(self)).into_response()
+    }
+}
+
+/// Stores the rate of a currency pair on a day, replacing any rate already
+/// stored for that pair and day.
+pub async fn set_exchange_rate(
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+    Json(req): Json<SetExchangeRateRequest>,
+) -> Result<SetExchangeRateResponse> {
+    let base: Currency = req.base.parse().map_err(BudgetServiceError::from)?;
+    let quote: Currency = req.quote.parse().map_err(BudgetServiceError::from)?;
+    let result = svc
+        .set_exchange_rate(
+            user.id,
+            SetExchangeRateCmd {
+                base,
+                quote,
+                rate: req.rate,
+                valid_on: req.valid_on,
+            },
+        )
+        .await?;
+
+    Ok(SetExchangeRateResponse {
+        data: result.into(),
+    })
+}
+
+pub async fn delete_exchange_rate(
+    Path(id): Path<i64>,
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+) -> impl IntoResponse {
+    let result = svc.delete_exchange_rate(user.id, id).await;
+
+    match result {
+        Ok(()) => (StatusCode::OK).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+#[derive(Serialize)]
+struct ImportedRates {
+    imported: usize,
+}
+
+#[derive(Serialize)]
+pub struct ImportExchangeRatesResponse {
+    data: ImportedRates,
+}
+
+impl IntoResponse for ImportExchangeRatesResponse {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::CREATED, Json(self)).into_response()
+    }
+}
+
+/// Imports rates from a CSV body with a `date,base,quote,rate` header, no
+/// rate is stored when any row is invalid.
+pub async fn import_exchange_rates(
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+    body: String,
+) -> Result<ImportExchangeRatesResponse> {
+    let imported = svc.import_exchange_rates(user.id, body).await?;
+
+    Ok(ImportExchangeRatesResponse {
+        data: ImportedRates { imported },
+    })
+}
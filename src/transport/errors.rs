@@ -1,52 +1,275 @@
+use std::error::Error;
+
 use axum::{
-    Json,
-    http::StatusCode,
+    body::{Body, to_bytes},
+    extract::Request,
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    middleware::Next,
     response::{IntoResponse, Response},
 };
 use serde::Serialize;
+use utoipa::{IntoResponses, ToSchema};
 
 use crate::domain::errors::BudgetServiceError;
 
-#[derive(Serialize)]
-pub struct JsonError {
+/// Media type of every error response.
+const PROBLEM_JSON: &str = "application/problem+json";
+
+/// Body of an error response, following the problem details format of
+/// RFC 9457 with the invalid request fields listed in `errors`.
+#[derive(Serialize, Clone, Debug, ToSchema)]
+pub struct Problem {
+    /// URI identifying the kind of problem, `about:blank` for plain HTTP
+    /// errors.
+    #[serde(rename = "type")]
+    problem_type: String,
+    title: String,
+    status: u16,
+    detail: String,
+    /// Path of the request that failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    instance: Option<String>,
+    /// Name of the kind of problem, such as `RecordValidationError`.
     code: String,
-    message: String,
+    errors: Vec<FieldViolation>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq, ToSchema)]
+pub struct FieldViolation {
+    /// Path of the field in the request, such as `splits[0].amount`.
+    pub field: String,
+    pub message: String,
 }
 
-impl JsonError {
-    pub fn response(httpcode: StatusCode, code: String, message: String) -> Response {
-        (httpcode, Json(Self { code, message })).into_response()
+/// Error responses documented for every route.
+#[derive(IntoResponses)]
+#[allow(dead_code)]
+pub enum ProblemResponses {
+    /// Invalid parameters or body, `errors` lists every invalid field.
+    #[response(status = 400, content_type = "application/problem+json")]
+    BadRequest(Problem),
+    /// Missing, invalid or expired bearer token.
+    #[response(status = 401, content_type = "application/problem+json")]
+    Unauthorized(Problem),
+    /// The entity does not exist or belongs to another user.
+    #[response(status = 404, content_type = "application/problem+json")]
+    NotFound(Problem),
+    /// The change conflicts with existing data, such as a duplicate name.
+    #[response(status = 409, content_type = "application/problem+json")]
+    Conflict(Problem),
+    /// The body is well formed but references data that does not exist.
+    #[response(status = 422, content_type = "application/problem+json")]
+    UnprocessableEntity(Problem),
+}
+
+impl Problem {
+    /// Problem of the kind named by `code`, its type URI and title are
+    /// derived from the name.
+    pub fn new(status: StatusCode, code: &str, detail: impl Into<String>) -> Self {
+        Self {
+            problem_type: format!("/problems/{}", words(code).join("-")),
+            title: sentence(&words(code)),
+            status: status.as_u16(),
+            detail: detail.into(),
+            instance: None,
+            code: code.to_owned(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Problem with no more meaning than its HTTP status code.
+    pub fn from_status(status: StatusCode, detail: impl Into<String>) -> Self {
+        let title = status.canonical_reason().unwrap_or("Error");
+        Self {
+            problem_type: "about:blank".into(),
+            title: title.into(),
+            status: status.as_u16(),
+            detail: detail.into(),
+            instance: None,
+            code: title.replace(' ', ""),
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn with_errors(mut self, errors: Vec<FieldViolation>) -> Self {
+        self.errors = errors;
+        self
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn errors(&self) -> &[FieldViolation] {
+        &self.errors
+    }
+
+    fn status(&self) -> StatusCode {
+        StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    fn body(&self) -> Body {
+        Body::from(serde_json::to_vec(self).unwrap_or_default())
     }
 }
 
-impl IntoResponse for BudgetServiceError {
+impl IntoResponse for Problem {
     fn into_response(self) -> Response {
-        match &self {
-            Self::RecordValidationError(e) => JsonError::response(
-                StatusCode::BAD_REQUEST,
-                "RecordValidationError".into(),
-                e.to_string(),
-            ),
-            Self::CategoryValidationError(e) => JsonError::response(
-                StatusCode::BAD_REQUEST,
-                "CategoryValidationError".into(),
-                e.to_string(),
-            ),
-            Self::AccountValidationError(e) => JsonError::response(
-                StatusCode::BAD_REQUEST,
-                "AccountValidationError".into(),
-                e.to_string(),
-            ),
-            Self::EntityNotFoundError(_) => JsonError::response(
-                StatusCode::NOT_FOUND,
-                "EntityNotFoundError".into(),
-                self.to_string(),
-            ),
-
-            Self::DatabaseError(e) => {
-                dbg!(e);
-                (StatusCode::INTERNAL_SERVER_ERROR).into_response()
-            }
+        let mut response = (
+            self.status(),
+            [(header::CONTENT_TYPE, HeaderValue::from_static(PROBLEM_JSON))],
+            self.body(),
+        )
+            .into_response();
+        // Kept so that `problem_details` can add the request path.
+        response.extensions_mut().insert(self);
+        response
+    }
+}
+
+/// Renders every error response as problem details with the request path as
+/// `instance`, including errors axum produces itself such as unknown routes.
+/// JSON bodies other than problems, such as the report of a failed import,
+/// are left as they are.
+pub async fn problem_details(req: Request, next: Next) -> Response {
+    let instance = req.uri().path().to_owned();
+    let response = next.run(req).await;
+    let status = response.status();
+    if !status.is_client_error() && !status.is_server_error() {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let mut problem = match parts.extensions.remove::<Problem>() {
+        Some(problem) => problem,
+        None if is_json(&parts.headers) => return Response::from_parts(parts, body),
+        None => {
+            let text = to_bytes(body, usize::MAX).await.unwrap_or_default();
+            let text = String::from_utf8_lossy(&text);
+            let detail = match text.trim() {
+                "" => status.canonical_reason().unwrap_or_default().to_lowercase(),
+                text => text.to_owned(),
+            };
+            Problem::from_status(status, detail)
+        }
+    };
+    problem.instance = Some(instance);
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts
+        .headers
+        .insert(header::CONTENT_TYPE, HeaderValue::from_static(PROBLEM_JSON));
+    let body = problem.body();
+    parts.extensions.insert(problem);
+
+    Response::from_parts(parts, body)
+}
+
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"))
+}
+
+/// Splits a `CamelCase` name into lowercase words.
+fn words(name: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    for c in name.chars() {
+        match words.last_mut() {
+            Some(word) if !c.is_uppercase() => word.push(c),
+            _ => words.push(c.to_lowercase().collect()),
         }
     }
+    words
+}
+
+fn sentence(words: &[String]) -> String {
+    let sentence = words.join(" ");
+    let mut chars = sentence.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => sentence,
+    }
+}
+
+impl From<BudgetServiceError> for Problem {
+    fn from(e: BudgetServiceError) -> Self {
+        let (status, code) = match &e {
+            BudgetServiceError::RecordValidationError(_) => {
+                (StatusCode::BAD_REQUEST, "RecordValidationError")
+            }
+            BudgetServiceError::CategoryValidationError(_) => {
+                (StatusCode::BAD_REQUEST, "CategoryValidationError")
+            }
+            BudgetServiceError::TagValidationError(_) => {
+                (StatusCode::BAD_REQUEST, "TagValidationError")
+            }
+            BudgetServiceError::AccountValidationError(_) => {
+                (StatusCode::BAD_REQUEST, "AccountValidationError")
+            }
+            BudgetServiceError::ImportValidationError(_) => {
+                (StatusCode::BAD_REQUEST, "ImportValidationError")
+            }
+            BudgetServiceError::RecurrenceValidationError(_) => {
+                (StatusCode::BAD_REQUEST, "RecurrenceValidationError")
+            }
+            BudgetServiceError::MoneyValidationError(_) => {
+                (StatusCode::BAD_REQUEST, "MoneyValidationError")
+            }
+            BudgetServiceError::CurrencyValidationError(_) => {
+                (StatusCode::BAD_REQUEST, "CurrencyValidationError")
+            }
+            BudgetServiceError::ReportValidationError(_) => {
+                (StatusCode::BAD_REQUEST, "ReportValidationError")
+            }
+            BudgetServiceError::DeletePolicyValidationError(_) => {
+                (StatusCode::BAD_REQUEST, "DeletePolicyValidationError")
+            }
+            BudgetServiceError::UserValidationError(_) => {
+                (StatusCode::BAD_REQUEST, "UserValidationError")
+            }
+            BudgetServiceError::PageValidationError(_) => {
+                (StatusCode::BAD_REQUEST, "PageValidationError")
+            }
+            BudgetServiceError::Unauthorized(_) => (StatusCode::UNAUTHORIZED, "Unauthorized"),
+            BudgetServiceError::EntityNotFoundError { .. } => {
+                (StatusCode::NOT_FOUND, "EntityNotFoundError")
+            }
+            BudgetServiceError::ConflictError(_) => (StatusCode::CONFLICT, "ConflictError"),
+            BudgetServiceError::InvalidReferenceError(_) => {
+                (StatusCode::UNPROCESSABLE_ENTITY, "InvalidReferenceError")
+            }
+            BudgetServiceError::DatabaseError(e) => {
+                tracing::error!("database error: {e}");
+                return Self::from_status(StatusCode::INTERNAL_SERVER_ERROR, "internal error");
+            }
+            BudgetServiceError::TransactionError(e) => {
+                tracing::error!("transaction error: {e}");
+                return Self::from_status(StatusCode::INTERNAL_SERVER_ERROR, "internal error");
+            }
+        };
+
+        let errors = e
+            .field_errors()
+            .into_iter()
+            .map(|(field, message)| FieldViolation {
+                field: field.into(),
+                message,
+            })
+            .collect();
+
+        // Validation errors wrap the domain error, whose message is enough.
+        let detail = e
+            .source()
+            .map_or_else(|| e.to_string(), ToString::to_string);
+
+        Self::new(status, code, detail).with_errors(errors)
+    }
+}
+
+impl IntoResponse for BudgetServiceError {
+    fn into_response(self) -> Response {
+        Problem::from(self).into_response()
+    }
 }
@@ -0,0 +1,172 @@
+//! Wrappers of the axum extractors whose rejections are rendered as
+//! [`Problem`]s naming the invalid fields.
+
+use std::error::Error;
+
+use axum::{
+    extract::{
+        FromRequest, FromRequestParts, RawPathParams,
+        path::ErrorKind,
+        rejection::{JsonRejection, PathRejection, QueryRejection},
+    },
+    http::request::Parts,
+    response::{IntoResponse, Response},
+};
+use serde::de::DeserializeOwned;
+
+use crate::transport::errors::{FieldViolation, Problem};
+
+#[derive(FromRequest)]
+#[from_request(via(axum::Json), rejection(Problem))]
+pub struct Json<T>(pub T);
+
+impl<T: serde::Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> Response {
+        axum::Json(self.0).into_response()
+    }
+}
+
+#[derive(FromRequestParts)]
+#[from_request(via(axum::extract::Query), rejection(Problem))]
+pub struct Query<T>(pub T);
+
+pub struct Path<T>(pub T);
+
+impl<T, S> FromRequestParts<S> for Path<T>
+where
+    T: DeserializeOwned + Send,
+    S: Send + Sync,
+{
+    type Rejection = Problem;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        match axum::extract::Path::<T>::from_request_parts(parts, state).await {
+            Ok(axum::extract::Path(value)) => Ok(Self(value)),
+            Err(rejection) => {
+                let params = RawPathParams::from_request_parts(parts, state).await.ok();
+                Err(path_problem(rejection, params))
+            }
+        }
+    }
+}
+
+impl From<JsonRejection> for Problem {
+    fn from(rejection: JsonRejection) -> Self {
+        let errors = match &rejection {
+            JsonRejection::JsonDataError(e) => field_violation::<serde_json::Error>(e),
+            _ => Vec::new(),
+        };
+
+        Problem::new(
+            rejection.status(),
+            "InvalidRequestBody",
+            rejection.body_text(),
+        )
+        .with_errors(errors)
+    }
+}
+
+impl From<QueryRejection> for Problem {
+    fn from(rejection: QueryRejection) -> Self {
+        let errors = match &rejection {
+            QueryRejection::FailedToDeserializeQueryString(e) => {
+                field_violation::<serde_urlencoded::de::Error>(e)
+            }
+            _ => Vec::new(),
+        };
+
+        Problem::new(
+            rejection.status(),
+            "InvalidQueryString",
+            rejection.body_text(),
+        )
+        .with_errors(errors)
+    }
+}
+
+/// Names the invalid parameter, looking it up by value among the `params`
+/// of the route when the rejection does not say which one it is.
+fn path_problem(rejection: PathRejection, params: Option<RawPathParams>) -> Problem {
+    let errors = match &rejection {
+        PathRejection::FailedToDeserializePathParams(e) => {
+            let key = match e.kind() {
+                ErrorKind::ParseErrorAtKey { key, .. }
+                | ErrorKind::InvalidUtf8InPathParam { key }
+                | ErrorKind::DeserializeError { key, .. } => Some(key.clone()),
+                ErrorKind::ParseError { value, .. }
+                | ErrorKind::ParseErrorAtIndex { value, .. } => params
+                    .iter()
+                    .flatten()
+                    .find_map(|(key, param)| (param == value).then(|| key.to_owned())),
+                _ => None,
+            };
+            key.map(|field| FieldViolation {
+                field,
+                message: e.kind().to_string(),
+            })
+            .into_iter()
+            .collect()
+        }
+        _ => Vec::new(),
+    };
+
+    Problem::new(
+        rejection.status(),
+        "InvalidPathParameter",
+        rejection.body_text(),
+    )
+    .with_errors(errors)
+}
+
+/// Finds the field a deserialization error is about in the error's sources,
+/// where axum keeps the error with the path of the field.
+fn field_violation<E: Error + 'static>(error: &(dyn Error + 'static)) -> Vec<FieldViolation> {
+    let mut source = error.source();
+    while let Some(e) = source {
+        if let Some(e) = e.downcast_ref::<serde_path_to_error::Error<E>>() {
+            return vec![FieldViolation {
+                field: e.path().to_string(),
+                message: e.inner().to_string(),
+            }];
+        }
+        source = e.source();
+    }
+
+    Vec::new()
+}
+
+#[cfg(test)]
+mod test {
+    use axum::{body::Body, http::Request};
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Deserialize)]
+    #[allow(dead_code)]
+    struct Split {
+        amount: String,
+    }
+
+    #[derive(Deserialize)]
+    #[allow(dead_code)]
+    struct SplitRecord {
+        splits: Vec<Split>,
+    }
+
+    #[tokio::test]
+    async fn test_json_rejection_names_field() {
+        let req = Request::builder()
+            .method("POST")
+            .header("content-type", "application/json")
+            .body(Body::from(r#"{"splits":[{"amount":1}]}"#))
+            .unwrap();
+
+        let Err(problem) = Json::<SplitRecord>::from_request(req, &()).await else {
+            panic!("body must be rejected");
+        };
+        assert_eq!(problem.code(), "InvalidRequestBody");
+        assert_eq!(problem.errors().len(), 1);
+        assert_eq!(problem.errors()[0].field, "splits[0].amount");
+    }
+}
@@ -0,0 +1,299 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    extract::Multipart,
+    http::StatusCode,
+    response::{IntoResponse, Result},
+};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{errors::BudgetServiceError, models, models::ImportError},
+    service::{
+        budget::BudgetService,
+        imports::{CsvColumn, CsvMapping, ImportCsvCmd, ImportRowError, SignConvention},
+    },
+    transport::{
+        amounts::format_amount,
+        auth::CurrentUser,
+        extract::{Json, Query},
+    },
+};
+
+type State = Extension<Arc<dyn BudgetService>>;
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Column {
+    Index(usize),
+    Name(String),
+}
+
+impl From<Column> for CsvColumn {
+    fn from(column: Column) -> Self {
+        match column {
+            Column::Index(index) => Self::Index(index),
+            Column::Name(name) => Self::Name(name),
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+enum Sign {
+    #[default]
+    NegativeIsOutcome,
+    PositiveIsOutcome,
+}
+
+impl From<Sign> for SignConvention {
+    fn from(sign: Sign) -> Self {
+        match sign {
+            Sign::NegativeIsOutcome => Self::NegativeIsOutcome,
+            Sign::PositiveIsOutcome => Self::PositiveIsOutcome,
+        }
+    }
+}
+
+/// Mapping profile sent as the `mapping` part of the upload.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Mapping {
+    #[serde(default = "default_has_header")]
+    has_header: bool,
+    date_column: Column,
+    amount_column: Column,
+    description_column: Option<Column>,
+    #[serde(default)]
+    sign_convention: Sign,
+    #[serde(default = "default_date_format")]
+    date_format: String,
+    #[serde(default = "default_delimiter")]
+    delimiter: char,
+    #[serde(default = "default_decimal_separator")]
+    decimal_separator: char,
+}
+
+fn default_has_header() -> bool {
+    true
+}
+
+fn default_date_format() -> String {
+    "%Y-%m-%d".into()
+}
+
+fn default_delimiter() -> char {
+    ','
+}
+
+fn default_decimal_separator() -> char {
+    '.'
+}
+
+impl From<Mapping> for CsvMapping {
+    fn from(mapping: Mapping) -> Self {
+        Self {
+            has_header: mapping.has_header,
+            date_column: mapping.date_column.into(),
+            amount_column: mapping.amount_column.into(),
+            description_column: mapping.description_column.map(Into::into),
+            sign_convention: mapping.sign_convention.into(),
+            date_format: mapping.date_format,
+            delimiter: mapping.delimiter,
+            decimal_separator: mapping.decimal_separator,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ImportedRecord {
+    /// Absent when nothing was written.
+    id: Option<i64>,
+    date: DateTime<Local>,
+    amount: String,
+    currency: String,
+    record_type: String,
+    description: Option<String>,
+}
+
+impl From<models::Record> for ImportedRecord {
+    fn from(record: models::Record) -> Self {
+        Self {
+            id: (record.id != 0).then_some(record.id),
+            date: record.occurred_at,
+            amount: format_amount(record.amount.get(), &record.currency),
+            currency: record.currency.to_string(),
+            record_type: record.record_type.to_string(),
+            description: record.description,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct RowError {
+    line: u64,
+    message: String,
+}
+
+impl From<ImportRowError> for RowError {
+    fn from(error: ImportRowError) -> Self {
+        Self {
+            line: error.line,
+            message: error.message,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ImportReport {
+    dry_run: bool,
+    imported: usize,
+    records: Vec<ImportedRecord>,
+    errors: Vec<RowError>,
+}
+
+#[derive(Deserialize)]
+pub struct ImportCsvReq {
+    account_id: i64,
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Serialize)]
+pub struct ImportCsvResponse {
+    #[serde(skip)]
+    status: StatusCode,
+    data: ImportReport,
+}
+
+impl IntoResponse for ImportCsvResponse {
+    fn into_response(self) -> axum::response::Response {
+        (self.status, Json(self)).into_response()
+    }
+}
+
+/// Imports a CSV statement sent as multipart form data with a `file` part
+/// holding the CSV and a `mapping` part holding the mapping profile as JSON.
+pub async fn import_csv(
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+    Query(req): Query<ImportCsvReq>,
+    mut multipart: Multipart,
+) -> Result<ImportCsvResponse> {
+    let mut content = None;
+    let mut mapping = None;
+    while let Some(field) = multipart.next_field().await? {
+        match field.name() {
+            Some("file") => content = Some(field.text().await?),
+            Some("mapping") => {
+                let bytes = field.bytes().await?;
+                let parsed: Mapping = serde_json::from_slice(&bytes).map_err(|e| {
+                    BudgetServiceError::from(ImportError::InvalidMapping(e.to_string()))
+                })?;
+                mapping = Some(parsed);
+            }
+            _ => {}
+        }
+    }
+
+    let content = content.ok_or(BudgetServiceError::from(ImportError::MissingField("file")))?;
+    let mapping = mapping.ok_or(BudgetServiceError::from(ImportError::MissingField(
+        "mapping",
+    )))?;
+
+    let result = svc
+        .import_csv(
+            user.id,
+            ImportCsvCmd {
+                account_id: req.account_id,
+                content,
+                mapping: mapping.into(),
+                dry_run: req.dry_run,
+            },
+        )
+        .await?;
+
+    let status = if !result.errors.is_empty() {
+        StatusCode::UNPROCESSABLE_ENTITY
+    } else if result.dry_run {
+        StatusCode::OK
+    } else {
+        StatusCode::CREATED
+    };
+
+    Ok(ImportCsvResponse {
+        status,
+        data: ImportReport {
+            dry_run: result.dry_run,
+            imported: result.imported,
+            records: result.records.into_iter().map(Into::into).collect(),
+            errors: result.errors.into_iter().map(Into::into).collect(),
+        },
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use axum::{
+        Router,
+        body::{Body, to_bytes},
+        http::{Request, StatusCode, header},
+    };
+    use serde_json::{Value, json};
+    use tower::ServiceExt;
+
+    use crate::{repository::test::test_db, service::budget::BudgetServiceImpl, transport::router};
+
+    async fn send(app: &Router, req: Request<Body>) -> (StatusCode, Value) {
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    fn post_json(uri: &str, token: Option<&str>, body: Value) -> Request<Body> {
+        let mut req = Request::post(uri).header(header::CONTENT_TYPE, "application/json");
+        if let Some(token) = token {
+            req = req.header(header::AUTHORIZATION, format!("Bearer {token}"));
+        }
+        req.body(Body::from(body.to_string())).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_import_report_keeps_row_errors() {
+        let app = router::new(BudgetServiceImpl::new(test_db(None).await));
+
+        let credentials = json!({"username": "importer", "password": "correct horse battery"});
+        send(&app, post_json("/auth/register", None, credentials.clone())).await;
+        let (_, login) = send(&app, post_json("/auth/login", None, credentials)).await;
+        let token = login["data"]["token"].as_str().unwrap().to_owned();
+        let account = json!({"name": "Wallet", "account_type": "Cash", "initial_balance": "0"});
+        let (_, account) = send(&app, post_json("/accounts", Some(&token), account)).await;
+
+        let mapping = r#"{"date_column": "Date", "amount_column": "Amount"}"#;
+        let csv = "Date,Amount\n2026-03-01,-3.50\n2026-03-02,broken\n";
+        let body = format!(
+            "--boundary\r\n\
+             Content-Disposition: form-data; name=\"mapping\"\r\n\r\n{mapping}\r\n\
+             --boundary\r\n\
+             Content-Disposition: form-data; name=\"file\"; filename=\"statement.csv\"\r\n\r\n{csv}\r\n\
+             --boundary--\r\n"
+        );
+        let req = Request::post(format!("/imports/csv?account_id={}", account["data"]["id"]))
+            .header(header::AUTHORIZATION, format!("Bearer {token}"))
+            .header(
+                header::CONTENT_TYPE,
+                "multipart/form-data; boundary=boundary",
+            )
+            .body(Body::from(body))
+            .unwrap();
+
+        let (status, report) = send(&app, req).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(report["data"]["imported"], 0);
+        assert_eq!(report["data"]["errors"].as_array().unwrap().len(), 1);
+        assert_eq!(report["data"]["errors"][0]["line"], 3);
+    }
+}
@@ -1,5 +1,18 @@
 pub mod accounts;
+pub mod amounts;
+pub mod audit;
+pub mod auth;
 pub mod categories;
+pub mod currencies;
 pub mod errors;
+pub mod extract;
+pub mod imports;
+pub mod openapi;
+pub mod params;
 pub mod records;
+pub mod recurring;
+pub mod reports;
 pub mod router;
+pub mod tags;
+pub mod trash;
+pub mod users;
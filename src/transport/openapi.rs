@@ -0,0 +1,99 @@
+use axum::{Json, Router, routing::get};
+use utoipa::{
+    Modify, OpenApi,
+    openapi::{
+        self,
+        security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+    },
+};
+use utoipa_scalar::{Scalar, Servable};
+
+use crate::transport::{accounts, categories, errors::Problem, records};
+
+/// OpenAPI document built from the `#[utoipa::path]` attributes of the
+/// handlers and the schemas of their request and response types.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Budget API"),
+    paths(
+        accounts::list_accounts,
+        accounts::get_account,
+        accounts::create_account,
+        accounts::update_account,
+        accounts::delete_account,
+        categories::list_categories,
+        categories::get_category,
+        categories::create_category,
+        categories::update_category,
+        categories::delete_category,
+        categories::category_tree,
+        categories::category_subtree,
+        categories::move_category,
+        records::list_records,
+        records::get_record,
+        records::create_record,
+        records::update_record,
+        records::delete_record,
+    ),
+    components(schemas(Problem)),
+    modifiers(&BearerAuth),
+    security(("bearer" = [])),
+    tags(
+        (name = "accounts", description = "Accounts holding a balance in one currency."),
+        (name = "categories", description = "Categories and their budgets, nested into trees."),
+        (name = "records", description = "Income, outcome and transfer records."),
+    )
+)]
+pub struct ApiDoc;
+
+/// Adds the bearer token scheme the document's `security` refers to.
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut openapi::OpenApi) {
+        openapi
+            .components
+            .get_or_insert_with(Default::default)
+            .add_security_scheme(
+                "bearer",
+                SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+            );
+    }
+}
+
+/// Routes serving the document at `/openapi.json` and an interactive page
+/// rendering it at `/docs`, both public.
+pub fn routes() -> Router {
+    Router::new()
+        .route("/openapi.json", get(|| async { Json(ApiDoc::openapi()) }))
+        .merge(Scalar::with_url("/docs", ApiDoc::openapi()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_schema_references_resolve() {
+        let doc = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let schemas = doc["components"]["schemas"].as_object().unwrap();
+
+        let mut stack = vec![&doc];
+        while let Some(value) = stack.pop() {
+            match value {
+                serde_json::Value::Object(map) => {
+                    if let Some(reference) = map.get("$ref").and_then(|r| r.as_str()) {
+                        let name = reference.trim_start_matches("#/components/schemas/");
+                        assert!(schemas.contains_key(name), "unknown schema {reference}");
+                    }
+                    stack.extend(map.values());
+                }
+                serde_json::Value::Array(values) => stack.extend(values),
+                _ => {}
+            }
+        }
+
+        assert_eq!(doc["openapi"], "3.1.0");
+        assert!(doc["paths"]["/records/{id}"]["put"].is_object());
+    }
+}
@@ -0,0 +1,325 @@
+use std::str::FromStr;
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{
+    DateTime, FixedOffset, Local, NaiveDate, NaiveTime, SecondsFormat, TimeDelta, TimeZone,
+};
+use serde::{Deserialize, Deserializer, Serialize, de::Error};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    domain::models::{
+        Currency, CurrencyError, Cursor, CursorKey, DeletePolicy, DeletePolicyError,
+        MAX_PAGE_LIMIT, Page, PageError,
+    },
+    service::budget::PageCmd,
+};
+
+/// Date query parameter, either a full RFC 3339 timestamp or a calendar date
+/// meaning local midnight of that day.
+#[derive(Deserialize, ToSchema)]
+#[serde(untagged)]
+pub enum DateParam {
+    DateTime(DateTime<FixedOffset>),
+    Date(NaiveDate),
+}
+
+impl From<DateParam> for DateTime<Local> {
+    fn from(param: DateParam) -> Self {
+        match param {
+            DateParam::DateTime(datetime) => datetime.with_timezone(&Local),
+            DateParam::Date(date) => start_of_day(date, &Local),
+        }
+    }
+}
+
+/// Midnight of `date` in `tz`, or the first quarter hour after it when a
+/// daylight saving change skips midnight, as it does in Santiago.
+fn start_of_day<Tz: TimeZone>(date: NaiveDate, tz: &Tz) -> DateTime<Tz> {
+    let midnight = date.and_time(NaiveTime::MIN);
+
+    // Gaps last an hour, a whole day at most when a zone skipped a date.
+    (0..=24 * 4)
+        .find_map(|quarter| {
+            (midnight + TimeDelta::minutes(15 * quarter))
+                .and_local_timezone(tz.clone())
+                .earliest()
+        })
+        .unwrap_or_else(|| tz.from_utc_datetime(&midnight))
+}
+
+/// Deletion policy query parameter, `reassign` needs a `target_id`.
+#[derive(Deserialize, Default, Clone, Copy, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DeletePolicyParam {
+    #[default]
+    Refuse,
+    Reassign,
+    Cascade,
+}
+
+impl DeletePolicyParam {
+    pub fn with_target(self, target_id: Option<i64>) -> Result<DeletePolicy, DeletePolicyError> {
+        match (self, target_id) {
+            (Self::Reassign, Some(target_id)) => Ok(DeletePolicy::Reassign(target_id)),
+            (Self::Reassign, None) => Err(DeletePolicyError::TargetRequired),
+            (_, Some(_)) => Err(DeletePolicyError::UnexpectedTarget),
+            (Self::Refuse, None) => Ok(DeletePolicy::Refuse),
+            (Self::Cascade, None) => Ok(DeletePolicy::Cascade),
+        }
+    }
+}
+
+/// Related objects to embed in a response, from a comma separated `expand`
+/// query parameter such as `category,account`.
+#[derive(Default, Clone, Copy)]
+pub struct ExpandParam {
+    pub category: bool,
+    pub account: bool,
+}
+
+impl<'de> Deserialize<'de> for ExpandParam {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        const FIELDS: &[&str] = &["category", "account"];
+
+        let value = String::deserialize(deserializer)?;
+        let mut expand = Self::default();
+        for field in value.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+            match field {
+                "category" => expand.category = true,
+                "account" => expand.account = true,
+                _ => return Err(D::Error::unknown_variant(field, FIELDS)),
+            }
+        }
+
+        Ok(expand)
+    }
+}
+
+/// Paging query parameters of list endpoints.
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PageParam {
+    /// Maximum number of items, from 1 to 1000, every remaining item when
+    /// absent.
+    #[param(minimum = 1, maximum = 1000)]
+    limit: Option<u64>,
+    /// `next_cursor` of the previous page.
+    cursor: Option<String>,
+    /// Also return `total_count`, the number of items on all pages.
+    #[serde(default)]
+    include_total: bool,
+    /// No longer supported, rejected so that clients still sending it do
+    /// not get the first page again and again.
+    #[param(ignore)]
+    offset: Option<String>,
+}
+
+impl PageParam {
+    pub fn into_cmd(self) -> Result<PageCmd, PageError> {
+        if self.offset.is_some() {
+            return Err(PageError::OffsetUnsupported);
+        }
+        if self
+            .limit
+            .is_some_and(|limit| !(1..=MAX_PAGE_LIMIT).contains(&limit))
+        {
+            return Err(PageError::InvalidLimit);
+        }
+
+        Ok(PageCmd {
+            limit: self.limit,
+            after: self.cursor.as_deref().map(decode_cursor).transpose()?,
+            include_total: self.include_total,
+        })
+    }
+}
+
+/// Paging metadata returned alongside `data` by list endpoints.
+#[derive(Serialize, ToSchema)]
+pub struct PageInfo {
+    /// Pass as `cursor` to get the next page, `null` on the last page.
+    next_cursor: Option<String>,
+    has_more: bool,
+    /// Number of items on all pages, only with `include_total=true`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_count: Option<u64>,
+}
+
+impl<T> From<&Page<T>> for PageInfo {
+    fn from(page: &Page<T>) -> Self {
+        Self {
+            next_cursor: page.next_cursor.as_ref().map(encode_cursor),
+            has_more: page.has_more(),
+            total_count: page.total_count,
+        }
+    }
+}
+
+/// Encodes a cursor as an opaque URL safe string, clients only pass it back.
+fn encode_cursor(cursor: &Cursor) -> String {
+    let key = match &cursor.key {
+        CursorKey::Id => "i|".to_owned(),
+        CursorKey::Date(date) => format!("d|{}", date.to_rfc3339_opts(SecondsFormat::AutoSi, true)),
+        CursorKey::Day(day) => format!("y|{day}"),
+        CursorKey::Amount(amount) => format!("a|{amount}"),
+        CursorKey::Name(Some(name)) => format!("n|{name}"),
+        CursorKey::Name(None) => "u|".to_owned(),
+    };
+    URL_SAFE_NO_PAD.encode(format!("{}|{key}", cursor.id))
+}
+
+fn decode_cursor(value: &str) -> Result<Cursor, PageError> {
+    let text = URL_SAFE_NO_PAD
+        .decode(value)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .ok_or(PageError::InvalidCursor)?;
+    // The name comes last since it may contain the separator.
+    let mut parts = text.splitn(3, '|');
+    let (Some(id), Some(kind), Some(value)) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(PageError::InvalidCursor);
+    };
+
+    let id = id.parse().map_err(|_| PageError::InvalidCursor)?;
+    let key = match (kind, value) {
+        ("i", "") => CursorKey::Id,
+        ("d", date) => DateTime::parse_from_rfc3339(date)
+            .map(|date| CursorKey::Date(date.with_timezone(&Local)))
+            .map_err(|_| PageError::InvalidCursor)?,
+        ("y", day) => day
+            .parse()
+            .map(CursorKey::Day)
+            .map_err(|_| PageError::InvalidCursor)?,
+        ("a", amount) => amount
+            .parse()
+            .map(CursorKey::Amount)
+            .map_err(|_| PageError::InvalidCursor)?,
+        ("n", name) => CursorKey::Name(Some(name.to_owned())),
+        ("u", "") => CursorKey::Name(None),
+        _ => return Err(PageError::InvalidCursor),
+    };
+
+    Ok(Cursor::new(key, id))
+}
+
+/// Parses an optional ISO 4217 currency code of a request.
+pub fn parse_currency(code: Option<&str>) -> Result<Option<Currency>, CurrencyError> {
+    code.map(Currency::from_str).transpose()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let cursors = [
+            Cursor::new(CursorKey::Id, 1),
+            Cursor::new(CursorKey::Date(Local::now()), 2),
+            Cursor::new(
+                CursorKey::Day(NaiveDate::from_ymd_opt(2026, 3, 1).unwrap()),
+                3,
+            ),
+            Cursor::new(CursorKey::Amount(-150), 3),
+            Cursor::new(CursorKey::Name(Some("food|drinks".into())), 4),
+            Cursor::new(CursorKey::Name(None), 5),
+        ];
+        for cursor in cursors {
+            let encoded = encode_cursor(&cursor);
+            assert!(
+                encoded
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "-_".contains(c))
+            );
+            assert_eq!(decode_cursor(&encoded), Ok(cursor));
+        }
+
+        for invalid in ["", "not a cursor", &URL_SAFE_NO_PAD.encode("1|x|2")] {
+            assert_eq!(decode_cursor(invalid), Err(PageError::InvalidCursor));
+        }
+    }
+
+    /// Zone moving from UTC-4 to UTC-3 at local midnight of 2026-09-06,
+    /// skipping the first hour of that day.
+    #[derive(Clone)]
+    struct Gap;
+
+    impl Gap {
+        fn switch() -> chrono::NaiveDateTime {
+            NaiveDate::from_ymd_opt(2026, 9, 6)
+                .unwrap()
+                .and_hms_opt(4, 0, 0)
+                .unwrap()
+        }
+
+        fn offset(hours: i32) -> FixedOffset {
+            FixedOffset::west_opt(hours * 60 * 60).unwrap()
+        }
+    }
+
+    impl TimeZone for Gap {
+        type Offset = FixedOffset;
+
+        fn from_offset(_: &FixedOffset) -> Self {
+            Gap
+        }
+
+        fn offset_from_local_date(&self, local: &NaiveDate) -> chrono::LocalResult<FixedOffset> {
+            self.offset_from_local_datetime(&local.and_time(NaiveTime::MIN))
+        }
+
+        fn offset_from_local_datetime(
+            &self,
+            local: &chrono::NaiveDateTime,
+        ) -> chrono::LocalResult<FixedOffset> {
+            let before = Gap::switch() - TimeDelta::hours(4);
+            match *local {
+                local if local < before => chrono::LocalResult::Single(Gap::offset(4)),
+                local if local < before + TimeDelta::hours(1) => chrono::LocalResult::None,
+                _ => chrono::LocalResult::Single(Gap::offset(3)),
+            }
+        }
+
+        fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+            self.offset_from_utc_datetime(&utc.and_time(NaiveTime::MIN))
+        }
+
+        fn offset_from_utc_datetime(&self, utc: &chrono::NaiveDateTime) -> FixedOffset {
+            match *utc < Gap::switch() {
+                true => Gap::offset(4),
+                false => Gap::offset(3),
+            }
+        }
+    }
+
+    #[test]
+    fn test_start_of_day_skipped_midnight() {
+        let day = |d| NaiveDate::from_ymd_opt(2026, 9, d).unwrap();
+
+        let start = start_of_day(day(5), &Gap);
+        assert_eq!(start.to_rfc3339(), "2026-09-05T00:00:00-04:00");
+        let start = start_of_day(day(6), &Gap);
+        assert_eq!(start.to_rfc3339(), "2026-09-06T01:00:00-03:00");
+    }
+
+    #[test]
+    fn test_offset_is_rejected() {
+        let page: PageParam = serde_urlencoded::from_str("limit=10&offset=20").unwrap();
+        assert!(matches!(page.into_cmd(), Err(PageError::OffsetUnsupported)));
+
+        let page: PageParam = serde_urlencoded::from_str("limit=10").unwrap();
+        assert_eq!(page.into_cmd().unwrap().limit, Some(10));
+    }
+
+    #[test]
+    fn test_limit_bounds() {
+        for invalid in ["limit=0", "limit=1001"] {
+            let page: PageParam = serde_urlencoded::from_str(invalid).unwrap();
+            assert!(matches!(page.into_cmd(), Err(PageError::InvalidLimit)));
+        }
+
+        let page: PageParam = serde_urlencoded::from_str("limit=1000").unwrap();
+        assert_eq!(page.into_cmd().unwrap().limit, Some(MAX_PAGE_LIMIT));
+    }
+}
@@ -1,51 +1,362 @@
-use std::sync::Arc;
+use std::{
+    collections::{BTreeSet, HashMap},
+    str::FromStr,
+    sync::Arc,
+};
 
 use axum::{
-    Extension, Json,
-    extract::{Path, Query},
+    Extension,
     http::StatusCode,
     response::{IntoResponse, Result},
 };
 use serde::{Deserialize, Serialize};
+use sqlx::types::chrono::{DateTime, Local};
+use utoipa::{IntoParams, ToSchema};
 
 use crate::{
-    domain::models,
+    domain::{
+        errors::BudgetServiceError,
+        models::{self, CategoryError, Currency, Money, RecordError, TagError},
+    },
     service::{
         budget::BudgetService,
-        records::{CreateRecordCmd, ListRecordsCmd, UpdateRecordCmd},
+        records::{
+            CreateRecordCmd, ListRecordsCmd, RecordSortField, RecordSplitCmd, SortOrder, TagMatch,
+            UpdateRecordCmd,
+        },
+    },
+    transport::{
+        accounts::Account,
+        amounts::{decimal, format_amount},
+        auth::CurrentUser,
+        categories::Category,
+        errors::ProblemResponses,
+        extract::{Json, Path, Query},
+        params::{DateParam, ExpandParam, PageInfo, PageParam},
     },
 };
 
 type State = Extension<Arc<dyn BudgetService>>;
 
-#[derive(Serialize)]
-struct Record {
+#[derive(Serialize, ToSchema)]
+pub struct Record {
     id: i64,
-    amount: i64,
+    account_id: i64,
+    amount: String,
+    /// Currency of the account, split lines use it as well.
+    currency: String,
     record_type: String,
+    description: Option<String>,
     category_id: Option<i64>,
+    destination_account_id: Option<i64>,
+    received_amount: Option<String>,
+    /// Currency of the destination account and the received amount.
+    destination_currency: Option<String>,
+    splits: Vec<RecordSplit>,
+    tag_ids: Vec<i64>,
+    occurred_at: DateTime<Local>,
+    posted_at: Option<DateTime<Local>>,
+    created_at: DateTime<Local>,
+    updated_at: DateTime<Local>,
+    /// Embedded with `expand=account`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    account: Option<Account>,
+    /// Destination account of a transfer, embedded with `expand=account`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    destination_account: Option<Account>,
+    /// Embedded with `expand=category`, split records have no category.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    category: Option<Category>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+struct RecordSplit {
+    category_id: i64,
+    amount: String,
+    note: Option<String>,
+}
+
+impl RecordSplit {
+    fn new(split: &models::RecordSplit, currency: &Currency) -> Self {
+        Self {
+            category_id: split.category.id,
+            amount: format_amount(split.amount.get(), currency),
+            note: split.note.clone(),
+        }
+    }
+}
+
+impl From<RecordSplit> for RecordSplitCmd {
+    fn from(split: RecordSplit) -> Self {
+        Self {
+            category_id: split.category_id,
+            amount: decimal(split.amount),
+            note: split.note,
+        }
+    }
 }
 
 impl From<&models::Record> for Record {
     fn from(record: &models::Record) -> Self {
         Self {
             id: record.id,
-            amount: record.amount.into(),
+            account_id: record.account_id,
+            amount: format_amount(record.amount.get(), &record.currency),
+            currency: record.currency.to_string(),
             record_type: record.record_type.to_string(),
+            description: record.description.clone(),
             category_id: record.category.clone().map(|c| c.id),
+            destination_account_id: record.transfer.as_ref().map(|t| t.account_id),
+            received_amount: record
+                .transfer
+                .as_ref()
+                .map(|t| format_amount(t.amount.get(), &t.currency)),
+            destination_currency: record.transfer.as_ref().map(|t| t.currency.to_string()),
+            splits: record
+                .splits
+                .iter()
+                .map(|split| RecordSplit::new(split, &record.currency))
+                .collect(),
+            tag_ids: record.tags.iter().map(|t| t.id).collect(),
+            occurred_at: record.occurred_at,
+            posted_at: record.posted_at,
+            created_at: record.created_at,
+            updated_at: record.updated_at,
+            account: None,
+            destination_account: None,
+            category: None,
         }
     }
 }
 
-#[derive(Deserialize)]
+/// Accounts and categories embedded in records as requested by `expand`.
+#[derive(Default)]
+struct Related {
+    accounts: HashMap<i64, Account>,
+    categories: HashMap<i64, Category>,
+}
+
+impl Related {
+    /// Loads the accounts and categories `records` refer to, those deleted
+    /// since are left out.
+    async fn load(
+        svc: &Arc<dyn BudgetService>,
+        user: &models::User,
+        expand: ExpandParam,
+        records: &[models::Record],
+    ) -> Result<Self, BudgetServiceError> {
+        let mut related = Self::default();
+        if expand.account {
+            let ids: BTreeSet<i64> = records
+                .iter()
+                .flat_map(|record| {
+                    let destination = record.transfer.as_ref().map(|t| t.account_id);
+                    std::iter::once(record.account_id).chain(destination)
+                })
+                .collect();
+            for id in ids {
+                match svc.get_account(user.id, id).await {
+                    Ok(account) => {
+                        related.accounts.insert(id, Account::from(&account));
+                    }
+                    Err(BudgetServiceError::EntityNotFoundError { .. }) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+        if expand.category {
+            let ids: BTreeSet<i64> = records
+                .iter()
+                .filter_map(|record| record.category.as_ref().map(|c| c.id))
+                .collect();
+            for id in ids {
+                match svc.get_category(user.id, id).await {
+                    Ok(category) => {
+                        let embedded = Category::new(&category, &user.reporting_currency);
+                        related.categories.insert(id, embedded);
+                    }
+                    Err(BudgetServiceError::EntityNotFoundError { .. }) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        Ok(related)
+    }
+
+    fn embed(&self, record: &models::Record) -> Record {
+        let mut embedded = Record::from(record);
+        embedded.account = self.accounts.get(&record.account_id).cloned();
+        embedded.destination_account = record
+            .transfer
+            .as_ref()
+            .and_then(|transfer| self.accounts.get(&transfer.account_id))
+            .cloned();
+        embedded.category = record
+            .category
+            .as_ref()
+            .and_then(|category| self.categories.get(&category.id))
+            .cloned();
+        embedded
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ListRecordsReq {
-    limit: Option<u64>,
-    offset: Option<u64>,
     category_id: Option<i64>,
+    /// Comma separated list of category ids.
+    category_ids: Option<String>,
+    #[serde(default)]
+    include_subcategories: bool,
+    /// Comma separated list of tag ids.
+    tag_ids: Option<String>,
+    #[serde(default)]
+    #[param(inline)]
+    tag_match: TagMatchParam,
+    account_id: Option<i64>,
+    record_type: Option<String>,
+    #[param(inline)]
+    from: Option<DateParam>,
+    #[param(inline)]
+    to: Option<DateParam>,
+    /// Decimal in the reporting currency of the user, amounts of accounts in
+    /// other currencies are converted with the rate of the record's date.
+    min_amount: Option<String>,
+    /// Decimal in the reporting currency of the user.
+    max_amount: Option<String>,
+    description: Option<String>,
+    #[serde(default)]
+    #[param(inline)]
+    sort: SortField,
+    #[serde(default)]
+    #[param(inline)]
+    order: Order,
+    /// Comma separated related objects to embed, `category` and `account`.
+    #[serde(default)]
+    #[param(value_type = Option<String>)]
+    expand: ExpandParam,
+}
+
+#[derive(Deserialize, Default, ToSchema)]
+#[serde(rename_all = "lowercase")]
+enum TagMatchParam {
+    #[default]
+    Any,
+    All,
+}
+
+impl From<TagMatchParam> for TagMatch {
+    fn from(tag_match: TagMatchParam) -> Self {
+        match tag_match {
+            TagMatchParam::Any => Self::Any,
+            TagMatchParam::All => Self::All,
+        }
+    }
+}
+
+#[derive(Deserialize, Default, ToSchema)]
+#[serde(rename_all = "lowercase")]
+enum SortField {
+    #[default]
+    Date,
+    Amount,
+    Category,
+}
+
+impl From<SortField> for RecordSortField {
+    fn from(field: SortField) -> Self {
+        match field {
+            SortField::Date => Self::Date,
+            SortField::Amount => Self::Amount,
+            SortField::Category => Self::Category,
+        }
+    }
+}
+
+#[derive(Deserialize, Default, ToSchema)]
+#[serde(rename_all = "lowercase")]
+enum Order {
+    Asc,
+    #[default]
+    Desc,
+}
+
+impl From<Order> for SortOrder {
+    fn from(order: Order) -> Self {
+        match order {
+            Order::Asc => Self::Asc,
+            Order::Desc => Self::Desc,
+        }
+    }
+}
+
+impl ListRecordsReq {
+    fn into_cmd(
+        self,
+        page: PageParam,
+        currency: &Currency,
+    ) -> Result<ListRecordsCmd, BudgetServiceError> {
+        let page = page.into_cmd()?;
+        let mut category_ids = self.category_id.into_iter().collect::<Vec<_>>();
+        if let Some(ids) = self.category_ids {
+            category_ids.extend(parse_ids(&ids, CategoryError::InvalidCategoryId)?);
+        }
+        let tag_ids = match self.tag_ids {
+            Some(ids) => parse_ids(&ids, TagError::InvalidTagId)?,
+            None => Vec::new(),
+        };
+
+        let record_type = self
+            .record_type
+            .map(|record_type| {
+                models::RecordType::from_str(&record_type)
+                    .map_err(|_| RecordError::InvalidRecordType(record_type))
+            })
+            .transpose()?;
+        let parse_amount = |amount: Option<String>| {
+            amount
+                .map(|amount| Money::parse(&amount, currency.clone()))
+                .transpose()
+        };
+
+        Ok(ListRecordsCmd {
+            limit: page.limit,
+            after: page.after,
+            include_total: page.include_total,
+            category_ids,
+            include_subcategories: self.include_subcategories,
+            tag_ids,
+            tag_match: self.tag_match.into(),
+            account_id: self.account_id,
+            record_type,
+            from: self.from.map(Into::into),
+            to: self.to.map(Into::into),
+            min_amount: parse_amount(self.min_amount)?,
+            max_amount: parse_amount(self.max_amount)?,
+            description: self.description,
+            sort_by: self.sort.into(),
+            sort_order: self.order.into(),
+        })
+    }
+}
+/// Parses a comma separated list of ids.
+fn parse_ids<E: Into<BudgetServiceError>>(
+    ids: &str,
+    invalid: impl Fn(String) -> E,
+) -> Result<Vec<i64>, BudgetServiceError> {
+    ids.split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| id.parse().map_err(|_| invalid(id.to_owned()).into()))
+        .collect()
 }
-#[derive(Serialize)]
+
+#[derive(Serialize, ToSchema)]
 pub struct ListRecordsResponse {
     data: Vec<Record>,
+    #[serde(flatten)]
+    page: PageInfo,
 }
 
 impl IntoResponse for ListRecordsResponse {
@@ -54,33 +365,94 @@ impl IntoResponse for ListRecordsResponse {
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/records",
+    tag = "records",
+    params(ListRecordsReq, PageParam),
+    responses((status = 200, body = ListRecordsResponse), ProblemResponses)
+)]
 pub async fn list_records(
     Extension(svc): State,
+    CurrentUser(user): CurrentUser,
     Query(req): Query<ListRecordsReq>,
+    Query(page): Query<PageParam>,
 ) -> Result<ListRecordsResponse> {
-    let result = svc
-        .list_records(ListRecordsCmd {
-            limit: req.limit,
-            offset: req.offset,
-            category_id: req.category_id,
-        })
-        .await?;
+    let expand = req.expand;
+    let cmd = req.into_cmd(page, &user.reporting_currency)?;
+    let result = svc.list_records(user.id, cmd).await?;
+    let related = Related::load(&svc, &user, expand, &result.items).await?;
 
     Ok(ListRecordsResponse {
-        data: result.iter().map(Record::from).collect(),
+        data: result
+            .items
+            .iter()
+            .map(|record| related.embed(record))
+            .collect(),
+        page: PageInfo::from(&result),
     })
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct GetRecordReq {
+    /// Comma separated related objects to embed, `category` and `account`.
+    #[serde(default)]
+    #[param(value_type = Option<String>)]
+    expand: ExpandParam,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct GetRecordResponse {
+    data: Record,
+}
+
+impl IntoResponse for GetRecordResponse {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::OK, Json(self)).into_response()
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/records/{id}",
+    tag = "records",
+    params(("id" = i64, Path), GetRecordReq),
+    responses((status = 200, body = GetRecordResponse), ProblemResponses)
+)]
+pub async fn get_record(
+    Path(id): Path<i64>,
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+    Query(req): Query<GetRecordReq>,
+) -> Result<GetRecordResponse> {
+    let result = svc.get_record(user.id, id).await?;
+    let related = Related::load(&svc, &user, req.expand, std::slice::from_ref(&result)).await?;
+
+    Ok(GetRecordResponse {
+        data: related.embed(&result),
+    })
+}
+
+#[derive(Deserialize, ToSchema)]
 pub struct CreateRecordRequest {
     account_id: i64,
     transaction_type: String,
-    amount: i64,
+    amount: String,
     category: Option<i64>,
     description: Option<String>,
+    destination_account_id: Option<i64>,
+    received_amount: Option<String>,
+    #[serde(default)]
+    splits: Vec<RecordSplit>,
+    #[serde(default)]
+    tag_ids: Vec<i64>,
+    /// Timestamp or calendar date of the transaction, defaults to now.
+    occurred_at: Option<DateParam>,
+    posted_at: Option<DateParam>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct CreateRecordResponse {
     data: Record,
 }
@@ -91,32 +463,60 @@ impl IntoResponse for CreateRecordResponse {
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/records",
+    tag = "records",
+    request_body = CreateRecordRequest,
+    responses((status = 201, body = CreateRecordResponse), ProblemResponses)
+)]
 pub async fn create_record(
     Extension(svc): State,
+    CurrentUser(user): CurrentUser,
     Json(req): Json<CreateRecordRequest>,
 ) -> Result<CreateRecordResponse> {
     let result = svc
-        .create_record(CreateRecordCmd {
-            account_id: req.account_id,
-            transaction_type: req.transaction_type,
-            amount: req.amount,
-            category: req.category,
-            description: req.description,
-        })
+        .create_record(
+            user.id,
+            CreateRecordCmd {
+                account_id: req.account_id,
+                transaction_type: req.transaction_type,
+                amount: decimal(req.amount),
+                category: req.category,
+                description: req.description,
+                destination_account_id: req.destination_account_id,
+                received_amount: req.received_amount.map(decimal),
+                splits: req.splits.into_iter().map(Into::into).collect(),
+                tag_ids: req.tag_ids,
+                occurred_at: req.occurred_at.map(Into::into),
+                posted_at: req.posted_at.map(Into::into),
+            },
+        )
         .await?;
     Ok(CreateRecordResponse {
         data: Record::from(&result),
     })
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct UpdateRecordRequest {
-    amount: i64,
+    amount: String,
     description: Option<String>,
     category_id: Option<i64>,
+    destination_account_id: Option<i64>,
+    /// Amount the destination of a transfer receives, kept when absent.
+    received_amount: Option<String>,
+    /// Replaces the split lines when present, an empty list removes them.
+    splits: Option<Vec<RecordSplit>>,
+    /// Replaces the tags when present.
+    tag_ids: Option<Vec<i64>>,
+    /// Replaces the transaction date when present.
+    occurred_at: Option<DateParam>,
+    /// Replaces the posting date when present.
+    posted_at: Option<DateParam>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct UpdateRecordResponse {
     data: Record,
 }
@@ -127,26 +527,57 @@ impl IntoResponse for UpdateRecordResponse {
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/records/{id}",
+    tag = "records",
+    params(("id" = i64, Path)),
+    request_body = UpdateRecordRequest,
+    responses((status = 200, body = UpdateRecordResponse), ProblemResponses)
+)]
 pub async fn update_record(
     Path(id): Path<i64>,
     Extension(svc): State,
+    CurrentUser(user): CurrentUser,
     Json(req): Json<UpdateRecordRequest>,
 ) -> Result<UpdateRecordResponse> {
     let result = svc
-        .update_record(UpdateRecordCmd {
-            id,
-            amount: req.amount,
-            description: req.description,
-            category_id: req.category_id,
-        })
+        .update_record(
+            user.id,
+            UpdateRecordCmd {
+                id,
+                amount: decimal(req.amount),
+                description: req.description,
+                category_id: req.category_id,
+                destination_account_id: req.destination_account_id,
+                received_amount: req.received_amount.map(decimal),
+                splits: req
+                    .splits
+                    .map(|splits| splits.into_iter().map(Into::into).collect()),
+                tag_ids: req.tag_ids,
+                occurred_at: req.occurred_at.map(Into::into),
+                posted_at: req.posted_at.map(Into::into),
+            },
+        )
         .await?;
 
     Ok(UpdateRecordResponse {
         data: Record::from(&result),
     })
 }
-pub async fn delete_record(Path(id): Path<i64>, Extension(svc): State) -> impl IntoResponse {
-    let result = svc.delete_record(id).await;
+#[utoipa::path(
+    delete,
+    path = "/records/{id}",
+    tag = "records",
+    params(("id" = i64, Path)),
+    responses((status = 200), ProblemResponses)
+)]
+pub async fn delete_record(
+    Path(id): Path<i64>,
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+) -> impl IntoResponse {
+    let result = svc.delete_record(user.id, id).await;
 
     match result {
         Ok(()) => (StatusCode::OK).into_response(),
@@ -0,0 +1,304 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    http::StatusCode,
+    response::{IntoResponse, Result},
+};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        errors::BudgetServiceError,
+        models::{self, RecurrenceUnit},
+    },
+    service::{
+        budget::BudgetService,
+        records::CreateRecordCmd,
+        recurring::{CreateRecurringRecordCmd, UpdateRecurringRecordCmd},
+    },
+    transport::{
+        amounts::{decimal, format_amount},
+        auth::CurrentUser,
+        extract::{Json, Path, Query},
+        params::{PageInfo, PageParam},
+    },
+};
+
+type State = Extension<Arc<dyn BudgetService>>;
+
+const DEFAULT_PREVIEW_COUNT: usize = 5;
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+enum Unit {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl From<Unit> for RecurrenceUnit {
+    fn from(unit: Unit) -> Self {
+        match unit {
+            Unit::Day => Self::Day,
+            Unit::Week => Self::Week,
+            Unit::Month => Self::Month,
+            Unit::Year => Self::Year,
+        }
+    }
+}
+
+impl From<RecurrenceUnit> for Unit {
+    fn from(unit: RecurrenceUnit) -> Self {
+        match unit {
+            RecurrenceUnit::Day => Self::Day,
+            RecurrenceUnit::Week => Self::Week,
+            RecurrenceUnit::Month => Self::Month,
+            RecurrenceUnit::Year => Self::Year,
+        }
+    }
+}
+
+fn default_interval() -> u32 {
+    1
+}
+
+#[derive(Serialize, Deserialize)]
+struct Recurrence {
+    unit: Unit,
+    #[serde(default = "default_interval")]
+    interval: u32,
+    day_of_month: Option<u32>,
+}
+
+#[derive(Serialize)]
+struct RecurringRecord {
+    id: i64,
+    account_id: i64,
+    record_type: String,
+    amount: String,
+    /// Currency of the account.
+    currency: String,
+    description: Option<String>,
+    category_id: Option<i64>,
+    destination_account_id: Option<i64>,
+    received_amount: Option<String>,
+    /// Currency of the destination account and the received amount.
+    destination_currency: Option<String>,
+    recurrence: Recurrence,
+    starts_at: DateTime<Local>,
+    ends_at: Option<DateTime<Local>>,
+    next_occurrence_at: Option<DateTime<Local>>,
+}
+
+impl From<models::RecurringRecord> for RecurringRecord {
+    fn from(recurring: models::RecurringRecord) -> Self {
+        Self {
+            id: recurring.id,
+            account_id: recurring.account_id,
+            record_type: recurring.record_type.to_string(),
+            amount: format_amount(recurring.amount, &recurring.currency),
+            currency: recurring.currency.to_string(),
+            description: recurring.description,
+            category_id: recurring.category_id,
+            destination_account_id: recurring.destination_account_id,
+            received_amount: recurring
+                .received_amount
+                .zip(recurring.destination_currency.as_ref())
+                .map(|(amount, currency)| format_amount(amount, currency)),
+            destination_currency: recurring.destination_currency.map(|c| c.to_string()),
+            recurrence: Recurrence {
+                unit: recurring.recurrence.unit.into(),
+                interval: recurring.recurrence.interval,
+                day_of_month: recurring.recurrence.day_of_month,
+            },
+            starts_at: recurring.starts_at,
+            ends_at: recurring.ends_at,
+            next_occurrence_at: recurring.next_occurrence_at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ListRecurringRecordsResponse {
+    data: Vec<RecurringRecord>,
+    #[serde(flatten)]
+    page: PageInfo,
+}
+
+impl IntoResponse for ListRecurringRecordsResponse {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::OK, Json(self)).into_response()
+    }
+}
+
+pub async fn list_recurring_records(
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+    Query(page): Query<PageParam>,
+) -> Result<ListRecurringRecordsResponse> {
+    let page = page.into_cmd().map_err(BudgetServiceError::from)?;
+    let result = svc.list_recurring_records(user.id, page).await?;
+    let page = PageInfo::from(&result);
+
+    Ok(ListRecurringRecordsResponse {
+        data: result
+            .items
+            .into_iter()
+            .map(RecurringRecord::from)
+            .collect(),
+        page,
+    })
+}
+
+#[derive(Deserialize)]
+pub struct CreateRecurringRecordRequest {
+    account_id: i64,
+    transaction_type: String,
+    amount: String,
+    category: Option<i64>,
+    description: Option<String>,
+    destination_account_id: Option<i64>,
+    received_amount: Option<String>,
+    recurrence: Recurrence,
+    starts_at: DateTime<Local>,
+    ends_at: Option<DateTime<Local>>,
+}
+
+#[derive(Serialize)]
+pub struct CreateRecurringRecordResponse {
+    data: RecurringRecord,
+}
+
+impl IntoResponse for CreateRecurringRecordResponse {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::CREATED, Json(self)).into_response()
+    }
+}
+
+pub async fn create_recurring_record(
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+    Json(req): Json<CreateRecurringRecordRequest>,
+) -> Result<CreateRecurringRecordResponse> {
+    let result = svc
+        .create_recurring_record(
+            user.id,
+            CreateRecurringRecordCmd {
+                record: CreateRecordCmd {
+                    account_id: req.account_id,
+                    transaction_type: req.transaction_type,
+                    amount: decimal(req.amount),
+                    category: req.category,
+                    description: req.description,
+                    destination_account_id: req.destination_account_id,
+                    received_amount: req.received_amount.map(decimal),
+                    splits: Vec::new(),
+                    tag_ids: Vec::new(),
+                    occurred_at: None,
+                    posted_at: None,
+                },
+                unit: req.recurrence.unit.into(),
+                interval: req.recurrence.interval,
+                day_of_month: req.recurrence.day_of_month,
+                starts_at: req.starts_at,
+                ends_at: req.ends_at,
+            },
+        )
+        .await?;
+
+    Ok(CreateRecurringRecordResponse {
+        data: result.into(),
+    })
+}
+
+#[derive(Deserialize)]
+pub struct UpdateRecurringRecordRequest {
+    amount: String,
+    description: Option<String>,
+    category_id: Option<i64>,
+    destination_account_id: Option<i64>,
+    received_amount: Option<String>,
+    ends_at: Option<DateTime<Local>>,
+}
+
+#[derive(Serialize)]
+pub struct UpdateRecurringRecordResponse {
+    data: RecurringRecord,
+}
+
+impl IntoResponse for UpdateRecurringRecordResponse {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::OK, Json(self)).into_response()
+    }
+}
+
+pub async fn update_recurring_record(
+    Path(id): Path<i64>,
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+    Json(req): Json<UpdateRecurringRecordRequest>,
+) -> Result<UpdateRecurringRecordResponse> {
+    let result = svc
+        .update_recurring_record(
+            user.id,
+            UpdateRecurringRecordCmd {
+                id,
+                amount: decimal(req.amount),
+                description: req.description,
+                category_id: req.category_id,
+                destination_account_id: req.destination_account_id,
+                received_amount: req.received_amount.map(decimal),
+                ends_at: req.ends_at,
+            },
+        )
+        .await?;
+
+    Ok(UpdateRecurringRecordResponse {
+        data: result.into(),
+    })
+}
+
+pub async fn delete_recurring_record(
+    Path(id): Path<i64>,
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+) -> impl IntoResponse {
+    let result = svc.delete_recurring_record(user.id, id).await;
+
+    match result {
+        Ok(()) => (StatusCode::OK).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct PreviewRecurringRecordReq {
+    count: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct PreviewRecurringRecordResponse {
+    data: Vec<DateTime<Local>>,
+}
+
+impl IntoResponse for PreviewRecurringRecordResponse {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::OK, Json(self)).into_response()
+    }
+}
+
+pub async fn preview_recurring_record(
+    Path(id): Path<i64>,
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+    Query(req): Query<PreviewRecurringRecordReq>,
+) -> Result<PreviewRecurringRecordResponse> {
+    let result = svc
+        .preview_recurring_record(user.id, id, req.count.unwrap_or(DEFAULT_PREVIEW_COUNT))
+        .await?;
+
+    Ok(PreviewRecurringRecordResponse { data: result })
+}
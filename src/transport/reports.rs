@@ -0,0 +1,272 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    http::StatusCode,
+    response::{IntoResponse, Result},
+};
+use serde::{Deserialize, Serialize};
+use sqlx::types::chrono::NaiveDate;
+
+use crate::{
+    domain::{
+        errors::BudgetServiceError,
+        models::{self, Currency},
+    },
+    service::{
+        budget::BudgetService,
+        reports::{BudgetReportCmd, CashFlowCmd, CashFlowInterval},
+    },
+    transport::{
+        amounts::format_amount,
+        auth::CurrentUser,
+        extract::{Json, Query},
+        params::{DateParam, parse_currency},
+    },
+};
+
+type State = Extension<Arc<dyn BudgetService>>;
+
+#[derive(Serialize)]
+struct CategoryBudget {
+    category_id: i64,
+    name: String,
+    parent_id: Option<i64>,
+    budget: Option<String>,
+    spent: String,
+    remaining: Option<String>,
+    percent_used: Option<f64>,
+}
+
+impl CategoryBudget {
+    fn new(report: models::CategoryBudgetReport, currency: &Currency) -> Self {
+        Self {
+            category_id: report.category.id,
+            name: report.category.name,
+            parent_id: report.category.parent_id,
+            budget: report
+                .category
+                .budget
+                .map(|budget| format_amount(budget, currency)),
+            spent: format_amount(report.spent, currency),
+            remaining: report
+                .remaining
+                .map(|remaining| format_amount(remaining, currency)),
+            percent_used: report.percent_used,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct TagTotal {
+    tag_id: i64,
+    name: String,
+    spent: String,
+}
+
+impl TagTotal {
+    fn new(report: models::TagSpending, currency: &Currency) -> Self {
+        Self {
+            tag_id: report.tag.id,
+            name: report.tag.name,
+            spent: format_amount(report.spent, currency),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct CashFlowBucket {
+    /// First day of the interval.
+    start: NaiveDate,
+    income: String,
+    outcome: String,
+    net: String,
+    /// Balance of the selected account, or of all accounts, at the end of
+    /// the interval.
+    running_balance: String,
+}
+
+impl CashFlowBucket {
+    fn new(bucket: models::CashFlowBucket, currency: &Currency) -> Self {
+        Self {
+            start: bucket.start,
+            income: format_amount(bucket.income, currency),
+            outcome: format_amount(bucket.outcome, currency),
+            net: format_amount(bucket.net, currency),
+            running_balance: format_amount(bucket.running_balance, currency),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct BudgetReportReq {
+    from: DateParam,
+    to: DateParam,
+    /// ISO 4217 code, defaults to the reporting currency of the user.
+    currency: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct BudgetReportResponse {
+    currency: String,
+    data: Vec<CategoryBudget>,
+}
+
+impl IntoResponse for BudgetReportResponse {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::OK, Json(self)).into_response()
+    }
+}
+
+pub async fn budget_report(
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+    Query(req): Query<BudgetReportReq>,
+) -> Result<BudgetReportResponse> {
+    let currency = parse_currency(req.currency.as_deref())
+        .map_err(BudgetServiceError::from)?
+        .unwrap_or(user.reporting_currency);
+    let result = svc
+        .budget_report(
+            user.id,
+            BudgetReportCmd {
+                from: req.from.into(),
+                to: req.to.into(),
+                currency: currency.clone(),
+            },
+        )
+        .await?;
+
+    Ok(BudgetReportResponse {
+        currency: currency.to_string(),
+        data: result
+            .into_iter()
+            .map(|report| CategoryBudget::new(report, &currency))
+            .collect(),
+    })
+}
+
+#[derive(Deserialize)]
+pub struct TagReportReq {
+    from: DateParam,
+    to: DateParam,
+    /// ISO 4217 code, defaults to the reporting currency of the user.
+    currency: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct TagReportResponse {
+    currency: String,
+    data: Vec<TagTotal>,
+}
+
+impl IntoResponse for TagReportResponse {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::OK, Json(self)).into_response()
+    }
+}
+
+pub async fn tag_report(
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+    Query(req): Query<TagReportReq>,
+) -> Result<TagReportResponse> {
+    let currency = parse_currency(req.currency.as_deref())
+        .map_err(BudgetServiceError::from)?
+        .unwrap_or(user.reporting_currency);
+    let result = svc
+        .tag_report(
+            user.id,
+            BudgetReportCmd {
+                from: req.from.into(),
+                to: req.to.into(),
+                currency: currency.clone(),
+            },
+        )
+        .await?;
+
+    Ok(TagReportResponse {
+        currency: currency.to_string(),
+        data: result
+            .into_iter()
+            .map(|report| TagTotal::new(report, &currency))
+            .collect(),
+    })
+}
+
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+enum IntervalParam {
+    Day,
+    Week,
+    #[default]
+    Month,
+    Year,
+}
+
+impl From<IntervalParam> for CashFlowInterval {
+    fn from(interval: IntervalParam) -> Self {
+        match interval {
+            IntervalParam::Day => Self::Day,
+            IntervalParam::Week => Self::Week,
+            IntervalParam::Month => Self::Month,
+            IntervalParam::Year => Self::Year,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CashFlowReq {
+    from: DateParam,
+    to: DateParam,
+    #[serde(default)]
+    interval: IntervalParam,
+    account_id: Option<i64>,
+    category_id: Option<i64>,
+    /// ISO 4217 code, defaults to the reporting currency of the user.
+    currency: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct CashFlowResponse {
+    currency: String,
+    data: Vec<CashFlowBucket>,
+}
+
+impl IntoResponse for CashFlowResponse {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::OK, Json(self)).into_response()
+    }
+}
+
+/// Income, outcome and net flow per day, week, month or year of a period.
+pub async fn cash_flow(
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+    Query(req): Query<CashFlowReq>,
+) -> Result<CashFlowResponse> {
+    let currency = parse_currency(req.currency.as_deref())
+        .map_err(BudgetServiceError::from)?
+        .unwrap_or(user.reporting_currency);
+    let result = svc
+        .cash_flow(
+            user.id,
+            CashFlowCmd {
+                from: req.from.into(),
+                to: req.to.into(),
+                currency: currency.clone(),
+                interval: req.interval.into(),
+                account_id: req.account_id,
+                category_id: req.category_id,
+            },
+        )
+        .await?;
+
+    Ok(CashFlowResponse {
+        currency: currency.to_string(),
+        data: result
+            .into_iter()
+            .map(|bucket| CashFlowBucket::new(bucket, &currency))
+            .collect(),
+    })
+}
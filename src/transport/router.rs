@@ -1,15 +1,37 @@
 use std::sync::Arc;
 
 use axum::{
-    Extension, Router,
-    routing::{get, put},
+    Extension, Router, middleware,
+    routing::{delete, get, post, put},
 };
 
 use crate::{
     service::budget::BudgetService,
     transport::{
-        accounts::{create_account, delete_account, list_accounts, update_account},
-        categories::{create_category, delete_category, list_categories, update_category},
+        accounts::{create_account, delete_account, get_account, list_accounts, update_account},
+        audit::{activity_feed, entity_history},
+        auth::{create_token, delete_token, list_tokens, login, logout, register, require_auth},
+        categories::{
+            category_subtree, category_tree, create_category, delete_category, get_category,
+            list_categories, move_category, update_category,
+        },
+        currencies::{
+            delete_exchange_rate, import_exchange_rates, list_exchange_rates, set_exchange_rate,
+        },
+        errors::problem_details,
+        imports::import_csv,
+        openapi,
+        recurring::{
+            create_recurring_record, delete_recurring_record, list_recurring_records,
+            preview_recurring_record, update_recurring_record,
+        },
+        reports::{budget_report, cash_flow, tag_report},
+        tags::{create_tag, delete_tag, list_tags, merge_tag, update_tag},
+        trash::{
+            empty_trash, list_deleted_accounts, list_deleted_categories, list_deleted_records,
+            restore_account, restore_category, restore_record,
+        },
+        users::{get_current_user, update_current_user},
     },
 };
 
@@ -17,19 +39,86 @@ use super::records::*;
 
 pub fn new<T: BudgetService>(budget_svc: T) -> Router {
     let tx_svc = Arc::new(budget_svc) as Arc<dyn BudgetService>;
-    Router::new()
+
+    let protected = Router::new()
+        //
+        .route("/auth/logout", post(logout))
+        .route("/auth/tokens", get(list_tokens).post(create_token))
+        .route("/auth/tokens/{id}", delete(delete_token))
+        //
+        .route("/users/me", get(get_current_user).put(update_current_user))
         //
         .route("/accounts", get(list_accounts).post(create_account))
-        .route("/accounts/{id}", put(update_account).delete(delete_account))
+        .route(
+            "/accounts/{id}",
+            get(get_account).put(update_account).delete(delete_account),
+        )
         //
         .route("/records", get(list_records).post(create_record))
-        .route("/records/{id}", put(update_record).delete(delete_record))
+        .route(
+            "/records/{id}",
+            get(get_record).put(update_record).delete(delete_record),
+        )
+        //
+        .route(
+            "/recurring-records",
+            get(list_recurring_records).post(create_recurring_record),
+        )
+        .route(
+            "/recurring-records/{id}",
+            put(update_recurring_record).delete(delete_recurring_record),
+        )
+        .route(
+            "/recurring-records/{id}/preview",
+            get(preview_recurring_record),
+        )
         //
         .route("/categories", get(list_categories).post(create_category))
         .route(
             "/categories/{id}",
-            put(update_category).delete(delete_category),
+            get(get_category)
+                .put(update_category)
+                .delete(delete_category),
+        )
+        .route("/categories/tree", get(category_tree))
+        .route("/categories/{id}/tree", get(category_subtree))
+        .route("/categories/{id}/parent", put(move_category))
+        //
+        .route("/tags", get(list_tags).post(create_tag))
+        .route("/tags/{id}", put(update_tag).delete(delete_tag))
+        .route("/tags/{id}/merge", post(merge_tag))
+        //
+        .route("/trash", delete(empty_trash))
+        .route("/trash/records", get(list_deleted_records))
+        .route("/trash/accounts", get(list_deleted_accounts))
+        .route("/trash/categories", get(list_deleted_categories))
+        .route("/trash/records/{id}/restore", post(restore_record))
+        .route("/trash/accounts/{id}/restore", post(restore_account))
+        .route("/trash/categories/{id}/restore", post(restore_category))
+        //
+        .route("/imports/csv", post(import_csv))
+        //
+        .route(
+            "/exchange-rates",
+            get(list_exchange_rates).post(set_exchange_rate),
         )
+        .route("/exchange-rates/{id}", delete(delete_exchange_rate))
+        .route("/exchange-rates/import", post(import_exchange_rates))
+        //
+        .route("/reports/budget", get(budget_report))
+        .route("/reports/tags", get(tag_report))
+        .route("/reports/cash-flow", get(cash_flow))
         //
+        .route("/audit", get(activity_feed))
+        .route("/audit/{entity_type}/{id}", get(entity_history))
+        //
+        .route_layer(middleware::from_fn(require_auth));
+
+    Router::new()
+        .route("/auth/register", post(register))
+        .route("/auth/login", post(login))
+        .merge(openapi::routes())
+        .merge(protected)
         .layer(Extension(tx_svc))
+        .layer(middleware::from_fn(problem_details))
 }
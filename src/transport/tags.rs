@@ -0,0 +1,178 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    http::StatusCode,
+    response::{IntoResponse, Result},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{errors::BudgetServiceError, models},
+    service::{
+        budget::BudgetService,
+        tags::{CreateTagCmd, MergeTagsCmd, RenameTagCmd},
+    },
+    transport::{
+        auth::CurrentUser,
+        extract::{Json, Path, Query},
+        params::{PageInfo, PageParam},
+    },
+};
+
+type State = Extension<Arc<dyn BudgetService>>;
+
+#[derive(Serialize)]
+struct Tag {
+    id: i64,
+    name: String,
+}
+
+impl From<models::Tag> for Tag {
+    fn from(tag: models::Tag) -> Self {
+        Self {
+            id: tag.id,
+            name: tag.name,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ListTagsResponse {
+    data: Vec<Tag>,
+    #[serde(flatten)]
+    page: PageInfo,
+}
+
+impl IntoResponse for ListTagsResponse {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::OK, Json(self)).into_response()
+    }
+}
+
+pub async fn list_tags(
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+    Query(page): Query<PageParam>,
+) -> Result<ListTagsResponse> {
+    let page = page.into_cmd().map_err(BudgetServiceError::from)?;
+    let result = svc.list_tags(user.id, page).await?;
+    let page = PageInfo::from(&result);
+
+    Ok(ListTagsResponse {
+        data: result.items.into_iter().map(Tag::from).collect(),
+        page,
+    })
+}
+
+#[derive(Deserialize)]
+pub struct CreateTagRequest {
+    name: String,
+}
+
+#[derive(Serialize)]
+pub struct CreateTagResponse {
+    data: Tag,
+}
+
+impl IntoResponse for CreateTagResponse {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::CREATED, Json(self)).into_response()
+    }
+}
+
+pub async fn create_tag(
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+    Json(req): Json<CreateTagRequest>,
+) -> Result<CreateTagResponse> {
+    let result = svc
+        .create_tag(user.id, CreateTagCmd { name: req.name })
+        .await?;
+
+    Ok(CreateTagResponse {
+        data: result.into(),
+    })
+}
+
+#[derive(Deserialize)]
+pub struct UpdateTagRequest {
+    name: String,
+}
+
+#[derive(Serialize)]
+pub struct UpdateTagResponse {
+    data: Tag,
+}
+
+impl IntoResponse for UpdateTagResponse {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::OK, Json(self)).into_response()
+    }
+}
+
+pub async fn update_tag(
+    Path(id): Path<i64>,
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+    Json(req): Json<UpdateTagRequest>,
+) -> Result<UpdateTagResponse> {
+    let result = svc
+        .rename_tag(user.id, RenameTagCmd { id, name: req.name })
+        .await?;
+
+    Ok(UpdateTagResponse {
+        data: result.into(),
+    })
+}
+
+pub async fn delete_tag(
+    Path(id): Path<i64>,
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+) -> impl IntoResponse {
+    let result = svc.delete_tag(user.id, id).await;
+
+    match result {
+        Ok(()) => (StatusCode::OK).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct MergeTagRequest {
+    target_id: i64,
+}
+
+#[derive(Serialize)]
+pub struct MergeTagResponse {
+    data: Tag,
+}
+
+impl IntoResponse for MergeTagResponse {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::OK, Json(self)).into_response()
+    }
+}
+
+/// Merges the tag from the path into `target_id` and returns the target tag.
+pub async fn merge_tag(
+    Path(id): Path<i64>,
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+    Json(req): Json<MergeTagRequest>,
+) -> Result<MergeTagResponse> {
+    let result = svc
+        .merge_tags(
+            user.id,
+            MergeTagsCmd {
+                source_id: id,
+                target_id: req.target_id,
+            },
+        )
+        .await?;
+
+    Ok(MergeTagResponse {
+        data: result.into(),
+    })
+}
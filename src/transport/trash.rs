@@ -0,0 +1,194 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    http::StatusCode,
+    response::{IntoResponse, Result},
+};
+use serde::Serialize;
+use sqlx::types::chrono::{DateTime, Local};
+
+use crate::{
+    domain::{errors::BudgetServiceError, models},
+    service::budget::BudgetService,
+    transport::{
+        accounts::Account,
+        auth::CurrentUser,
+        categories::Category,
+        extract::{Json, Path, Query},
+        params::{PageInfo, PageParam},
+        records::Record,
+    },
+};
+
+type State = Extension<Arc<dyn BudgetService>>;
+
+#[derive(Serialize)]
+struct Deleted<T> {
+    #[serde(flatten)]
+    item: T,
+    deleted_at: DateTime<Local>,
+}
+
+impl<'a, T, M> From<&'a models::Deleted<M>> for Deleted<T>
+where
+    T: From<&'a M>,
+{
+    fn from(deleted: &'a models::Deleted<M>) -> Self {
+        Self {
+            item: T::from(&deleted.item),
+            deleted_at: deleted.deleted_at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ListDeletedResponse<T> {
+    data: Vec<Deleted<T>>,
+    #[serde(flatten)]
+    page: PageInfo,
+}
+
+impl<T: Serialize> IntoResponse for ListDeletedResponse<T> {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::OK, Json(self)).into_response()
+    }
+}
+
+impl<'a, T, M> From<&'a models::Page<models::Deleted<M>>> for ListDeletedResponse<T>
+where
+    T: From<&'a M>,
+{
+    fn from(page: &'a models::Page<models::Deleted<M>>) -> Self {
+        Self {
+            data: page.items.iter().map(Deleted::from).collect(),
+            page: PageInfo::from(page),
+        }
+    }
+}
+
+pub async fn list_deleted_records(
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+    Query(page): Query<PageParam>,
+) -> Result<ListDeletedResponse<Record>> {
+    let page = page.into_cmd().map_err(BudgetServiceError::from)?;
+    let result = svc.list_deleted_records(user.id, page).await?;
+
+    Ok((&result).into())
+}
+
+pub async fn list_deleted_accounts(
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+    Query(page): Query<PageParam>,
+) -> Result<ListDeletedResponse<Account>> {
+    let page = page.into_cmd().map_err(BudgetServiceError::from)?;
+    let result = svc.list_deleted_accounts(user.id, page).await?;
+
+    Ok((&result).into())
+}
+
+pub async fn list_deleted_categories(
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+    Query(page): Query<PageParam>,
+) -> Result<ListDeletedResponse<Category>> {
+    let page = page.into_cmd().map_err(BudgetServiceError::from)?;
+    let result = svc.list_deleted_categories(user.id, page).await?;
+
+    Ok(ListDeletedResponse {
+        data: result
+            .items
+            .iter()
+            .map(|deleted| Deleted {
+                item: Category::new(&deleted.item, &user.reporting_currency),
+                deleted_at: deleted.deleted_at,
+            })
+            .collect(),
+        page: PageInfo::from(&result),
+    })
+}
+
+#[derive(Serialize)]
+pub struct RestoreResponse<T> {
+    data: T,
+}
+
+impl<T: Serialize> IntoResponse for RestoreResponse<T> {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::OK, Json(self)).into_response()
+    }
+}
+
+pub async fn restore_record(
+    Path(id): Path<i64>,
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+) -> Result<RestoreResponse<Record>> {
+    let result = svc.restore_record(user.id, id).await?;
+
+    Ok(RestoreResponse {
+        data: (&result).into(),
+    })
+}
+
+pub async fn restore_account(
+    Path(id): Path<i64>,
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+) -> Result<RestoreResponse<Account>> {
+    let result = svc.restore_account(user.id, id).await?;
+
+    Ok(RestoreResponse {
+        data: (&result).into(),
+    })
+}
+
+pub async fn restore_category(
+    Path(id): Path<i64>,
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+) -> Result<RestoreResponse<Category>> {
+    let result = svc.restore_category(user.id, id).await?;
+
+    Ok(RestoreResponse {
+        data: Category::new(&result, &user.reporting_currency),
+    })
+}
+
+/// Number of items permanently removed from the trash.
+#[derive(Serialize)]
+struct PurgeReport {
+    records: u64,
+    accounts: u64,
+    categories: u64,
+}
+
+#[derive(Serialize)]
+pub struct EmptyTrashResponse {
+    data: PurgeReport,
+}
+
+impl IntoResponse for EmptyTrashResponse {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::OK, Json(self)).into_response()
+    }
+}
+
+/// Permanently removes every item in the trash of the user, deleted items
+/// are otherwise purged once the retention period is over.
+pub async fn empty_trash(
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+) -> Result<EmptyTrashResponse> {
+    let report = svc.empty_trash(user.id).await?;
+
+    Ok(EmptyTrashResponse {
+        data: PurgeReport {
+            records: report.records,
+            accounts: report.accounts,
+            categories: report.categories,
+        },
+    })
+}
@@ -0,0 +1,89 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    http::StatusCode,
+    response::{IntoResponse, Result},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{errors::BudgetServiceError, models},
+    service::{budget::BudgetService, users::UpdateUserCmd},
+    transport::{auth::CurrentUser, extract::Json, params::parse_currency},
+};
+
+type State = Extension<Arc<dyn BudgetService>>;
+
+#[derive(Serialize)]
+pub struct User {
+    id: i64,
+    username: String,
+    reporting_currency: String,
+}
+
+impl From<&models::User> for User {
+    fn from(user: &models::User) -> Self {
+        Self {
+            id: user.id,
+            username: user.username.clone(),
+            reporting_currency: user.reporting_currency.to_string(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct GetUserResponse {
+    data: User,
+}
+
+impl IntoResponse for GetUserResponse {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::OK, Json(self)).into_response()
+    }
+}
+
+pub async fn get_current_user(CurrentUser(user): CurrentUser) -> Result<GetUserResponse> {
+    Ok(GetUserResponse {
+        data: User::from(&user),
+    })
+}
+
+#[derive(Deserialize)]
+pub struct UpdateUserRequest {
+    username: String,
+    /// ISO 4217 code reports are converted to, unchanged when omitted.
+    /// Budgets are kept in it, so it cannot change while any are set.
+    reporting_currency: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct UpdateUserResponse {
+    data: User,
+}
+
+impl IntoResponse for UpdateUserResponse {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::OK, Json(self)).into_response()
+    }
+}
+
+pub async fn update_current_user(
+    Extension(svc): State,
+    CurrentUser(user): CurrentUser,
+    Json(req): Json<UpdateUserRequest>,
+) -> Result<UpdateUserResponse> {
+    let reporting_currency =
+        parse_currency(req.reporting_currency.as_deref()).map_err(BudgetServiceError::from)?;
+    let result = svc
+        .update_user(UpdateUserCmd {
+            id: user.id,
+            username: req.username,
+            reporting_currency,
+        })
+        .await?;
+
+    Ok(UpdateUserResponse {
+        data: User::from(&result),
+    })
+}